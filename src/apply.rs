@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::fix::fix_workspace_dependency;
+use crate::rules::{UNUSED_WORKSPACE_DEPENDENCIES, find_rule, hash};
+
+/// The parts of a report written by `--format json` needed to apply its fixes
+#[derive(Debug, Deserialize)]
+struct Report {
+    findings: Vec<ReportFinding>,
+}
+
+/// A finding of the report
+#[derive(Debug, Deserialize)]
+struct ReportFinding {
+    rule: String,
+    manifest_path: PathBuf,
+    dependency: Option<String>,
+    fix: Option<ReportFix>,
+}
+
+/// The automatic fix recorded for a finding
+#[derive(Debug, Deserialize)]
+struct ReportFix {
+    manifest_hash: String,
+}
+
+/// Applies the automatic fixes recorded in a report written by `--format json`
+///
+/// The manifests are first checked against the content hashes recorded along with the fixes, and nothing
+/// is rewritten when any of them changed since the report was written. Relative manifest paths are
+/// resolved against the current directory, like they were when the report was written. Like with --fix,
+/// the fixes of rule ENDF029 removing entries are only applied when `remove_unused` is set. A description
+/// of every fix applied is returned.
+pub fn apply_report(report_path: &Path, remove_unused: bool) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(report_path).with_context(|| format!("Failed to read {}", report_path.display()))?;
+    let report: Report = serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", report_path.display()))?;
+
+    let mut by_manifest: BTreeMap<&Path, Vec<(&ReportFinding, &ReportFix)>> = BTreeMap::new();
+    for finding in &report.findings {
+        if let Some(fix) = &finding.fix
+            && (remove_unused || !finding.rule.eq_ignore_ascii_case(UNUSED_WORKSPACE_DEPENDENCIES.id))
+        {
+            by_manifest.entry(&finding.manifest_path).or_default().push((finding, fix));
+        }
+    }
+
+    let mut manifests = Vec::new();
+    for (path, fixes) in by_manifest {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let content_hash = hash(&content);
+        if fixes.iter().any(|(_, fix)| fix.manifest_hash != content_hash) {
+            bail!(
+                "{} changed since {} was written, run the check again to get up-to-date fixes",
+                path.display(),
+                report_path.display()
+            );
+        }
+
+        manifests.push((path, content, fixes));
+    }
+
+    let mut applied = Vec::new();
+    for (path, content, fixes) in manifests {
+        let mut fixed = content.clone();
+        for (finding, _) in fixes {
+            let rule = find_rule(&finding.rule)?;
+            let name = finding
+                .dependency
+                .as_deref()
+                .with_context(|| format!("A fix of rule {} in {} names no dependency", rule.id, path.display()))?;
+            let Some((description, result)) = fix_workspace_dependency(rule, &fixed, name)? else {
+                bail!("Rule {} has no automatic fix", rule.id);
+            };
+
+            fixed = result;
+            applied.push(format!("{}: '{name}': {description}", path.display()));
+        }
+
+        if fixed != content {
+            std::fs::write(path, fixed).with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        let content = "[workspace.dependencies]\nlog = \"0.4\"\nserde = \"1.0\"\n";
+        std::fs::write(&manifest_path, content).unwrap();
+
+        let report_path = dir.path().join("report.json");
+        let finding = |rule: &str, dependency: &str, manifest_hash: &str| {
+            serde_json::json!({
+                "rule": rule,
+                "manifest_path": manifest_path,
+                "dependency": dependency,
+                "fix": { "description": "", "manifest_hash": manifest_hash },
+            })
+        };
+        let write_report = |findings: Vec<serde_json::Value>| {
+            std::fs::write(&report_path, serde_json::json!({ "findings": findings }).to_string()).unwrap();
+        };
+
+        write_report(vec![finding("ENDF001", "serde", "0000000000000000")]);
+        let err = apply_report(&report_path, true).unwrap_err();
+        assert!(err.to_string().contains("changed since"));
+        assert_eq!(std::fs::read_to_string(&manifest_path).unwrap(), content);
+
+        write_report(vec![
+            finding("ENDF001", "serde", &hash(content)),
+            finding("ENDF029", "log", &hash(content)),
+            serde_json::json!({ "rule": "ENDF006", "manifest_path": manifest_path, "dependency": null, "fix": null }),
+        ]);
+        let applied = apply_report(&report_path, false).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert!(applied[0].ends_with("'serde': Set default-features = false"));
+        let fixed = "[workspace.dependencies]\nlog = \"0.4\"\nserde = { version = \"1.0\", default-features = false }\n";
+        assert_eq!(std::fs::read_to_string(&manifest_path).unwrap(), fixed);
+
+        write_report(vec![finding("ENDF029", "log", &hash(fixed))]);
+        let applied = apply_report(&report_path, true).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&manifest_path).unwrap(),
+            "[workspace.dependencies]\nserde = { version = \"1.0\", default-features = false }\n"
+        );
+    }
+}
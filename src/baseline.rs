@@ -0,0 +1,152 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::rules::Finding;
+
+/// A violation recorded in a baseline file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// Fingerprint identifying the violation across runs, see `Finding::fingerprint`
+    pub fingerprint: String,
+
+    /// ID of the rule that reported the violation
+    pub rule: String,
+
+    /// Path to the manifest the violation was found in, relative to the baseline file's directory
+    pub manifest_path: PathBuf,
+
+    /// Description of the violation
+    pub message: String,
+}
+
+/// The content of a baseline file
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BaselineFile {
+    violations: BTreeSet<BaselineEntry>,
+}
+
+/// How the violations of a run compare to the ones recorded in a baseline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BaselineComparison {
+    /// Number of recorded violations no longer found
+    pub fixed: usize,
+
+    /// Number of violations found that weren't recorded
+    pub new: usize,
+
+    /// Number of recorded violations still found
+    pub remaining: usize,
+}
+
+/// Records the given violations in a baseline file
+///
+/// # Returns
+///
+/// The number of violations recorded.
+pub fn write_baseline(path: &Path, violations: &[&Finding]) -> Result<usize> {
+    let file = BaselineFile {
+        violations: baseline_entries(path, violations),
+    };
+
+    let content = serde_json::to_string_pretty(&file).context("Failed to serialize the baseline")?;
+    std::fs::write(path, format!("{content}\n")).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(file.violations.len())
+}
+
+/// Compares the given violations to the ones recorded in a baseline file
+///
+/// Violations are matched by fingerprint, so rewording a message or moving the baseline along with the
+/// workspace doesn't turn recorded violations into new ones.
+pub fn compare_to_baseline(path: &Path, violations: &[&Finding]) -> Result<BaselineComparison> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let recorded: BaselineFile = serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    let recorded: BTreeSet<_> = recorded.violations.into_iter().map(|entry| entry.fingerprint).collect();
+    let current: BTreeSet<_> = baseline_entries(path, violations)
+        .into_iter()
+        .map(|entry| entry.fingerprint)
+        .collect();
+
+    Ok(BaselineComparison {
+        fixed: recorded.difference(&current).count(),
+        new: current.difference(&recorded).count(),
+        remaining: current.intersection(&recorded).count(),
+    })
+}
+
+/// Turns violations into baseline entries, with manifest paths relative to the baseline file's directory
+fn baseline_entries(path: &Path, violations: &[&Finding]) -> BTreeSet<BaselineEntry> {
+    let dir = std::path::absolute(path.parent().unwrap_or_else(|| Path::new(""))).unwrap_or_default();
+    violations
+        .iter()
+        .map(|finding| {
+            let manifest_path = std::path::absolute(&finding.manifest_path).unwrap_or_else(|_| finding.manifest_path.clone());
+            BaselineEntry {
+                fingerprint: finding.fingerprint(&dir),
+                rule: finding.rule.id.to_string(),
+                manifest_path: manifest_path
+                    .strip_prefix(&dir)
+                    .map_or_else(|_| manifest_path.clone(), Path::to_path_buf),
+                message: finding.message.clone(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, finding};
+    use crate::rules::{DEFAULT_FEATURES, Severity};
+
+    #[test]
+    fn test_compare_to_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        let manifest_path = dir.path().join("member/Cargo.toml");
+
+        let serde = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            &manifest_path,
+            entry("serde", "'serde': uses simple version string"),
+        );
+        let tokio = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            &manifest_path,
+            entry("tokio", "'tokio': uses simple version string"),
+        );
+        let clap = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            &manifest_path,
+            entry("clap", "'clap': uses simple version string"),
+        );
+        let reworded = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            &manifest_path,
+            entry("tokio", "'tokio': uses a simple version string"),
+        );
+        assert_eq!(write_baseline(&path, &[&serde, &tokio]).unwrap(), 2);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains(r#""manifest_path": "member/Cargo.toml""#), "{content}");
+
+        assert_eq!(
+            compare_to_baseline(&path, &[&reworded, &clap]).unwrap(),
+            BaselineComparison {
+                fixed: 1,
+                new: 1,
+                remaining: 1,
+            }
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        let _ = compare_to_baseline(&path, &[]).unwrap_err();
+    }
+}
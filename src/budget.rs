@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use glob::Pattern;
+
+/// Usage of a single violation budget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetStatus {
+    /// The path or glob pattern the budget applies to
+    pub pattern: String,
+
+    /// Maximum number of violations allowed
+    pub allowed: usize,
+
+    /// Number of violations found in the directories governed by the budget
+    pub used: usize,
+}
+
+impl BudgetStatus {
+    /// Returns whether more violations were found than the budget allows
+    pub const fn exceeded(&self) -> bool {
+        self.used > self.allowed
+    }
+}
+
+/// Attributes violations to the per-directory budgets they fall under
+///
+/// Each violation is identified by the path of the manifest it was found in. Its directory, relative
+/// to the workspace root, is matched against every budget pattern, along with all of its parent
+/// directories, so a budget for `services` also covers `services/payments`. When several patterns
+/// match, the longest one is used.
+///
+/// # Returns
+///
+/// The status of every budget, and the number of violations not covered by any budget.
+pub fn evaluate_budgets(
+    budgets: &BTreeMap<String, usize>,
+    workspace_root: &Path,
+    violations: &[&Path],
+) -> Result<(Vec<BudgetStatus>, usize)> {
+    let patterns = budgets
+        .keys()
+        .map(|key| Pattern::new(key).map(|pattern| (key, pattern)))
+        .collect::<Result<Vec<_>, _>>()
+        .context("Invalid max-violations pattern")?;
+
+    let mut used: BTreeMap<&String, usize> = BTreeMap::new();
+    let mut uncovered = 0;
+    for manifest_path in violations {
+        let dir = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+        let dir = dir.strip_prefix(workspace_root).unwrap_or(dir);
+
+        let budget = patterns
+            .iter()
+            .filter(|(_, pattern)| dir.ancestors().any(|ancestor| pattern.matches_path(ancestor)))
+            .max_by_key(|(key, _)| key.len());
+
+        match budget {
+            Some((key, _)) => *used.entry(key).or_default() += 1,
+            None => uncovered += 1,
+        }
+    }
+
+    let statuses = budgets
+        .iter()
+        .map(|(pattern, allowed)| BudgetStatus {
+            pattern: pattern.clone(),
+            allowed: *allowed,
+            used: used.get(pattern).copied().unwrap_or_default(),
+        })
+        .collect();
+
+    Ok((statuses, uncovered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budgets(entries: &[(&str, usize)]) -> BTreeMap<String, usize> {
+        entries.iter().map(|(key, value)| ((*key).to_string(), *value)).collect()
+    }
+
+    #[test]
+    fn test_evaluate_budgets_most_specific_wins() {
+        let budgets = budgets(&[("services/payments", 0), ("*", 10)]);
+        let violations = [
+            Path::new("ws/services/payments/Cargo.toml"),
+            Path::new("ws/services/payments/sub/Cargo.toml"),
+            Path::new("ws/tools/Cargo.toml"),
+            Path::new("ws/Cargo.toml"),
+        ];
+
+        let (statuses, uncovered) = evaluate_budgets(&budgets, Path::new("ws"), &violations).unwrap();
+        assert_eq!(uncovered, 0);
+        assert_eq!(
+            statuses,
+            vec![
+                BudgetStatus {
+                    pattern: "*".to_string(),
+                    allowed: 10,
+                    used: 2,
+                },
+                BudgetStatus {
+                    pattern: "services/payments".to_string(),
+                    allowed: 0,
+                    used: 2,
+                },
+            ]
+        );
+        assert!(!statuses[0].exceeded());
+        assert!(statuses[1].exceeded());
+    }
+
+    #[test]
+    fn test_evaluate_budgets_uncovered_and_globs() {
+        let budgets = budgets(&[("crates/*", 1)]);
+        let violations = [Path::new("crates/a/Cargo.toml"), Path::new("Cargo.toml")];
+
+        let (statuses, uncovered) = evaluate_budgets(&budgets, Path::new(""), &violations).unwrap();
+        assert_eq!(uncovered, 1);
+        assert_eq!(statuses[0].used, 1);
+        assert!(!statuses[0].exceeded());
+    }
+
+    #[test]
+    fn test_evaluate_budgets_invalid_pattern() {
+        let budgets = budgets(&[("[", 1)]);
+        let err = evaluate_budgets(&budgets, Path::new(""), &[]).unwrap_err();
+        assert!(err.to_string().contains("Invalid max-violations pattern"));
+    }
+}
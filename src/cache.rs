@@ -0,0 +1,250 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::daemon::write_metric;
+
+/// Keeps the content of manifests and the output of `cargo metadata` in memory across checks
+///
+/// Entries are revalidated against the modification time and size of the files they were read from on
+/// every read, so edits made between two checks are always picked up.
+#[derive(Debug, Default)]
+pub struct ManifestCache {
+    entries: Mutex<HashMap<PathBuf, CachedManifest>>,
+    metadata: Mutex<HashMap<(PathBuf, bool), CachedMetadata>>,
+    manifest_hits: Counter,
+    metadata_hits: Counter,
+}
+
+/// Counts how often an entry of the cache could be reused
+#[derive(Debug, Default)]
+struct Counter {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Counter {
+    fn record(&self, hit: bool) {
+        let count = if hit { &self.hits } else { &self.misses };
+        let _ = count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Writes the hits and misses as Prometheus counters whose names start with the given prefix
+    fn write_metrics(&self, out: &mut String, prefix: &str, what: &str) {
+        let hits = self.hits.load(Ordering::Relaxed);
+        write_metric(
+            out,
+            &format!("{prefix}_hits_total"),
+            "counter",
+            &format!("{what} reused from the cache"),
+            hits,
+        );
+        let misses = self.misses.load(Ordering::Relaxed);
+        write_metric(
+            out,
+            &format!("{prefix}_misses_total"),
+            "counter",
+            &format!("{what} missing from the cache"),
+            misses,
+        );
+    }
+}
+
+#[derive(Debug)]
+struct CachedManifest {
+    stamp: Stamp,
+    content: String,
+}
+
+#[derive(Debug)]
+struct CachedMetadata {
+    /// The files the output depends on, with their stamps once it was produced
+    inputs: Vec<(PathBuf, Option<Stamp>)>,
+    output: Value,
+}
+
+/// Modification time and size of a file, telling whether it changed since it was last read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl Stamp {
+    /// Returns the stamp of the file at the given path, or `None` when it can't be read
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+        })
+    }
+}
+
+impl ManifestCache {
+    /// Returns the content of the file at the given path, reading it from disk only when it changed
+    pub fn read(&self, path: &Path) -> Result<String> {
+        let stamp = Stamp::of(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        if let Some(entry) = lock(&self.entries).get(path)
+            && entry.stamp == stamp
+        {
+            self.manifest_hits.record(true);
+            return Ok(entry.content.clone());
+        }
+
+        self.manifest_hits.record(false);
+
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let _ = lock(&self.entries).insert(
+            path.to_path_buf(),
+            CachedManifest {
+                stamp,
+                content: content.clone(),
+            },
+        );
+
+        Ok(content)
+    }
+
+    /// Returns the output of `cargo metadata` for a workspace, calling `run` only when a file it depends
+    /// on changed
+    ///
+    /// The output depends on the workspace's Cargo.lock and on the manifests of the local packages it
+    /// describes, whose stamps are recorded once `run` returns, since cargo may update Cargo.lock itself.
+    pub fn metadata(&self, manifest_path: &Path, no_deps: bool, run: impl FnOnce() -> Result<Value>) -> Result<Value> {
+        let key = (manifest_path.to_path_buf(), no_deps);
+        if let Some(entry) = lock(&self.metadata).get(&key)
+            && entry.inputs.iter().all(|(path, stamp)| Stamp::of(path) == *stamp)
+        {
+            self.metadata_hits.record(true);
+            return Ok(entry.output.clone());
+        }
+
+        self.metadata_hits.record(false);
+
+        let output = run()?;
+        let inputs = metadata_inputs(manifest_path, &output)
+            .into_iter()
+            .map(|path| {
+                let stamp = Stamp::of(&path);
+                (path, stamp)
+            })
+            .collect();
+        let _ = lock(&self.metadata).insert(
+            key,
+            CachedMetadata {
+                inputs,
+                output: output.clone(),
+            },
+        );
+
+        Ok(output)
+    }
+
+    /// Writes how often manifests and `cargo metadata` output were reused, in the Prometheus text format
+    pub fn write_metrics(&self, out: &mut String) {
+        self.manifest_hits
+            .write_metrics(out, "endf_daemon_manifest_cache", "Manifest reads");
+        self.metadata_hits
+            .write_metrics(out, "endf_daemon_metadata_cache", "Runs of cargo metadata");
+    }
+}
+
+/// Returns the files the output of `cargo metadata` for a workspace depends on
+fn metadata_inputs(manifest_path: &Path, output: &Value) -> Vec<PathBuf> {
+    let mut inputs = vec![manifest_path.to_path_buf(), manifest_path.with_file_name("Cargo.lock")];
+    let packages = output.get("packages").and_then(Value::as_array).into_iter().flatten();
+    inputs.extend(
+        packages
+            .filter(|package| package.get("source").is_none_or(Value::is_null))
+            .filter_map(|package| package.get("manifest_path").and_then(Value::as_str))
+            .map(PathBuf::from),
+    );
+    inputs
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // The maps are always left consistent, so a panic while one was locked doesn't invalidate it
+    mutex.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Reads a manifest through the cache when one is available, or straight from disk otherwise
+pub fn read_manifest(path: &Path, cache: Option<&ManifestCache>) -> Result<String> {
+    cache.map_or_else(
+        || std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display())),
+        |cache| cache.read(path),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_picks_up_changes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        std::fs::write(&path, "[workspace]\n").unwrap();
+
+        let cache = ManifestCache::default();
+        assert_eq!(cache.read(&path).unwrap(), "[workspace]\n");
+        assert_eq!(cache.read(&path).unwrap(), "[workspace]\n");
+
+        std::fs::write(&path, "[workspace]\nmembers = []\n").unwrap();
+        assert_eq!(cache.read(&path).unwrap(), "[workspace]\nmembers = []\n");
+
+        let _ = cache.read(&dir.path().join("missing.toml")).unwrap_err();
+    }
+
+    #[test]
+    fn test_metadata_reruns_on_changes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        let member_path = dir.path().join("member/Cargo.toml");
+        std::fs::create_dir(dir.path().join("member")).unwrap();
+        std::fs::write(&manifest_path, "[workspace]\n").unwrap();
+        std::fs::write(&member_path, "[package]\n").unwrap();
+
+        let cache = ManifestCache::default();
+        let runs = core::cell::Cell::new(0);
+        let output = serde_json::json!({ "packages": [{ "source": null, "manifest_path": member_path }] });
+        let metadata = || {
+            cache
+                .metadata(&manifest_path, false, || {
+                    runs.set(runs.get() + 1);
+                    Ok(output.clone())
+                })
+                .unwrap()
+        };
+
+        assert_eq!(metadata(), output);
+        assert_eq!(metadata(), output);
+        assert_eq!(runs.get(), 1);
+
+        let mut metrics = String::new();
+        cache.write_metrics(&mut metrics);
+        assert!(metrics.contains("\nendf_daemon_metadata_cache_hits_total 1\n"));
+        assert!(metrics.contains("\nendf_daemon_metadata_cache_misses_total 1\n"));
+
+        std::fs::write(&member_path, "[package]\nname = \"member\"\n").unwrap();
+        let _ = metadata();
+        assert_eq!(runs.get(), 2);
+
+        std::fs::write(dir.path().join("Cargo.lock"), "version = 4\n").unwrap();
+        let _ = metadata();
+        assert_eq!(runs.get(), 3);
+    }
+
+    #[test]
+    fn test_read_manifest_without_cache() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        std::fs::write(&path, "[workspace]\n").unwrap();
+        assert_eq!(read_manifest(&path, None).unwrap(), "[workspace]\n");
+    }
+}
@@ -0,0 +1,918 @@
+use core::time::Duration;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result, bail};
+
+use crate::budget::{BudgetStatus, evaluate_budgets};
+use crate::cache::{ManifestCache, read_manifest};
+use crate::config::{Config, Exception};
+use crate::deny::load_cargo_deny_exceptions;
+use crate::features::{
+    find_denied_features, find_duplicate_features, find_excessive_default_features, find_feature_budget_overruns,
+    find_missing_required_features, find_no_std_violations, find_umbrella_features, find_ungated_optional_dependencies, workspace_features,
+};
+use crate::fix::{
+    dedup_features, inherit_from_workspace, remove_dependency, rename_legacy_default_features, set_default_features_false,
+    sort_dependencies,
+};
+use crate::git::{RevisionExports, toplevel};
+use crate::lockfile::{find_duplicate_versions, find_leaked_defaults, find_redundant_default_features};
+use crate::members::{Member, load_members};
+use crate::package::{check_packaged_members, find_unversioned_path_dependencies};
+use crate::path_deps::{find_path_default_consumers, follow_path_dependencies};
+use crate::plugins::{ManifestModel, run_plugins};
+use crate::rules::{
+    BUILD_DEPENDENCY_MISMATCH, CUSTOM_RULES, DECENTRALIZED_DEPENDENCIES, DEFAULT_FEATURE_REFERENCES, DEFAULT_FEATURES, DEFAULT_IN_FEATURES,
+    DENIED_FEATURES, DUPLICATE_FEATURES, DUPLICATE_VERSIONS, FEATURE_BUDGET, Finding, GIT_DEPENDENCIES, INCONSISTENT_ALIASES,
+    LEAKED_DEFAULTS, LEGACY_DEFAULT_FEATURES, MEMBER_DEFAULT_FEATURES, MEMBER_DEFAULT_OVERRIDES, NO_STD, OPTIONAL_DEPENDENCY_GATING,
+    PACKAGED_MANIFESTS, PATH_DEFAULTS, PATH_DEPENDENCIES, Preset, REDUNDANT_DEFAULT_FEATURES, REQUIRED_FEATURES, Rule, RuleSet,
+    SORTED_DEPENDENCIES, Severity, UMBRELLA_FEATURES, UNUSED_WORKSPACE_DEPENDENCIES, UNVERSIONED_PATH_DEPENDENCIES, VERSION_MISMATCH,
+    VERSION_PRECISION, Violation, WORKSPACE_BYPASS, WORKSPACE_INHERITANCE,
+};
+use crate::scripts::run_scripts;
+use crate::spans::KeyLocations;
+use crate::suppressions::find_suppressions;
+use crate::validation::{
+    ExceptionPattern, compile_exceptions, find_build_dependency_mismatches, find_decentralized_dependencies,
+    find_default_feature_overrides, find_default_feature_references, find_default_feature_requests, find_git_dependencies,
+    find_inconsistent_aliases, find_legacy_default_features, find_obsolete_exceptions, find_uninherited_dependencies,
+    find_unsorted_dependencies, find_unused_workspace_dependencies, find_workspace_bypasses, fixable_workspace_dependencies,
+    resolve_versioned_exceptions, section_label, validate_workspace_dependencies,
+};
+use crate::vendor::audit_vendor_dir;
+use crate::versions::{find_imprecise_versions, find_version_mismatches};
+
+/// Signature of the rules that only need a member's manifest and the exceptions
+type MemberCheck = fn(&str, &[ExceptionPattern]) -> Result<Vec<Violation>>;
+
+/// Rules run against the manifest of every member, in addition to the ones needing the workspace's manifest
+const MEMBER_RULES: [(&Rule, MemberCheck); 5] = [
+    (&DEFAULT_IN_FEATURES, find_default_feature_requests),
+    (&DEFAULT_FEATURE_REFERENCES, find_default_feature_references),
+    (&MEMBER_DEFAULT_OVERRIDES, find_default_feature_overrides),
+    (&DECENTRALIZED_DEPENDENCIES, find_decentralized_dependencies),
+    (&OPTIONAL_DEPENDENCY_GATING, find_ungated_optional_dependencies),
+];
+
+/// Settings controlling how a workspace is checked
+#[derive(Debug)]
+#[expect(clippy::struct_excessive_bools, reason = "Command-line flags are naturally bools")]
+pub struct CheckOptions<'a> {
+    /// Dependencies excluded from every rule
+    pub exceptions: &'a [String],
+
+    /// Whether the exceptions replace the ones of the configuration, instead of adding to them
+    pub replace_exceptions: bool,
+
+    /// Whether every exception of the configuration must give a reason
+    pub require_exception_reasons: bool,
+
+    /// Rule overrides given on the command line, applied on top of the configuration file's
+    pub rules: &'a RuleSet,
+
+    /// Preset given on the command line, replacing the configured one
+    pub preset: Option<Preset>,
+
+    /// Directory produced by `cargo vendor` to audit
+    pub vendor_dir: Option<&'a Path>,
+
+    /// Point in time after which the remaining member checks are skipped
+    pub deadline: Option<Instant>,
+
+    /// Configuration file to use instead of the one found next to each workspace manifest
+    pub config_path: Option<&'a Path>,
+
+    /// Cache of manifest contents kept warm across checks when running as a daemon
+    pub cache: Option<&'a ManifestCache>,
+
+    /// Exports of the git revision whose violations are considered pre-existing and left out of the report
+    pub changed_since: Option<&'a RevisionExports>,
+
+    /// Whether every member is held to the `no_std` rules, on top of those listed in the configuration
+    pub no_std: bool,
+
+    /// Whether the check is run for the daemon, which neither writes files nor runs code supplied by
+    /// the workspace, so custom rules and packaging are refused
+    pub sandboxed: bool,
+}
+
+/// Outcome of checking a single workspace
+#[derive(Debug, Default)]
+pub struct WorkspaceReport {
+    /// Path to the workspace's Cargo.toml
+    pub manifest_path: PathBuf,
+
+    /// Problems reported by the enabled rules
+    pub findings: Vec<Finding>,
+
+    /// The rules that ran, with their severities
+    pub rules: RuleSet,
+
+    /// Names of all dependencies found in `[workspace.dependencies]`, along with the packages they rename
+    pub found_deps: Vec<String>,
+
+    /// Exceptions of the default-features check covering only dependencies that already pass it
+    pub obsolete_exceptions: Vec<String>,
+
+    /// Exceptions of the configuration giving a reason, paired with the rule ID or name they're keyed by
+    pub exception_reasons: Vec<(String, Exception)>,
+
+    /// Exceptions of the configuration past their expiry date, paired with the rule ID or name they're keyed by
+    pub expired_exceptions: Vec<(String, Exception)>,
+
+    /// Vendored crates consumed with default features, when a vendor directory was audited
+    pub vendor_audit: Option<Vec<(String, Vec<String>)>>,
+
+    /// Whether the deadline passed before all members were checked
+    pub truncated: bool,
+
+    /// Usage of each violation budget configured through `max-violations`
+    pub budgets: Vec<BudgetStatus>,
+
+    /// Number of violations not covered by any configured budget
+    pub unbudgeted: usize,
+
+    /// Time spent checking the workspace, as measured by the caller
+    pub duration: Duration,
+}
+
+impl WorkspaceReport {
+    /// Returns the findings with error severity, which fail the check
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|finding| finding.severity == Severity::Error)
+    }
+
+    /// Returns the findings with warning severity
+    pub fn warnings(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|finding| finding.severity == Severity::Warning)
+    }
+
+    /// Returns the total number of findings with error severity
+    pub fn error_count(&self) -> usize {
+        self.errors().count()
+    }
+}
+
+/// Collects the findings of the enabled rules
+struct Findings<'a> {
+    rules: &'a RuleSet,
+    cache: Option<&'a ManifestCache>,
+    locations: BTreeMap<PathBuf, KeyLocations>,
+    collected: Vec<Finding>,
+}
+
+impl Findings<'_> {
+    /// Records one finding per violation, unless the rule is disabled
+    ///
+    /// Each finding is located within its manifest, whose keys are indexed once.
+    fn add(&mut self, rule: &'static Rule, manifest_path: &Path, violations: impl IntoIterator<Item = impl Into<Violation>>) {
+        let Some(severity) = self.rules.severity(rule) else {
+            return;
+        };
+
+        let cache = self.cache;
+        let locations = self.locations.entry(manifest_path.to_path_buf()).or_insert_with(|| {
+            read_manifest(manifest_path, cache)
+                .map(|content| KeyLocations::new(&content))
+                .unwrap_or_default()
+        });
+        for violation in violations {
+            let violation = violation.into();
+            let location = locations.locate(&violation.subject);
+            self.collected
+                .push(Finding::new(rule, severity, manifest_path, violation, location));
+        }
+    }
+}
+
+/// Dependencies excluded from the rules, either from all of them or from specific ones
+///
+/// The exceptions are compiled once, when the workspace's check starts.
+#[derive(Debug)]
+struct Exceptions {
+    global: Vec<ExceptionPattern>,
+    per_rule: BTreeMap<&'static str, Vec<ExceptionPattern>>,
+    per_member: BTreeMap<(&'static str, String), Vec<ExceptionPattern>>,
+    suppressed: BTreeMap<&'static str, Vec<ExceptionPattern>>,
+    reasons: Vec<(String, Exception)>,
+    expired: Vec<(String, Exception)>,
+}
+
+impl Exceptions {
+    /// Combines the exceptions given on the command line with the ones of the configuration
+    ///
+    /// Exceptions constrained to a version requirement are resolved against the workspace's manifest,
+    /// whose suppression comments are read as well. The allowlists of the cargo-deny configuration
+    /// named by the configuration, relative to the manifest's directory, count as configured exceptions.
+    fn new(config: &Config, options: &CheckOptions<'_>, manifest_path: &Path, content: &str) -> Result<Self> {
+        let (mut per_rule, per_member, reasons, expired) = if options.replace_exceptions {
+            (BTreeMap::new(), BTreeMap::new(), Vec::new(), Vec::new())
+        } else {
+            if options.require_exception_reasons {
+                config.require_exception_reasons()?;
+            }
+
+            (
+                config.rule_exceptions()?,
+                config.member_exceptions()?,
+                config.exception_reasons(),
+                config.expired_exceptions(),
+            )
+        };
+
+        if let Some(deny_config) = config.cargo_deny.as_ref().filter(|_| !options.replace_exceptions) {
+            let path = manifest_path.parent().unwrap_or_else(|| Path::new("")).join(deny_config);
+            for (rule, exceptions) in load_cargo_deny_exceptions(&path)? {
+                per_rule.entry(rule).or_default().extend(exceptions);
+            }
+        }
+
+        Ok(Self {
+            global: compile_exceptions(&resolve_versioned_exceptions(options.exceptions, content)),
+            per_rule: per_rule
+                .into_iter()
+                .map(|(rule, exceptions)| (rule, compile_exceptions(&resolve_versioned_exceptions(&exceptions, content))))
+                .collect(),
+            per_member: per_member
+                .into_iter()
+                .map(|(key, exceptions)| (key, compile_exceptions(&resolve_versioned_exceptions(&exceptions, content))))
+                .collect(),
+            suppressed: compile_suppressions(find_suppressions(content)?),
+            reasons,
+            expired,
+        })
+    }
+
+    /// Returns the dependencies excluded from the given rule in the workspace's root manifest
+    fn for_rule(&self, rule: &Rule) -> Vec<ExceptionPattern> {
+        self.global
+            .iter()
+            .chain(self.per_rule.get(rule.id).into_iter().flatten())
+            .chain(self.suppressed.get(rule.id).into_iter().flatten())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the dependencies excluded from the given rule in a member's manifest
+    ///
+    /// `suppressed` holds the suppression comments of the member's manifest, keyed by rule ID.
+    fn for_member(&self, rule: &Rule, member: &str, suppressed: &BTreeMap<&'static str, Vec<ExceptionPattern>>) -> Vec<ExceptionPattern> {
+        self.global
+            .iter()
+            .chain(self.per_rule.get(rule.id).into_iter().flatten())
+            .chain(self.per_member.get(&(rule.id, member.to_string())).into_iter().flatten())
+            .chain(suppressed.get(rule.id).into_iter().flatten())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Compiles the dependencies named by the suppression comments of a manifest, keyed by rule ID
+fn compile_suppressions(suppressed: BTreeMap<&'static str, Vec<String>>) -> BTreeMap<&'static str, Vec<ExceptionPattern>> {
+    suppressed
+        .into_iter()
+        .map(|(rule, names)| (rule, compile_exceptions(&names)))
+        .collect()
+}
+
+/// Runs every enabled rule against the workspace whose root manifest is at the given path
+pub fn check_workspace(manifest_path: &Path, options: &CheckOptions<'_>) -> Result<WorkspaceReport> {
+    let content = read_manifest(manifest_path, options.cache)?;
+    let config = Config::discover(manifest_path, options.config_path)?;
+    let mut rules = config.rules(options.preset)?;
+    rules.merge(options.rules);
+    let exceptions = Exceptions::new(&config, options, manifest_path, &content)?;
+
+    let mut findings = Findings {
+        rules: &rules,
+        cache: options.cache,
+        locations: BTreeMap::new(),
+        collected: Vec::new(),
+    };
+
+    let (errors, found_deps) = validate_workspace_dependencies(&content, &exceptions.for_rule(&DEFAULT_FEATURES))?;
+    findings.add(&DEFAULT_FEATURES, manifest_path, errors);
+
+    if rules.is_enabled(&PATH_DEPENDENCIES) {
+        for (path, errors) in follow_path_dependencies(manifest_path, &content, &exceptions.for_rule(&PATH_DEPENDENCIES))? {
+            findings.add(&PATH_DEPENDENCIES, &path, errors);
+        }
+    }
+
+    let vendor_audit = options
+        .vendor_dir
+        .map(|vendor_dir| audit_vendor_dir(vendor_dir, &content))
+        .transpose()?;
+
+    let members = load_members(manifest_path, &content, options.cache)?;
+
+    // When the root manifest is also a package, it's checked along with the other members
+    if rules.is_enabled(&DEFAULT_IN_FEATURES) && !members.iter().any(|member| member.manifest_path == manifest_path) {
+        findings.add(
+            &DEFAULT_IN_FEATURES,
+            manifest_path,
+            find_default_feature_requests(&content, &exceptions.for_rule(&DEFAULT_IN_FEATURES))?,
+        );
+    }
+
+    if rules.is_enabled(&PACKAGED_MANIFESTS) {
+        check_packaging(&mut findings, manifest_path, &content, &members, &exceptions, !options.sandboxed)?;
+    }
+
+    if rules.is_enabled(&UNVERSIONED_PATH_DEPENDENCIES) {
+        findings.add(
+            &UNVERSIONED_PATH_DEPENDENCIES,
+            manifest_path,
+            find_unversioned_path_dependencies(&content, &members)?,
+        );
+    }
+
+    if rules.is_enabled(&PATH_DEFAULTS) {
+        findings.add(
+            &PATH_DEFAULTS,
+            manifest_path,
+            find_path_default_consumers(manifest_path, &content, &members)?,
+        );
+    }
+
+    check_workspace_table(&mut findings, manifest_path, &content, &config)?;
+
+    check_resolved_graph(&mut findings, manifest_path, &content, &exceptions)?;
+
+    let mut truncated = false;
+    for member in &members {
+        if options.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            truncated = true;
+            break;
+        }
+
+        check_member(&mut findings, member, &content, &config, options, &exceptions)
+            .with_context(|| format!("Failed to validate {}", member.manifest_path.display()))?;
+    }
+
+    if !truncated && rules.is_enabled(&INCONSISTENT_ALIASES) {
+        findings.add(&INCONSISTENT_ALIASES, manifest_path, find_inconsistent_aliases(&members, &content)?);
+    }
+
+    if !truncated && rules.is_enabled(&UNUSED_WORKSPACE_DEPENDENCIES) {
+        let unused = find_unused_workspace_dependencies(&members, &content, &exceptions.for_rule(&UNUSED_WORKSPACE_DEPENDENCIES))?;
+        findings.add(&UNUSED_WORKSPACE_DEPENDENCIES, manifest_path, unused);
+    }
+
+    if rules.is_enabled(&CUSTOM_RULES) {
+        check_custom_rules(&mut findings, manifest_path, &content, &members, &config, !options.sandboxed)?;
+    }
+
+    let mut findings = findings.collected;
+    if let Some(exports) = options.changed_since {
+        remove_preexisting_violations(&mut findings, manifest_path, exports, options)?;
+    }
+
+    let mut report = WorkspaceReport {
+        manifest_path: manifest_path.to_path_buf(),
+        findings,
+        rules,
+        found_deps,
+        obsolete_exceptions: find_obsolete_exceptions(&content, &exceptions.for_rule(&DEFAULT_FEATURES)),
+        exception_reasons: exceptions.reasons,
+        expired_exceptions: exceptions.expired,
+        vendor_audit,
+        truncated,
+        budgets: Vec::new(),
+        unbudgeted: 0,
+        duration: Duration::ZERO,
+    };
+
+    if !config.max_violations.is_empty() {
+        let root = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+        let violations: Vec<_> = report.errors().map(|finding| finding.manifest_path.as_path()).collect();
+        let (budgets, unbudgeted) = evaluate_budgets(&config.max_violations, root, &violations)?;
+        report.budgets = budgets;
+        report.unbudgeted = unbudgeted;
+    }
+
+    Ok(report)
+}
+
+/// Runs the rules that only look at the `[workspace.dependencies]` table of the root manifest
+fn check_workspace_table(findings: &mut Findings<'_>, manifest_path: &Path, content: &str, config: &Config) -> Result<()> {
+    if findings.rules.is_enabled(&VERSION_PRECISION) {
+        findings.add(
+            &VERSION_PRECISION,
+            manifest_path,
+            find_imprecise_versions(content, config.version_precision)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&DUPLICATE_FEATURES) {
+        findings.add(&DUPLICATE_FEATURES, manifest_path, find_duplicate_features(content, None)?);
+    }
+
+    if findings.rules.is_enabled(&DENIED_FEATURES) {
+        findings.add(
+            &DENIED_FEATURES,
+            manifest_path,
+            find_denied_features(content, true, &config.denied_features)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&REQUIRED_FEATURES) {
+        findings.add(
+            &REQUIRED_FEATURES,
+            manifest_path,
+            find_missing_required_features(content, true, &config.required_features)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&FEATURE_BUDGET) {
+        findings.add(
+            &FEATURE_BUDGET,
+            manifest_path,
+            find_feature_budget_overruns(content, None, config.max_features, &config.max_features_per_crate)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&UMBRELLA_FEATURES) {
+        findings.add(
+            &UMBRELLA_FEATURES,
+            manifest_path,
+            find_umbrella_features(content, true, &config.umbrella_features())?,
+        );
+    }
+
+    if findings.rules.is_enabled(&LEGACY_DEFAULT_FEATURES) {
+        findings.add(
+            &LEGACY_DEFAULT_FEATURES,
+            manifest_path,
+            find_legacy_default_features(content, true)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&SORTED_DEPENDENCIES) {
+        findings.add(&SORTED_DEPENDENCIES, manifest_path, find_unsorted_dependencies(content)?);
+    }
+
+    if findings.rules.is_enabled(&GIT_DEPENDENCIES) {
+        findings.add(
+            &GIT_DEPENDENCIES,
+            manifest_path,
+            find_git_dependencies(content, &config.allowed_git_hosts, &config.allowed_git_crates)?,
+        );
+    }
+
+    Ok(())
+}
+
+/// Reports the problems of the manifests `cargo package` generates for the members
+///
+/// Packaging writes `.crate` files and runs cargo under the workspace's own configuration, so it
+/// fails when `allowed` isn't set.
+fn check_packaging(
+    findings: &mut Findings<'_>,
+    manifest_path: &Path,
+    content: &str,
+    members: &[Member],
+    exceptions: &Exceptions,
+    allowed: bool,
+) -> Result<()> {
+    if !allowed {
+        bail!(
+            "Rule {} packages the members of {}, which the daemon doesn't do, check it without --connect instead",
+            PACKAGED_MANIFESTS.id,
+            manifest_path.display()
+        );
+    }
+
+    for (path, errors) in check_packaged_members(manifest_path, content, members, &exceptions.for_rule(&PACKAGED_MANIFESTS))? {
+        findings.add(&PACKAGED_MANIFESTS, &path, errors);
+    }
+
+    Ok(())
+}
+
+/// Runs the custom rules configured through the plugins and scripts settings
+///
+/// Fails when custom rules are configured but `allowed` isn't set.
+fn check_custom_rules(
+    findings: &mut Findings<'_>,
+    manifest_path: &Path,
+    content: &str,
+    members: &[Member],
+    config: &Config,
+    allowed: bool,
+) -> Result<()> {
+    if config.plugins.is_empty() && config.scripts.is_none() {
+        return Ok(());
+    }
+
+    if !allowed {
+        bail!(
+            "The configuration of {} sets plugins or scripts, which the daemon doesn't run, check it without --connect instead",
+            manifest_path.display()
+        );
+    }
+
+    let model = ManifestModel::new(manifest_path, content, members)?;
+    for (path, message) in run_plugins(&config.plugins, &model)? {
+        findings.add(&CUSTOM_RULES, &path, [Violation::manifest(message)]);
+    }
+
+    if let Some(scripts) = &config.scripts {
+        for (path, message) in run_scripts(scripts, &model)? {
+            findings.add(&CUSTOM_RULES, &path, [Violation::manifest(message)]);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the `[workspace.dependencies]` entries of a workspace manifest being edited, with rule ENDF001
+///
+/// The content is given rather than read from disk, since it may not be saved yet. The configuration,
+/// exceptions and suppression comments apply like for `check_workspace`, and the findings carry the
+/// severity the configuration gives the rule, none being returned when it's disabled.
+pub fn check_workspace_dependencies(manifest_path: &Path, content: &str, options: &CheckOptions<'_>) -> Result<Vec<Finding>> {
+    let config = Config::discover_with_content(manifest_path, content, options.config_path)?;
+    let mut rules = config.rules(options.preset)?;
+    rules.merge(options.rules);
+    let exceptions = Exceptions::new(&config, options, manifest_path, content)?;
+
+    let mut findings = Findings {
+        rules: &rules,
+        cache: None,
+        locations: BTreeMap::from([(manifest_path.to_path_buf(), KeyLocations::new(content))]),
+        collected: Vec::new(),
+    };
+
+    let (errors, _) = validate_workspace_dependencies(content, &exceptions.for_rule(&DEFAULT_FEATURES))?;
+    findings.add(&DEFAULT_FEATURES, manifest_path, errors);
+    Ok(findings.collected)
+}
+
+/// Runs the rules that look at the resolved dependency graph, through Cargo.lock or `cargo metadata`
+fn check_resolved_graph(findings: &mut Findings<'_>, manifest_path: &Path, content: &str, exceptions: &Exceptions) -> Result<()> {
+    if findings.rules.is_enabled(&LEAKED_DEFAULTS) {
+        findings.add(
+            &LEAKED_DEFAULTS,
+            manifest_path,
+            find_leaked_defaults(manifest_path, content, findings.cache)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&DUPLICATE_VERSIONS) {
+        findings.add(
+            &DUPLICATE_VERSIONS,
+            manifest_path,
+            find_duplicate_versions(manifest_path, &exceptions.for_rule(&DUPLICATE_VERSIONS))?,
+        );
+    }
+
+    if findings.rules.is_enabled(&REDUNDANT_DEFAULT_FEATURES) {
+        findings.add(
+            &REDUNDANT_DEFAULT_FEATURES,
+            manifest_path,
+            find_redundant_default_features(manifest_path, content, findings.cache)?,
+        );
+    }
+
+    Ok(())
+}
+
+/// Runs the rules that apply to the manifest of a single member
+fn check_member(
+    findings: &mut Findings<'_>,
+    member: &Member,
+    workspace_content: &str,
+    config: &Config,
+    options: &CheckOptions<'_>,
+    exceptions: &Exceptions,
+) -> Result<()> {
+    findings.add(
+        &WORKSPACE_BYPASS,
+        &member.manifest_path,
+        find_workspace_bypasses(&member.content, workspace_content)?,
+    );
+
+    if findings.rules.is_enabled(&WORKSPACE_INHERITANCE) {
+        findings.add(
+            &WORKSPACE_INHERITANCE,
+            &member.manifest_path,
+            find_uninherited_dependencies(&member.content, workspace_content)?,
+        );
+    }
+
+    check_member_features(findings, member, workspace_content, config, options.no_std)?;
+
+    if findings.rules.is_enabled(&LEGACY_DEFAULT_FEATURES) {
+        findings.add(
+            &LEGACY_DEFAULT_FEATURES,
+            &member.manifest_path,
+            find_legacy_default_features(&member.content, false)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&VERSION_MISMATCH) {
+        findings.add(
+            &VERSION_MISMATCH,
+            &member.manifest_path,
+            find_version_mismatches(&member.content, workspace_content)?,
+        );
+    }
+
+    let mismatches = find_build_dependency_mismatches(&member.content, workspace_content)?;
+    findings.add(
+        &BUILD_DEPENDENCY_MISMATCH,
+        &member.manifest_path,
+        mismatches.into_iter().map(|mismatch| Violation {
+            message: format!("in member '{}', {}", member.name, mismatch.message),
+            ..mismatch
+        }),
+    );
+
+    let suppressed = compile_suppressions(find_suppressions(&member.content)?);
+    for (rule, check) in MEMBER_RULES {
+        if findings.rules.is_enabled(rule) {
+            findings.add(
+                rule,
+                &member.manifest_path,
+                check(&member.content, &exceptions.for_member(rule, &member.name, &suppressed))?,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the rules about the features a single member enables on its dependencies
+///
+/// Rule ENDF027 applies when `no_std` is set or the member is listed in the configuration.
+fn check_member_features(
+    findings: &mut Findings<'_>,
+    member: &Member,
+    workspace_content: &str,
+    config: &Config,
+    no_std: bool,
+) -> Result<()> {
+    if findings.rules.is_enabled(&DUPLICATE_FEATURES) {
+        findings.add(
+            &DUPLICATE_FEATURES,
+            &member.manifest_path,
+            find_duplicate_features(&member.content, Some(workspace_content))?,
+        );
+    }
+
+    if findings.rules.is_enabled(&DENIED_FEATURES) {
+        findings.add(
+            &DENIED_FEATURES,
+            &member.manifest_path,
+            find_denied_features(&member.content, false, &config.denied_features)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&REQUIRED_FEATURES) {
+        findings.add(
+            &REQUIRED_FEATURES,
+            &member.manifest_path,
+            find_missing_required_features(&member.content, false, &config.required_features)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&FEATURE_BUDGET) {
+        findings.add(
+            &FEATURE_BUDGET,
+            &member.manifest_path,
+            find_feature_budget_overruns(
+                &member.content,
+                Some(workspace_content),
+                config.max_features,
+                &config.max_features_per_crate,
+            )?,
+        );
+    }
+
+    if findings.rules.is_enabled(&UMBRELLA_FEATURES) {
+        findings.add(
+            &UMBRELLA_FEATURES,
+            &member.manifest_path,
+            find_umbrella_features(&member.content, false, &config.umbrella_features())?,
+        );
+    }
+
+    if findings.rules.is_enabled(&MEMBER_DEFAULT_FEATURES) {
+        findings.add(
+            &MEMBER_DEFAULT_FEATURES,
+            &member.manifest_path,
+            find_excessive_default_features(&member.content, &config.allowed_default_features)?,
+        );
+    }
+
+    if findings.rules.is_enabled(&NO_STD) && (no_std || config.no_std_members.contains(&member.name)) {
+        findings.add(
+            &NO_STD,
+            &member.manifest_path,
+            find_no_std_violations(&member.content, workspace_content, &config.no_std_features())?,
+        );
+    }
+
+    Ok(())
+}
+
+/// Applies the automatic fixes of the enabled rules to the manifests of a workspace
+///
+/// Legacy `default_features` keys are renamed, workspace dependencies get `default-features = false`
+/// and are sorted alphabetically, member dependencies declared directly are switched to
+/// `workspace = true`, and duplicate features are removed everywhere. Workspace dependencies no member
+/// inherits are only removed when `remove_unused` is set, since deleting entries is the one fix that
+/// loses information. Manifests are rewritten in place, preserving their formatting, and a
+/// description of every fix applied is returned.
+pub fn fix_workspace(manifest_path: &Path, options: &CheckOptions<'_>, remove_unused: bool) -> Result<Vec<String>> {
+    let config = Config::discover(manifest_path, options.config_path)?;
+    let mut rules = config.rules(options.preset)?;
+    rules.merge(options.rules);
+
+    let mut content = std::fs::read_to_string(manifest_path).with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    let exceptions = Exceptions::new(&config, options, manifest_path, &content)?;
+    let mut applied = Vec::new();
+    if rules.is_enabled(&LEGACY_DEFAULT_FEATURES) {
+        let fixed = rename_all_legacy_default_features(manifest_path, &content, true, &mut applied)?;
+        write_fixed(manifest_path, &content, &fixed)?;
+        content = fixed;
+    }
+
+    if rules.is_enabled(&DEFAULT_FEATURES) {
+        let mut fixed = content.clone();
+        for name in fixable_workspace_dependencies(&content, &exceptions.for_rule(&DEFAULT_FEATURES))? {
+            fixed = set_default_features_false(&fixed, &["workspace", "dependencies"], &name)?;
+            applied.push(format!("{}: set default-features = false for '{name}'", manifest_path.display()));
+        }
+
+        write_fixed(manifest_path, &content, &fixed)?;
+        content = fixed;
+    }
+
+    if rules.is_enabled(&SORTED_DEPENDENCIES) && !find_unsorted_dependencies(&content)?.is_empty() {
+        let fixed = sort_dependencies(&content, &["workspace", "dependencies"])?;
+        applied.push(format!(
+            "{}: sorted [workspace.dependencies] alphabetically",
+            manifest_path.display()
+        ));
+        write_fixed(manifest_path, &content, &fixed)?;
+        content = fixed;
+    }
+
+    if rules.is_enabled(&DUPLICATE_FEATURES) {
+        let fixed = dedup_all_features(manifest_path, &content, None, &mut applied)?;
+        write_fixed(manifest_path, &content, &fixed)?;
+        content = fixed;
+    }
+
+    for member in load_members(manifest_path, &content, None)? {
+        let mut fixed = member.content.clone();
+        if rules.is_enabled(&LEGACY_DEFAULT_FEATURES) {
+            fixed = rename_all_legacy_default_features(&member.manifest_path, &fixed, false, &mut applied)?;
+        }
+
+        if rules.is_enabled(&WORKSPACE_INHERITANCE) {
+            for dependency in find_uninherited_dependencies(&member.content, &content)? {
+                let table_path: Vec<_> = dependency.table_path.iter().map(String::as_str).collect();
+                fixed = inherit_from_workspace(&fixed, &table_path, &dependency.name)?;
+                applied.push(format!(
+                    "{}: '{}' in {} now inherits from [workspace.dependencies]",
+                    member.manifest_path.display(),
+                    dependency.name,
+                    section_label(&dependency.table_path),
+                ));
+            }
+        }
+
+        if rules.is_enabled(&DUPLICATE_FEATURES) {
+            fixed = dedup_all_features(&member.manifest_path, &fixed, Some(&content), &mut applied)?;
+        }
+
+        write_fixed(&member.manifest_path, &member.content, &fixed)?;
+    }
+
+    // Members are only reloaded now, since switching their entries to `workspace = true` uses more
+    // of the workspace dependencies
+    if remove_unused && rules.is_enabled(&UNUSED_WORKSPACE_DEPENDENCIES) {
+        let members = load_members(manifest_path, &content, None)?;
+        let mut fixed = content.clone();
+        for dependency in find_unused_workspace_dependencies(&members, &content, &exceptions.for_rule(&UNUSED_WORKSPACE_DEPENDENCIES))? {
+            fixed = remove_dependency(&fixed, &["workspace", "dependencies"], &dependency.name)?;
+            applied.push(format!(
+                "{}: removed '{}' from [workspace.dependencies] since no member inherits it",
+                manifest_path.display(),
+                dependency.name
+            ));
+        }
+
+        write_fixed(manifest_path, &content, &fixed)?;
+    }
+
+    Ok(applied)
+}
+
+/// Renames the legacy `default_features` key of every dependency entry of a manifest, returning the
+/// fixed content
+///
+/// The tables fixed are chosen the same way as for `find_legacy_default_features`.
+fn rename_all_legacy_default_features(path: &Path, content: &str, workspace_table: bool, applied: &mut Vec<String>) -> Result<String> {
+    let mut fixed = content.to_string();
+    for problem in find_legacy_default_features(content, workspace_table)? {
+        let table_path: Vec<_> = problem.table_path.iter().map(String::as_str).collect();
+        fixed = rename_legacy_default_features(&fixed, &table_path, &problem.name)?;
+        applied.push(format!(
+            "{}: renamed default_features to default-features for '{}' in {}",
+            path.display(),
+            problem.name,
+            section_label(&problem.table_path),
+        ));
+    }
+
+    Ok(fixed)
+}
+
+/// Removes the duplicate features of every dependency entry of a manifest, returning the fixed content
+///
+/// The workspace's content is given when fixing a member, see `find_duplicate_features`.
+fn dedup_all_features(path: &Path, content: &str, workspace_content: Option<&str>, applied: &mut Vec<String>) -> Result<String> {
+    let workspace: toml::Value = toml::from_str(workspace_content.unwrap_or(content)).context("Failed to parse Cargo.toml")?;
+
+    let mut fixed = content.to_string();
+    for problem in find_duplicate_features(content, workspace_content)? {
+        let table_path: Vec<_> = problem.table_path.iter().map(String::as_str).collect();
+        fixed = dedup_features(&fixed, &table_path, &problem.name, &workspace_features(&workspace, &problem.name))?;
+        applied.push(format!(
+            "{}: removed the duplicate features of '{}' in {}",
+            path.display(),
+            problem.name,
+            section_label(&problem.table_path),
+        ));
+    }
+
+    Ok(fixed)
+}
+
+/// Writes a fixed manifest back to disk, unless no fix changed it
+fn write_fixed(path: &Path, original: &str, fixed: &str) -> Result<()> {
+    if fixed != original {
+        std::fs::write(path, fixed).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Removes the findings that were already present at the given git revision
+///
+/// The repository is exported as it was at that revision, once for all the workspaces it holds, and the
+/// workspace is checked there with the same options, so every rule takes part. Findings whose fingerprint was already reported then are
+/// dropped, leaving only the violations introduced since. Fingerprints are taken relative to the
+/// repository's root, which keeps them comparable between the working tree and the export. When the
+/// workspace didn't exist at that revision, or couldn't be checked there, every finding is kept.
+fn remove_preexisting_violations(
+    findings: &mut Vec<Finding>,
+    manifest_path: &Path,
+    exports: &RevisionExports,
+    options: &CheckOptions<'_>,
+) -> Result<()> {
+    let manifest_path = std::path::absolute(manifest_path).with_context(|| format!("Failed to resolve {}", manifest_path.display()))?;
+    let workspace_dir = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+    let toplevel = toplevel(Some(workspace_dir))?;
+    let canonical = manifest_path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", manifest_path.display()))?;
+    let relative = canonical
+        .strip_prefix(toplevel.canonicalize().unwrap_or_else(|_| toplevel.clone()))
+        .with_context(|| format!("{} isn't in the git repository at {}", manifest_path.display(), toplevel.display()))?;
+
+    // The repository's root as reached from the manifest path, which is what the findings' paths start with
+    let root = relative
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .fold(workspace_dir.to_path_buf(), |root, _| {
+            root.parent().map(Path::to_path_buf).unwrap_or(root)
+        });
+
+    let export = exports.export(&toplevel)?;
+    let old_manifest_path = export.join(relative);
+    if !old_manifest_path.is_file() {
+        return Ok(());
+    }
+
+    let old_options = CheckOptions {
+        vendor_dir: None,
+        cache: None,
+        changed_since: None,
+        ..*options
+    };
+    let Ok(old_report) = check_workspace(&old_manifest_path, &old_options) else {
+        return Ok(());
+    };
+
+    let preexisting: BTreeSet<_> = old_report.findings.iter().map(|finding| finding.fingerprint(&export)).collect();
+    findings.retain(|finding| !preexisting.contains(&finding.fingerprint(&root)));
+    Ok(())
+}
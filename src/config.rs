@@ -0,0 +1,894 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use toml::value::{Date, Datetime};
+
+use crate::rules::{Preset, RULES, RuleSet, Severity, find_rule};
+use crate::validation::{DependencyKind, validate_exceptions};
+use crate::versions::VersionPrecision;
+
+/// Names of the configuration files looked up next to the workspace manifest, in priority order
+pub const CONFIG_FILE_NAMES: [&str; 2] = ["ensure-no-default-features.toml", ".ensure-no-default-features.toml"];
+
+/// Name of the table of `[workspace.metadata]` holding settings when there's no configuration file
+pub const METADATA_TABLE: &str = "ensure-no-default-features";
+
+/// Umbrella features flagged when the `umbrella-features` setting is absent
+pub const DEFAULT_UMBRELLA_FEATURES: [&str; 3] = ["full", "all", "complete"];
+
+/// Dependency features forbidden in `no_std` members when the `no-std-features` setting is absent
+pub const DEFAULT_NO_STD_FEATURES: [&str; 1] = ["std"];
+
+/// Settings read from a configuration file or from the workspace manifest's metadata
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    /// Maximum number of violations tolerated per directory
+    ///
+    /// Keys are paths or glob patterns relative to the workspace root, with `*` matching everything.
+    /// When several keys match a directory, the longest one applies.
+    #[serde(default)]
+    pub max_violations: BTreeMap<String, usize>,
+
+    /// Curated set of rules the other rule settings build on, the standard one when absent
+    #[serde(default)]
+    pub preset: Option<Preset>,
+
+    /// IDs or names of rules to run in addition to the ones enabled by default
+    #[serde(default)]
+    pub enable_rules: Vec<String>,
+
+    /// IDs or names of rules not to run
+    #[serde(default)]
+    pub disable_rules: Vec<String>,
+
+    /// Severity of the findings of specific rules, keyed by rule ID or name, enabling the rules
+    #[serde(default)]
+    pub severity: BTreeMap<String, Severity>,
+
+    /// Minimum precision of the version requirements in `[workspace.dependencies]`
+    #[serde(default)]
+    pub version_precision: VersionPrecision,
+
+    /// Hosts whose repositories may be used by git dependencies in `[workspace.dependencies]`
+    #[serde(default)]
+    pub allowed_git_hosts: Vec<String>,
+
+    /// Crates allowed to be git dependencies in `[workspace.dependencies]`
+    #[serde(default)]
+    pub allowed_git_crates: Vec<String>,
+
+    /// Features no dependency may enable, such as `vendored` or `bundled`
+    #[serde(default)]
+    pub denied_features: Vec<String>,
+
+    /// Features that must be enabled for each crate declared with `default-features = false`
+    #[serde(default)]
+    pub required_features: BTreeMap<String, Vec<String>>,
+
+    /// Maximum number of features a dependency may enable, unless overridden for its crate
+    #[serde(default)]
+    pub max_features: Option<usize>,
+
+    /// Maximum number of features the listed crates may enable, overriding `max-features`
+    #[serde(default)]
+    pub max_features_per_crate: BTreeMap<String, usize>,
+
+    /// Umbrella features such as `full` that re-enable most of a crate, see `umbrella_features()`
+    #[serde(default)]
+    pub umbrella_features: Option<Vec<String>>,
+
+    /// Features a member's own `default` feature may enable, such as `std`
+    #[serde(default)]
+    pub allowed_default_features: Vec<String>,
+
+    /// Names of the members that must build without `std`
+    #[serde(default)]
+    pub no_std_members: Vec<String>,
+
+    /// Dependency features `no_std` members must not enable, see `no_std_features()`
+    #[serde(default)]
+    pub no_std_features: Option<Vec<String>>,
+
+    /// Dependencies excluded from specific rules, keyed by rule ID or name, or by `*` for every rule
+    ///
+    /// These apply on top of the exceptions given on the command line, which cover every rule. An array
+    /// is shorthand for a table with a single `*` key.
+    #[serde(default, deserialize_with = "deserialize_exceptions")]
+    pub exceptions: BTreeMap<String, Vec<Exception>>,
+
+    /// Custom rules compiled to WebAssembly, relative to the workspace root
+    #[serde(default)]
+    pub plugins: Vec<PathBuf>,
+
+    /// Directory of custom rules written as Rhai scripts, relative to the workspace root
+    #[serde(default)]
+    pub scripts: Option<PathBuf>,
+
+    /// cargo-deny configuration file whose allowlists seed the exceptions, relative to the workspace root
+    #[serde(default)]
+    pub cargo_deny: Option<PathBuf>,
+
+    /// Configuration file whose settings apply beneath this one's, relative to the file declaring it
+    ///
+    /// The extended settings are merged while loading the configuration, so this is always `None` afterwards.
+    #[serde(default)]
+    #[expect(dead_code, reason = "Only declared so the setting is validated along with the others")]
+    extends: Option<PathBuf>,
+}
+
+/// A dependency excluded from some rules by the configuration
+///
+/// Written either as the dependency's name, or as a table such as `{ crate = "serde", reason = "..." }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Exception {
+    /// Name, glob pattern, or regular expression of the excluded dependencies
+    pub name: String,
+
+    /// Why the dependency is excluded, echoed in reports
+    pub reason: Option<String>,
+
+    /// Who to ask about the exception, such as `@platform-team`, echoed in reports
+    pub owner: Option<String>,
+
+    /// Last day the exception applies, after which the dependency is checked again
+    pub expires: Option<Date>,
+
+    /// Names of the members whose manifests the exception is limited to, every manifest when empty
+    pub members: Vec<String>,
+
+    /// Kinds of the dependencies the exception is limited to, every kind when empty
+    pub kinds: Vec<DependencyKind>,
+}
+
+impl Exception {
+    /// Returns whether the exception no longer applies on the given day
+    pub fn is_expired(&self, today: Date) -> bool {
+        self.expires.is_some_and(|expires| expires < today)
+    }
+
+    /// Returns the exception as handed to the rules, once per dependency kind it's limited to
+    fn scoped_names(&self) -> Vec<String> {
+        if self.kinds.is_empty() {
+            return vec![self.name.clone()];
+        }
+
+        self.kinds.iter().map(|kind| kind.scope(&self.name)).collect()
+    }
+}
+
+impl<'de> Deserialize<'de> for Exception {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        /// The table form of an exception
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct ExceptionTable {
+            #[serde(rename = "crate")]
+            name: String,
+            #[serde(default)]
+            reason: Option<String>,
+            #[serde(default)]
+            owner: Option<String>,
+            #[serde(default)]
+            expires: Option<String>,
+            #[serde(default)]
+            members: Vec<String>,
+            #[serde(default)]
+            kinds: Vec<DependencyKind>,
+        }
+
+        /// Visitor accepting both forms of an exception
+        struct ExceptionVisitor;
+
+        impl<'de> Visitor<'de> for ExceptionVisitor {
+            type Value = Exception;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                formatter.write_str("a dependency name or a table with a `crate` key")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> core::result::Result<Exception, E> {
+                Ok(Exception {
+                    name: v.to_string(),
+                    ..Exception::default()
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> core::result::Result<Exception, A::Error> {
+                let table = ExceptionTable::deserialize(MapAccessDeserializer::new(map))?;
+                let expires = table
+                    .expires
+                    .map(|expires| parse_date(&expires).map_err(serde::de::Error::custom))
+                    .transpose()?;
+
+                Ok(Exception {
+                    name: table.name,
+                    reason: table.reason,
+                    owner: table.owner,
+                    expires,
+                    members: table.members,
+                    kinds: table.kinds,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(ExceptionVisitor)
+    }
+}
+
+/// Deserializes the `exceptions` setting, either a table keyed by rule or an array covering every rule
+fn deserialize_exceptions<'de, D: Deserializer<'de>>(deserializer: D) -> core::result::Result<BTreeMap<String, Vec<Exception>>, D::Error> {
+    /// Visitor accepting both forms of the setting
+    struct ExceptionsVisitor;
+
+    impl<'de> Visitor<'de> for ExceptionsVisitor {
+        type Value = BTreeMap<String, Vec<Exception>>;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            formatter.write_str("a table of exceptions keyed by rule, or an array of exceptions")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> core::result::Result<Self::Value, A::Error> {
+            let entries = Vec::deserialize(SeqAccessDeserializer::new(seq))?;
+            Ok(BTreeMap::from([("*".to_string(), entries)]))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> core::result::Result<Self::Value, A::Error> {
+            BTreeMap::deserialize(MapAccessDeserializer::new(map))
+        }
+    }
+
+    deserializer.deserialize_any(ExceptionsVisitor)
+}
+
+impl Config {
+    /// Loads the configuration governing the given workspace manifest
+    ///
+    /// Settings are layered, each layer overriding the settings of the ones below it, setting by setting:
+    ///
+    /// 1. The nearest configuration file in the parent directories, up to the root of the enclosing git repository.
+    /// 2. The manifest's `[workspace.metadata.ensure-no-default-features]` table.
+    /// 3. The configuration file next to the manifest, or the one given explicitly.
+    ///
+    /// An explicit path replaces both configuration files. When no layer exists, the default
+    /// configuration is returned.
+    pub fn discover(manifest_path: &Path, explicit: Option<&Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(manifest_path).unwrap_or_default();
+        Self::discover_with_content(manifest_path, &content, explicit)
+    }
+
+    /// Loads the configuration governing a workspace manifest whose content is given, such as one being
+    /// edited and not saved yet, see `discover`
+    pub fn discover_with_content(manifest_path: &Path, content: &str, explicit: Option<&Path>) -> Result<Self> {
+        let mut files = config_dirs(manifest_path).into_iter().map(|dir| find_config_file(&dir));
+        let nearest = files.next().flatten();
+        let parent = files.flatten().next();
+
+        let mut layers = Vec::new();
+        if explicit.is_none()
+            && let Some(path) = parent
+        {
+            layers.push(load_layer(&path, &mut Vec::new())?);
+        }
+
+        layers.extend(metadata_layer(manifest_path, content)?);
+
+        if let Some(path) = explicit.map(Path::to_path_buf).or(nearest) {
+            layers.push(load_layer(&path, &mut Vec::new())?);
+        }
+
+        let mut merged = toml::Table::new();
+        for layer in layers {
+            merged.extend(layer);
+        }
+
+        merged.try_into().context("Failed to merge the configuration")
+    }
+
+    /// Returns the umbrella features to flag, falling back to `DEFAULT_UMBRELLA_FEATURES`
+    pub fn umbrella_features(&self) -> Vec<String> {
+        self.umbrella_features
+            .clone()
+            .unwrap_or_else(|| DEFAULT_UMBRELLA_FEATURES.map(str::to_string).to_vec())
+    }
+
+    /// Returns the dependency features forbidden in `no_std` members, falling back to `DEFAULT_NO_STD_FEATURES`
+    pub fn no_std_features(&self) -> Vec<String> {
+        self.no_std_features
+            .clone()
+            .unwrap_or_else(|| DEFAULT_NO_STD_FEATURES.map(str::to_string).to_vec())
+    }
+
+    /// Returns the per-rule exceptions covering every member, keyed by rule ID
+    ///
+    /// Expired exceptions are left out, so the dependencies they cover are checked again. Exceptions
+    /// limited to dependency kinds are scoped to each of them, as in `dev:criterion`.
+    pub fn rule_exceptions(&self) -> Result<BTreeMap<&'static str, Vec<String>>> {
+        let mut exceptions: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+        for (rule, entry) in self.applicable_exceptions()? {
+            if entry.members.is_empty() {
+                exceptions.entry(rule).or_default().extend(entry.scoped_names());
+            }
+        }
+
+        Ok(exceptions)
+    }
+
+    /// Returns the per-rule exceptions limited to specific members, keyed by rule ID and member name
+    pub fn member_exceptions(&self) -> Result<BTreeMap<(&'static str, String), Vec<String>>> {
+        let mut exceptions: BTreeMap<(&'static str, String), Vec<String>> = BTreeMap::new();
+        for (rule, entry) in self.applicable_exceptions()? {
+            for member in &entry.members {
+                exceptions.entry((rule, member.clone())).or_default().extend(entry.scoped_names());
+            }
+        }
+
+        Ok(exceptions)
+    }
+
+    /// Returns the exceptions that haven't expired, each paired with the ID of a rule it applies to
+    fn applicable_exceptions(&self) -> Result<Vec<(&'static str, &Exception)>> {
+        let today = today();
+        let mut applicable = Vec::new();
+        for (key, entries) in &self.exceptions {
+            let rules = if key == "*" {
+                RULES.to_vec()
+            } else {
+                vec![find_rule(key).context("Invalid exceptions setting")?]
+            };
+
+            let names: Vec<_> = entries.iter().map(|entry| entry.name.clone()).collect();
+            validate_exceptions(&names).context("Invalid exceptions setting")?;
+            for entry in entries.iter().filter(|entry| !entry.is_expired(today)) {
+                applicable.extend(rules.iter().map(|rule| (rule.id, entry)));
+            }
+        }
+
+        Ok(applicable)
+    }
+
+    /// Returns the exceptions in effect giving a reason or an owner, paired with the rule ID or name they're keyed by
+    pub fn exception_reasons(&self) -> Vec<(String, Exception)> {
+        let today = today();
+        self.exceptions
+            .iter()
+            .flat_map(|(key, entries)| entries.iter().map(move |entry| (key.clone(), entry.clone())))
+            .filter(|(_, entry)| (entry.reason.is_some() || entry.owner.is_some()) && !entry.is_expired(today))
+            .collect()
+    }
+
+    /// Returns the exceptions past their expiry date, paired with the rule ID or name they're keyed by
+    pub fn expired_exceptions(&self) -> Vec<(String, Exception)> {
+        let today = today();
+        self.exceptions
+            .iter()
+            .flat_map(|(key, entries)| entries.iter().map(move |entry| (key.clone(), entry.clone())))
+            .filter(|(_, entry)| entry.is_expired(today))
+            .collect()
+    }
+
+    /// Fails when one of the exceptions doesn't say why the dependency is excluded
+    pub fn require_exception_reasons(&self) -> Result<()> {
+        for (key, entries) in &self.exceptions {
+            if let Some(entry) = entries.iter().find(|entry| entry.reason.is_none()) {
+                bail!(
+                    "Exception '{}' for '{key}' has no reason, add one with `{{ crate = \"{}\", reason = \"...\" }}`",
+                    entry.name,
+                    entry.name
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the rule overrides defined by the configuration
+    ///
+    /// The rules of the preset come first, with the given preset replacing the configured one.
+    pub fn rules(&self, preset: Option<Preset>) -> Result<RuleSet> {
+        let mut rules = preset.or(self.preset).map_or_else(RuleSet::default, Preset::rules);
+        for key in &self.enable_rules {
+            rules.enable(find_rule(key).context("Invalid enable-rules setting")?);
+        }
+
+        for (key, severity) in &self.severity {
+            rules.set_severity(find_rule(key).context("Invalid severity setting")?, *severity);
+        }
+
+        for key in &self.disable_rules {
+            rules.disable(find_rule(key).context("Invalid disable-rules setting")?);
+        }
+
+        Ok(rules)
+    }
+}
+
+/// Parses a date written as `YYYY-MM-DD`
+fn parse_date(text: &str) -> Result<Date> {
+    match text.parse::<Datetime>() {
+        Ok(Datetime {
+            date: Some(date),
+            time: None,
+            offset: None,
+        }) => Ok(date),
+        _ => bail!("Invalid date '{text}', expected YYYY-MM-DD"),
+    }
+}
+
+/// Returns the current date in UTC
+fn today() -> Date {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    date_from_days(secs / 86_400)
+}
+
+/// Converts a number of days since 1970-01-01 to a date of the proleptic Gregorian calendar
+///
+/// This is Howard Hinnant's `civil_from_days` algorithm, restricted to dates after the epoch.
+fn date_from_days(days: u64) -> Date {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    Date {
+        year: u16::try_from(year).unwrap_or(u16::MAX),
+        month: u8::try_from(month).unwrap_or_default(),
+        day: u8::try_from(day).unwrap_or_default(),
+    }
+}
+
+/// Reads a file listing dependencies to exclude from the rules honoring exceptions
+///
+/// Files with a `.toml` extension hold an `exceptions` array. Any other file lists one dependency per
+/// line, ignoring blank lines and comments starting with `#`.
+pub fn load_exceptions_file(path: &Path) -> Result<Vec<String>> {
+    /// The content of a TOML exceptions file
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct ExceptionsFile {
+        exceptions: Vec<String>,
+    }
+
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if path.extension().is_some_and(|extension| extension == "toml") {
+        let file: ExceptionsFile = toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+        return Ok(file.exceptions);
+    }
+
+    Ok(content
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(before, _)| before).trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Reads a configuration file, validating its settings and merging the ones of the files it extends
+///
+/// `chain` holds the files extending this one, so cycles can be reported.
+fn load_layer(path: &Path, chain: &mut Vec<PathBuf>) -> Result<toml::Table> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let table: toml::Table = toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    check_setting_names(&table).with_context(|| format!("Invalid configuration file {}", path.display()))?;
+
+    // Parsing the content again reports wrongly-typed values along with their location in the file
+    let _: Config = toml::from_str(&content).with_context(|| format!("Invalid configuration file {}", path.display()))?;
+
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    if chain.contains(&canonical) {
+        bail!("Configuration files extend each other in a cycle through {}", path.display());
+    }
+
+    chain.push(canonical);
+    let table = resolve_extends(table, path.parent().unwrap_or_else(|| Path::new("")), chain);
+    let _ = chain.pop();
+    table
+}
+
+/// Merges the settings of the file named by a layer's `extends` setting beneath the layer's own
+///
+/// The path is relative to `dir`, the directory of the file declaring it.
+fn resolve_extends(mut table: toml::Table, dir: &Path, chain: &mut Vec<PathBuf>) -> Result<toml::Table> {
+    let Some(toml::Value::String(extends)) = table.remove("extends") else {
+        return Ok(table);
+    };
+
+    let mut merged = load_layer(&dir.join(extends), chain)?;
+    merged.extend(table);
+    Ok(merged)
+}
+
+/// Reads the `[workspace.metadata.ensure-no-default-features]` table of a manifest, validating its settings
+///
+/// Manifests that can't be read or parsed are treated as having no such table, leaving it to the
+/// checks to report the problem. Files the table extends are resolved against the manifest's directory.
+fn metadata_layer(manifest_path: &Path, manifest: &str) -> Result<Option<toml::Table>> {
+    let Ok(mut parsed) = toml::from_str::<toml::Table>(manifest) else {
+        return Ok(None);
+    };
+
+    let Some(toml::Value::Table(table)) = parsed
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("metadata"))
+        .and_then(|metadata| metadata.as_table_mut()?.remove(METADATA_TABLE))
+    else {
+        return Ok(None);
+    };
+
+    let context = || format!("Invalid [workspace.metadata.{METADATA_TABLE}] table in {}", manifest_path.display());
+    check_setting_names(&table).with_context(context)?;
+    let _: Config = table.clone().try_into().with_context(context)?;
+
+    let dir = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+    resolve_extends(table, dir, &mut Vec::new()).map(Some)
+}
+
+/// Fails when a table of settings has a key that isn't a known setting, suggesting the closest one
+fn check_setting_names(table: &toml::Table) -> Result<()> {
+    let known = setting_names();
+    let Some(key) = table.keys().find(|key| !known.contains(&key.as_str())) else {
+        return Ok(());
+    };
+
+    match closest_match(key, known.iter().copied()) {
+        Some(suggestion) => bail!("Unknown setting '{key}', did you mean '{suggestion}'?"),
+        None => bail!("Unknown setting '{key}', expected one of: {}", known.join(", ")),
+    }
+}
+
+/// Returns the names of the settings, as spelled in configuration files
+///
+/// The names are the ones `Config` hands to serde, so they can't drift from the struct's fields.
+fn setting_names() -> &'static [&'static str] {
+    /// Deserializer capturing the field names of the struct being deserialized, then failing
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldNames<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> core::result::Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("expected a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> core::result::Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("field names captured"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+            unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = Config::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// Returns the candidate most similar to the given key, if any is close enough to be a likely typo
+pub fn closest_match<'a>(key: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::jaro_winkler(key, candidate), candidate))
+        .filter(|(similarity, _)| *similarity >= 0.8)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, candidate)| candidate)
+}
+
+/// Returns the directories searched for configuration files, nearest first
+///
+/// The search starts in the directory of the given workspace manifest and walks up to the root of the
+/// enclosing git repository. Outside of a git repository, only the manifest's own directory is searched.
+fn config_dirs(manifest_path: &Path) -> Vec<PathBuf> {
+    let dir = manifest_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let Ok(dir) = dir.canonicalize() else {
+        return vec![dir.to_path_buf()];
+    };
+
+    let mut dirs = Vec::new();
+    for ancestor in dir.ancestors() {
+        dirs.push(ancestor.to_path_buf());
+        if ancestor.join(".git").exists() {
+            return dirs;
+        }
+    }
+
+    dirs.truncate(1);
+    dirs
+}
+
+/// Returns the path of the configuration file in the given directory, if one exists
+fn find_config_file(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES.iter().map(|name| dir.join(name)).find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("nested/workspace");
+        std::fs::create_dir_all(&workspace).unwrap();
+        let manifest_path = workspace.join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace]\n").unwrap();
+        std::fs::write(dir.path().join("ensure-no-default-features.toml"), "max-features = 1\n").unwrap();
+
+        // Outside of a git repository, only the workspace's directory is searched
+        assert_eq!(Config::discover(&manifest_path, None).unwrap().max_features, None);
+
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        assert_eq!(Config::discover(&manifest_path, None).unwrap().max_features, Some(1));
+
+        // Nearer layers override the settings of the ones farther away, one setting at a time
+        std::fs::write(
+            &manifest_path,
+            "[workspace]\n\n[workspace.metadata.ensure-no-default-features]\nmax-features = 2\ndenied-features = [\"vendored\"]\n",
+        )
+        .unwrap();
+        let config = Config::discover(&manifest_path, None).unwrap();
+        assert_eq!(config.max_features, Some(2));
+        assert_eq!(config.denied_features, ["vendored"]);
+
+        std::fs::write(workspace.join(".ensure-no-default-features.toml"), "max-features = 3\n").unwrap();
+        let config = Config::discover(&manifest_path, None).unwrap();
+        assert_eq!(config.max_features, Some(3));
+        assert_eq!(config.denied_features, ["vendored"]);
+
+        // An explicit file replaces the discovered ones, but not the metadata table
+        let explicit = dir.path().join("explicit.toml");
+        std::fs::write(&explicit, "allowed-git-crates = [\"serde\"]\n").unwrap();
+        let config = Config::discover(&manifest_path, Some(&explicit)).unwrap();
+        assert_eq!(config.max_features, Some(2));
+        assert_eq!(config.allowed_git_crates, ["serde"]);
+    }
+
+    #[test]
+    fn test_extends() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = dir.path().join("shared");
+        let workspace = dir.path().join("workspace");
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::create_dir_all(&workspace).unwrap();
+        let manifest_path = workspace.join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace]\n").unwrap();
+
+        std::fs::write(shared.join("base.toml"), "max-features = 1\ndenied-features = [\"vendored\"]\n").unwrap();
+        std::fs::write(shared.join("policy.toml"), "extends = \"base.toml\"\nmax-features = 2\n").unwrap();
+        std::fs::write(
+            workspace.join("ensure-no-default-features.toml"),
+            "extends = \"../shared/policy.toml\"\nallowed-git-crates = [\"serde\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::discover(&manifest_path, None).unwrap();
+        assert_eq!(config.max_features, Some(2));
+        assert_eq!(config.denied_features, ["vendored"]);
+        assert_eq!(config.allowed_git_crates, ["serde"]);
+
+        // The metadata table's extends is relative to the workspace root
+        std::fs::remove_file(workspace.join("ensure-no-default-features.toml")).unwrap();
+        std::fs::write(
+            &manifest_path,
+            "[workspace]\n\n[workspace.metadata.ensure-no-default-features]\nextends = \"../shared/base.toml\"\n",
+        )
+        .unwrap();
+        assert_eq!(Config::discover(&manifest_path, None).unwrap().max_features, Some(1));
+
+        std::fs::write(shared.join("base.toml"), "extends = \"policy.toml\"\n").unwrap();
+        let err = Config::discover(&manifest_path, None).unwrap_err();
+        assert!(format!("{err:#}").contains("Configuration files extend each other in a cycle"));
+    }
+
+    #[test]
+    fn test_exception_reasons() {
+        let config: Config = toml::from_str(
+            r#"
+[exceptions]
+default-features = ["tokio", { crate = "serde", reason = "needs std" }]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.rule_exceptions().unwrap()["ENDF001"], ["tokio", "serde"]);
+        assert_eq!(
+            config.exception_reasons(),
+            [(
+                "default-features".to_string(),
+                Exception {
+                    name: "serde".to_string(),
+                    reason: Some("needs std".to_string()),
+                    ..Exception::default()
+                }
+            )]
+        );
+
+        let err = config.require_exception_reasons().unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Exception 'tokio' for 'default-features' has no reason")
+        );
+
+        let config: Config = toml::from_str(r#"exceptions = [{ crate = "openssl", owner = "@platform-team" }]"#).unwrap();
+        let reasons = config.exception_reasons();
+        assert_eq!(reasons.len(), 1);
+        assert_eq!(reasons[0].1.owner.as_deref(), Some("@platform-team"));
+
+        let _ = toml::from_str::<Config>(
+            "[exceptions]
+default-features = [{ crate = \"serde\", reasn = \"typo\" }]
+",
+        )
+        .unwrap_err();
+        let _ = toml::from_str::<Config>(
+            "[exceptions]
+default-features = [{ reason = \"no crate\" }]
+",
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_severity() {
+        let config: Config = toml::from_str(
+            r#"
+disable-rules = ["ENDF026"]
+
+[severity]
+ENDF002 = "warn"
+ENDF026 = "error"
+sorted-dependencies = "warning"
+"#,
+        )
+        .unwrap();
+
+        let rules = config.rules(None).unwrap();
+        assert_eq!(rules.severity(find_rule("ENDF002").unwrap()), Some(Severity::Warning));
+        assert_eq!(rules.severity(find_rule("ENDF017").unwrap()), Some(Severity::Warning));
+        assert_eq!(rules.severity(find_rule("ENDF026").unwrap()), None);
+
+        let config: Config = toml::from_str("[severity]\nENDF999 = \"warn\"\n").unwrap();
+        let _ = config.rules(None).unwrap_err();
+        let _ = toml::from_str::<Config>("[severity]\nENDF002 = \"loud\"\n").unwrap_err();
+    }
+
+    #[test]
+    fn test_member_exceptions() {
+        let config: Config = toml::from_str(r#"exceptions = ["serde", { crate = "clap", members = ["cli", "xtask"] }]"#).unwrap();
+
+        assert_eq!(config.rule_exceptions().unwrap()["ENDF001"], ["serde"]);
+        let member_exceptions = config.member_exceptions().unwrap();
+        assert_eq!(member_exceptions[&("ENDF010", "cli".to_string())], ["clap"]);
+        assert_eq!(member_exceptions[&("ENDF010", "xtask".to_string())], ["clap"]);
+        assert!(!member_exceptions.contains_key(&("ENDF010", "core".to_string())));
+    }
+
+    #[test]
+    fn test_kind_exceptions() {
+        let config: Config = toml::from_str(
+            r#"exceptions = [{ crate = "criterion", kinds = ["dev"] }, { crate = "cc", kinds = ["build", "normal"], members = ["sys"] }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.rule_exceptions().unwrap()["ENDF012"], ["dev:criterion"]);
+        assert_eq!(
+            config.member_exceptions().unwrap()[&("ENDF012", "sys".to_string())],
+            ["build:cc", "normal:cc"]
+        );
+
+        let _ = toml::from_str::<Config>(r#"exceptions = [{ crate = "criterion", kinds = ["bench"] }]"#).unwrap_err();
+    }
+
+    #[test]
+    fn test_exception_expiry() {
+        let config: Config = toml::from_str(
+            r#"
+[exceptions]
+"*" = [{ crate = "serde", expires = "2000-01-01" }, { crate = "tokio", expires = "9999-12-31" }]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.rule_exceptions().unwrap()["ENDF001"], ["tokio"]);
+        let expired = config.expired_exceptions();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].1.name, "serde");
+        assert_eq!(expired[0].1.expires.unwrap().to_string(), "2000-01-01");
+
+        let err = toml::from_str::<Config>("[exceptions]\n\"*\" = [{ crate = \"serde\", expires = \"soon\" }]\n").unwrap_err();
+        assert!(err.to_string().contains("Invalid date 'soon', expected YYYY-MM-DD"));
+    }
+
+    #[test]
+    fn test_date_from_days() {
+        assert_eq!(date_from_days(0).to_string(), "1970-01-01");
+        assert_eq!(date_from_days(11_016).to_string(), "2000-02-29");
+        assert_eq!(date_from_days(20_453).to_string(), "2025-12-31");
+    }
+
+    #[test]
+    fn test_load_exceptions_file() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("exceptions.txt");
+        std::fs::write(&path, "# Needs std\nserde\n\n  tokio  # Runtime\n").unwrap();
+        assert_eq!(load_exceptions_file(&path).unwrap(), ["serde", "tokio"]);
+
+        let path = dir.path().join("exceptions.toml");
+        std::fs::write(&path, "exceptions = [\"serde\", \"tokio\"]\n").unwrap();
+        assert_eq!(load_exceptions_file(&path).unwrap(), ["serde", "tokio"]);
+
+        std::fs::write(&path, "exception = [\"serde\"]\n").unwrap();
+        let _ = load_exceptions_file(&path).unwrap_err();
+    }
+
+    #[test]
+    fn test_setting_names() {
+        let names = setting_names();
+        assert!(names.contains(&"max-violations"));
+        assert!(names.contains(&"exceptions"));
+        assert!(names.contains(&"scripts"));
+    }
+
+    #[test]
+    fn test_unknown_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ensure-no-default-features.toml");
+
+        std::fs::write(&path, "exeptions = { \"*\" = [\"serde\"] }\n").unwrap();
+        let err = Config::discover(&dir.path().join("Cargo.toml"), Some(&path)).unwrap_err();
+        assert!(format!("{err:#}").contains("Unknown setting 'exeptions', did you mean 'exceptions'?"));
+
+        std::fs::write(&path, "colors = true\n").unwrap();
+        let err = Config::discover(&dir.path().join("Cargo.toml"), Some(&path)).unwrap_err();
+        assert!(format!("{err:#}").contains("Unknown setting 'colors', expected one of: max-violations, "));
+
+        std::fs::write(&path, "max-features = \"many\"\n").unwrap();
+        let err = Config::discover(&dir.path().join("Cargo.toml"), Some(&path)).unwrap_err();
+        assert!(format!("{err:#}").contains("max-features = \"many\""));
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let candidates = ["enable-rules", "disable-rules", "max-features"];
+        assert_eq!(closest_match("enable-rule", candidates), Some("enable-rules"));
+        assert_eq!(closest_match("disabled-rules", candidates), Some("disable-rules"));
+        assert_eq!(closest_match("plugins", candidates), None);
+    }
+
+    #[test]
+    fn test_discover_reports_the_invalid_layer() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &manifest_path,
+            "[workspace.metadata.ensure-no-default-features]\nmax-features = \"many\"\n",
+        )
+        .unwrap();
+
+        let err = Config::discover(&manifest_path, None).unwrap_err();
+        assert!(format!("{err:#}").contains("Invalid [workspace.metadata.ensure-no-default-features] table"));
+    }
+}
@@ -0,0 +1,381 @@
+use core::fmt::{Display, Write as _};
+use core::net::SocketAddr;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Instant;
+
+use anyhow::{Context, Result, bail};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Address used by --daemon and --connect when none is given
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7491";
+
+/// How long a connection may stay silent before the daemon closes it
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Outcome of a check performed by the daemon on behalf of a client
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Response {
+    /// Exit status the client should use
+    pub status: i32,
+
+    /// Text the check printed to standard output
+    pub stdout: String,
+
+    /// Text the check printed to standard error
+    pub stderr: String,
+
+    /// Error that prevented the check from completing
+    pub error: Option<String>,
+}
+
+/// Counters describing the checks answered by the daemon, exposed on `/metrics`
+#[derive(Debug, Default)]
+struct CheckCounters {
+    checks: AtomicU64,
+    failures: AtomicU64,
+    last_duration_micros: AtomicU64,
+}
+
+impl CheckCounters {
+    /// Records a check that took the given time, and whether it failed to complete
+    fn record(&self, failed: bool, duration: Duration) {
+        let _ = self.checks.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            let _ = self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        self.last_duration_micros.store(micros, Ordering::Relaxed);
+    }
+
+    /// Renders the counters in the Prometheus text format, followed by those written by `metrics`
+    fn render(&self, metrics: &impl Fn(&mut String)) -> String {
+        let mut out = String::new();
+        let checks = self.checks.load(Ordering::Relaxed);
+        write_metric(
+            &mut out,
+            "endf_daemon_checks_total",
+            "counter",
+            "Checks answered by the daemon",
+            checks,
+        );
+        let failures = self.failures.load(Ordering::Relaxed);
+        write_metric(
+            &mut out,
+            "endf_daemon_check_failures_total",
+            "counter",
+            "Checks that failed to complete",
+            failures,
+        );
+        #[expect(clippy::cast_precision_loss, reason = "Durations are far below the precision of f64")]
+        let seconds = self.last_duration_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        write_metric(
+            &mut out,
+            "endf_daemon_last_check_duration_seconds",
+            "gauge",
+            "Time taken by the last check answered",
+            seconds,
+        );
+        metrics(&mut out);
+        out
+    }
+}
+
+/// Writes a metric along with its description in the Prometheus text format
+pub fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, value: impl Display) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}");
+}
+
+/// Listens for check requests on the given address until the process is killed
+///
+/// Requests and responses are exchanged as JSON, one per line, and a connection can carry any number of
+/// requests. Each connection is handled on its own thread, so a client keeping its connection open
+/// doesn't hold up the others, and connections silent for longer than a minute are closed. Requests
+/// are answered by the given handler, which receives the buffers standing in for standard output and
+/// standard error, and returns the exit status. Connections aren't authenticated, so only loopback
+/// addresses are accepted, keeping the daemon out of reach of other machines.
+///
+/// When `metrics_addr` is given, HTTP requests to `/healthz` and `/metrics` are answered there, the
+/// latter with counters of the checks answered in the Prometheus text format, followed by the metrics
+/// written by `metrics`.
+pub fn serve<Req, F, M>(addr: &str, metrics_addr: Option<&str>, handler: F, metrics: M) -> Result<()>
+where
+    Req: DeserializeOwned,
+    F: Fn(Req, &mut Vec<u8>, &mut Vec<u8>) -> Result<i32> + Sync,
+    M: Fn(&mut String) + Sync,
+{
+    let listener = bind(addr)?;
+    let metrics_listener = metrics_addr.map(bind).transpose()?;
+    let local_addr = listener.local_addr().context("Failed to determine the listening address")?;
+    eprintln!("👂 Listening for check requests on {local_addr}");
+    if let Some(metrics_listener) = &metrics_listener {
+        let local_addr = metrics_listener.local_addr().context("Failed to determine the listening address")?;
+        eprintln!("👂 Answering /healthz and /metrics on {local_addr}");
+    }
+
+    let counters = CheckCounters::default();
+    std::thread::scope(|scope| {
+        if let Some(metrics_listener) = &metrics_listener {
+            let _ = scope.spawn(|| accept_connections(metrics_listener, &|stream| answer_probe(stream, &counters, &metrics)));
+        }
+
+        accept_connections(&listener, &|stream| handle_connection(stream, &handler, &counters));
+    });
+
+    Ok(())
+}
+
+/// Listens on the given address, which must be a loopback one
+fn bind(addr: &str) -> Result<TcpListener> {
+    TcpListener::bind(&*loopback_addrs(addr)?).with_context(|| format!("Failed to listen on {addr}"))
+}
+
+/// Handles every connection made to the listener on a thread of its own, until the process is killed
+fn accept_connections(listener: &TcpListener, handle: &(impl Fn(TcpStream) -> Result<()> + Sync)) {
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let _ = scope.spawn(move || {
+                let result = stream.context("Failed to accept a connection").and_then(handle);
+                if let Err(err) = result {
+                    eprintln!("⚠️ Warning: {err:#}");
+                }
+            });
+        }
+    });
+}
+
+/// Resolves the address to listen on, failing unless every address it resolves to is a loopback one
+fn loopback_addrs(addr: &str) -> Result<Vec<SocketAddr>> {
+    let addrs: Vec<_> = addr
+        .to_socket_addrs()
+        .with_context(|| format!("Invalid address '{addr}'"))?
+        .collect();
+    if addrs.is_empty() || addrs.iter().any(|addr| !addr.ip().is_loopback()) {
+        bail!("Refusing to listen on {addr}, the daemon only listens on loopback addresses such as {DEFAULT_ADDR}");
+    }
+
+    Ok(addrs)
+}
+
+/// Answers every request sent over one connection
+fn handle_connection<Req, F>(stream: TcpStream, handler: &F, counters: &CheckCounters) -> Result<()>
+where
+    Req: DeserializeOwned,
+    F: Fn(Req, &mut Vec<u8>, &mut Vec<u8>) -> Result<i32>,
+{
+    stream
+        .set_read_timeout(Some(IDLE_TIMEOUT))
+        .context("Failed to set up the connection")?;
+    let mut writer = stream.try_clone().context("Failed to set up the connection")?;
+    for line in BufReader::new(stream).lines() {
+        let line = line.context("Failed to read a request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut response = Response::default();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let started = Instant::now();
+        let result = serde_json::from_str(&line)
+            .context("Failed to parse the request")
+            .and_then(|request| handler(request, &mut stdout, &mut stderr));
+        counters.record(result.is_err(), started.elapsed());
+        match result {
+            Ok(status) => response.status = status,
+            Err(err) => response.error = Some(format!("{err:#}")),
+        }
+
+        response.stdout = String::from_utf8_lossy(&stdout).into_owned();
+        response.stderr = String::from_utf8_lossy(&stderr).into_owned();
+
+        let body = serde_json::to_string(&response).context("Failed to serialize the response")?;
+        writeln!(writer, "{body}").context("Failed to send the response")?;
+    }
+
+    Ok(())
+}
+
+/// Answers an HTTP request for `/healthz` or `/metrics`, closing the connection afterwards
+fn answer_probe(mut stream: TcpStream, counters: &CheckCounters, metrics: &impl Fn(&mut String)) -> Result<()> {
+    stream
+        .set_read_timeout(Some(IDLE_TIMEOUT))
+        .context("Failed to set up the connection")?;
+    let mut reader = BufReader::new(stream.try_clone().context("Failed to set up the connection")?);
+    let mut request_line = String::new();
+    let _ = reader.read_line(&mut request_line).context("Failed to read a request")?;
+
+    // The headers are read without being looked at, up to the blank line ending them
+    let mut header = String::new();
+    while reader.read_line(&mut header).context("Failed to read a request")? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/healthz")) => ("200 OK", "ok\n".to_string()),
+        (Some("GET"), Some("/metrics")) => ("200 OK", counters.render(metrics)),
+        _ => ("404 Not Found", "Not found\n".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .context("Failed to send the response")
+}
+
+/// Sends a request to the daemon listening on the given address and waits for its response
+pub fn send<Req: Serialize>(addr: &str, request: &Req) -> Result<Response> {
+    let mut stream = TcpStream::connect(addr).with_context(|| format!("Failed to connect to the daemon at {addr}"))?;
+    let body = serde_json::to_string(request).context("Failed to serialize the request")?;
+    writeln!(stream, "{body}").context("Failed to send the request")?;
+
+    let mut line = String::new();
+    let _ = BufReader::new(stream)
+        .read_line(&mut line)
+        .context("Failed to read the daemon's response")?;
+    if line.is_empty() {
+        bail!("The daemon at {addr} closed the connection without responding");
+    }
+
+    serde_json::from_str(&line).context("Failed to parse the daemon's response")
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    /// Returns a handler echoing its request, counting the calls it answered
+    fn handler() -> impl Fn(Vec<String>, &mut Vec<u8>, &mut Vec<u8>) -> Result<i32> + Sync {
+        let calls = AtomicUsize::new(0);
+        move |request, stdout, stderr| {
+            let calls = calls.fetch_add(1, Ordering::Relaxed) + 1;
+            if request.is_empty() {
+                bail!("empty request");
+            }
+
+            writeln!(stdout, "checked {}", request.join(" "))?;
+            writeln!(stderr, "call {calls}")?;
+            Ok(1)
+        }
+    }
+
+    fn spawn_server(connections: usize) -> (String, std::thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let server = std::thread::spawn(move || {
+            let handler = handler();
+            let counters = CheckCounters::default();
+            for _ in 0..connections {
+                let (stream, _) = listener.accept().unwrap();
+                handle_connection(stream, &handler, &counters).unwrap();
+            }
+        });
+
+        (addr, server)
+    }
+
+    #[test]
+    fn test_idle_connection_doesnt_block_others() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let _ = std::thread::spawn(move || {
+            let handler = handler();
+            let counters = CheckCounters::default();
+            accept_connections(&listener, &|stream| handle_connection(stream, &handler, &counters));
+        });
+
+        let idle = TcpStream::connect(&addr).unwrap();
+        let response = send(&addr, &vec!["a".to_string()]).unwrap();
+        assert_eq!(response.stdout, "checked a\n");
+        drop(idle);
+    }
+
+    #[test]
+    fn test_probes() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let counters = CheckCounters::default();
+        counters.record(false, Duration::from_millis(250));
+        counters.record(true, Duration::from_millis(1500));
+        let _ = std::thread::spawn(move || {
+            let metrics = |out: &mut String| write_metric(out, "endf_test", "counter", "Test", 7);
+            accept_connections(&listener, &|stream| answer_probe(stream, &counters, &metrics));
+        });
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(&addr).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            let _ = std::io::Read::read_to_string(&mut stream, &mut response).unwrap();
+            response
+        };
+
+        let health = get("/healthz");
+        assert!(health.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(health.ends_with("\r\n\r\nok\n"));
+
+        let metrics = get("/metrics");
+        assert!(metrics.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(metrics.contains("# TYPE endf_daemon_checks_total counter\nendf_daemon_checks_total 2\n"));
+        assert!(metrics.contains("\nendf_daemon_check_failures_total 1\n"));
+        assert!(metrics.contains("\nendf_daemon_last_check_duration_seconds 1.5\n"));
+        assert!(metrics.ends_with("# HELP endf_test Test\n# TYPE endf_test counter\nendf_test 7\n"));
+
+        assert!(get("/other").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn test_loopback_addrs() {
+        assert!(loopback_addrs("127.0.0.1:0").unwrap()[0].ip().is_loopback());
+        assert!(loopback_addrs("[::1]:0").unwrap()[0].ip().is_loopback());
+
+        let err = loopback_addrs("0.0.0.0:7491").unwrap_err();
+        assert!(err.to_string().contains("only listens on loopback addresses"));
+        let _ = loopback_addrs("192.168.1.10:7491").unwrap_err();
+        let _ = loopback_addrs("not an address").unwrap_err();
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let (addr, server) = spawn_server(2);
+
+        let response = send(&addr, &vec!["a".to_string(), "b".to_string()]).unwrap();
+        assert_eq!(response.status, 1);
+        assert_eq!(response.stdout, "checked a b\n");
+        assert_eq!(response.stderr, "call 1\n");
+        assert!(response.error.is_none());
+
+        let response = send(&addr, &Vec::<String>::new()).unwrap();
+        assert_eq!(response.error.as_deref(), Some("empty request"));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_several_requests_per_connection() {
+        let (addr, server) = spawn_server(1);
+
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        writeln!(stream, "[\"a\"]\n\nnot json\n[\"b\"]").unwrap();
+        stream.shutdown(std::net::Shutdown::Write).unwrap();
+
+        let responses: Vec<Response> = BufReader::new(stream)
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0].stdout, "checked a\n");
+        assert!(responses[1].error.as_deref().unwrap().starts_with("Failed to parse the request"));
+        assert_eq!(responses[2].stderr, "call 2\n");
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::rules::{DUPLICATE_VERSIONS, RULES};
+
+/// Reads the exceptions implied by the allowlists of a cargo-deny configuration file, keyed by rule ID
+///
+/// Two sections of `deny.toml` carry over:
+///
+/// - The crates of `bans.skip` and `bans.skip-tree`, allowed to appear at several versions, are
+///   excluded from the duplicate-versions rule.
+/// - The crates whose `bans.features` entry allows their `default` feature are excluded from every rule.
+///
+/// Crates are given by name, possibly followed by `@` and a version, or as tables with a `name` or
+/// `crate` key. Versions are ignored, so an entry covers every version of its crate.
+pub fn load_cargo_deny_exceptions(path: &Path) -> Result<BTreeMap<&'static str, Vec<String>>> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let parsed: toml::Value = toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(cargo_deny_exceptions(&parsed))
+}
+
+/// Extracts the exceptions from a parsed cargo-deny configuration, keyed by rule ID
+fn cargo_deny_exceptions(deny: &toml::Value) -> BTreeMap<&'static str, Vec<String>> {
+    let bans = deny.get("bans");
+    let entries = |key: &str| {
+        bans.and_then(|bans| bans.get(key))
+            .and_then(toml::Value::as_array)
+            .map_or(&[][..], Vec::as_slice)
+    };
+
+    let mut exceptions: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+    for entry in entries("skip").iter().chain(entries("skip-tree")) {
+        if let Some(name) = crate_name(entry) {
+            exceptions.entry(DUPLICATE_VERSIONS.id).or_default().push(name.to_string());
+        }
+    }
+
+    for entry in entries("features") {
+        let allows_default = entry
+            .get("allow")
+            .and_then(toml::Value::as_array)
+            .is_some_and(|allow| allow.iter().any(|feature| feature.as_str() == Some("default")));
+        if let Some(name) = crate_name(entry).filter(|_| allows_default) {
+            for rule in RULES {
+                exceptions.entry(rule.id).or_default().push(name.to_string());
+            }
+        }
+    }
+
+    exceptions
+}
+
+/// Returns the name of the crate a cargo-deny entry refers to, without any version
+fn crate_name(entry: &toml::Value) -> Option<&str> {
+    let spec = match entry {
+        toml::Value::String(spec) => spec.as_str(),
+        toml::Value::Table(table) => table.get("crate").or_else(|| table.get("name")).and_then(toml::Value::as_str)?,
+        _ => return None,
+    };
+
+    spec.split('@').next().filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cargo_deny_exceptions() {
+        let deny: toml::Value = toml::from_str(
+            r#"
+[bans]
+multiple-versions = "warn"
+skip = ["windows-sys", { name = "syn", version = "1" }, "bitflags@1.3.2"]
+skip-tree = [{ crate = "tokio@0.2", reason = "legacy" }]
+
+[[bans.features]]
+crate = "reqwest"
+allow = ["default", "json"]
+
+[[bans.features]]
+name = "openssl"
+deny = ["vendored"]
+
+[licenses]
+allow = ["MIT"]
+"#,
+        )
+        .unwrap();
+
+        let exceptions = cargo_deny_exceptions(&deny);
+        assert_eq!(exceptions["ENDF030"], ["windows-sys", "syn", "bitflags", "tokio", "reqwest"]);
+        assert_eq!(exceptions["ENDF001"], ["reqwest"]);
+        assert!(cargo_deny_exceptions(&toml::from_str("[graph]\n").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_load_cargo_deny_exceptions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deny.toml");
+        std::fs::write(&path, "[bans]\nskip = [\"syn\"]\n").unwrap();
+        assert_eq!(load_cargo_deny_exceptions(&path).unwrap()["ENDF030"], ["syn"]);
+
+        let _ = load_cargo_deny_exceptions(&dir.path().join("missing.toml")).unwrap_err();
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::git::show_file;
+use crate::validation::{compile_exceptions, is_dependency_excepted, resolve_versioned_exceptions, validate_dependency};
+
+/// Changes in compliance between two versions of a workspace manifest
+#[derive(Debug, Default)]
+pub struct ManifestDiff {
+    /// Dependencies that violate the policy in the new version but didn't in the old one
+    pub regressions: Vec<String>,
+
+    /// Dependencies that violated the policy in the old version but no longer do in the new one
+    pub improvements: Vec<String>,
+}
+
+/// Loads one side of a comparison
+///
+/// The spec is either the path of a manifest file, or a git revision at which the manifest at
+/// `manifest_path` is read.
+pub fn load_manifest_version(spec: &str, manifest_path: &Path) -> Result<String> {
+    let path = Path::new(spec);
+    if path.is_file() {
+        return std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()));
+    }
+
+    show_file(spec, manifest_path)?.with_context(|| format!("{} doesn't exist at revision '{spec}'", manifest_path.display()))
+}
+
+/// Compares the `[workspace.dependencies]` entries of two versions of a manifest
+///
+/// A dependency added in the new version without `default-features = false` counts as a regression,
+/// and a violating dependency removed from the new version counts as an improvement.
+pub fn diff_manifests(old_content: &str, new_content: &str, exceptions: &[String]) -> Result<ManifestDiff> {
+    let old = violations(old_content, exceptions).context("Failed to validate the old manifest")?;
+    let new = violations(new_content, exceptions).context("Failed to validate the new manifest")?;
+
+    let regressions = new
+        .iter()
+        .filter(|(name, _)| !old.contains_key(*name))
+        .map(|(_, message)| message.clone())
+        .collect();
+
+    let improvements = old
+        .keys()
+        .filter(|name| !new.contains_key(*name))
+        .map(|name| format!("'{name}': no longer enables default features"))
+        .collect();
+
+    Ok(ManifestDiff { regressions, improvements })
+}
+
+/// Returns the error message of every violating entry in `[workspace.dependencies]`, keyed by name
+fn violations(content: &str, exceptions: &[String]) -> Result<BTreeMap<String, String>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let deps_table = parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    let exceptions = compile_exceptions(&resolve_versioned_exceptions(exceptions, content));
+    Ok(deps_table
+        .into_iter()
+        .flatten()
+        .filter(|(name, value)| !is_dependency_excepted(&exceptions, name, value))
+        .filter_map(|(name, value)| {
+            validate_dependency(value)
+                .err()
+                .map(|problem| (name.clone(), problem.message(name)))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_manifests() {
+        let old = r#"
+[workspace.dependencies]
+serde = "1.0"
+regex = { version = "1.0" }
+tokio = { version = "1.0", default-features = false }
+anyhow = "1.0"
+"#;
+        let new = r#"
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+regex = { version = "1.0", default-features = true }
+tokio = { version = "1.0" }
+clap = "4.0"
+"#;
+
+        let diff = diff_manifests(old, new, &[]).unwrap();
+        assert_eq!(
+            diff.regressions,
+            [
+                "'clap': uses simple version string, should be a table with default-features = false",
+                "'tokio': missing default-features = false",
+            ]
+        );
+        assert_eq!(
+            diff.improvements,
+            [
+                "'anyhow': no longer enables default features",
+                "'serde': no longer enables default features",
+            ]
+        );
+
+        let diff = diff_manifests(old, new, &["clap".to_string(), "tokio".to_string()]).unwrap();
+        assert!(diff.regressions.is_empty());
+    }
+
+    #[test]
+    fn test_diff_without_workspace_dependencies() {
+        let diff = diff_manifests("[package]\nname = \"a\"\n", "[workspace.dependencies]\nserde = \"1.0\"\n", &[]).unwrap();
+        assert_eq!(diff.regressions.len(), 1);
+        assert!(diff.improvements.is_empty());
+
+        let _ = diff_manifests("[workspace", "", &[]).unwrap_err();
+    }
+}
@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+
+use crate::config::CONFIG_FILE_NAMES;
+
+/// Directories never descended into while searching for manifests
+const SKIPPED_DIRS: &[&str] = &["target"];
+
+/// Resolves a user-supplied manifest path to the root manifest of the workspace it belongs to
+///
+/// When the path is a directory, the Cargo.toml inside it is used. When that manifest has no
+/// `[workspace]` section, the workspace root is located the same way Cargo does it: through the
+/// `package.workspace` key when present, and otherwise by walking up the parent directories until a
+/// manifest with a `[workspace]` section is found. If no workspace root can be found, the manifest
+/// itself is returned so the caller reports why it can't be checked.
+pub fn resolve_manifest_path(path: &Path) -> Result<PathBuf> {
+    let manifest_path = if path.is_dir() {
+        path.join("Cargo.toml")
+    } else {
+        path.to_path_buf()
+    };
+    let Ok(content) = std::fs::read_to_string(&manifest_path) else {
+        return Ok(manifest_path);
+    };
+
+    let Ok(parsed) = toml::from_str::<toml::Value>(&content) else {
+        return Ok(manifest_path);
+    };
+
+    if parsed.get("workspace").is_some() {
+        return Ok(manifest_path);
+    }
+
+    let manifest_path = std::path::absolute(&manifest_path).with_context(|| format!("Failed to resolve {}", manifest_path.display()))?;
+    let member_dir = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+
+    if let Some(workspace) = parsed.get("package").and_then(|p| p.get("workspace")).and_then(toml::Value::as_str) {
+        return Ok(member_dir.join(workspace).join("Cargo.toml"));
+    }
+
+    for ancestor in member_dir.ancestors().skip(1) {
+        let candidate = ancestor.join("Cargo.toml");
+        if candidate.is_file() && is_workspace_root(&candidate)? {
+            return Ok(candidate);
+        }
+    }
+
+    Ok(manifest_path)
+}
+
+/// Finds the root manifest of every workspace located beneath the given directory
+///
+/// A Cargo.toml counts as a workspace root when it contains a `[workspace]` section. Build output,
+/// hidden directories, paths ignored through `.gitignore` files, and paths matching any of the
+/// `excludes` globs are not searched. Exclude globs use gitignore syntax and are matched relative to
+/// the searched directory. The returned paths are sorted so reports are stable from one run to the next.
+pub fn find_workspace_manifests(dir: &Path, excludes: &[String]) -> Result<Vec<PathBuf>> {
+    let mut overrides = OverrideBuilder::new(dir);
+    for exclude in excludes {
+        let _ = overrides
+            .add(&format!("!{exclude}"))
+            .with_context(|| format!("Invalid exclude pattern '{exclude}'"))?;
+    }
+
+    let walker = WalkBuilder::new(dir)
+        .require_git(false)
+        .overrides(overrides.build().context("Failed to build exclude patterns")?)
+        .filter_entry(|entry| {
+            !(entry.file_type().is_some_and(|t| t.is_dir()) && SKIPPED_DIRS.iter().any(|skipped| entry.file_name() == *skipped))
+        })
+        .build();
+
+    let mut manifests = Vec::new();
+    for entry in walker {
+        let entry = entry.with_context(|| format!("Failed to search {}", dir.display()))?;
+        let path = entry.path();
+        if entry.file_name() == "Cargo.toml" && entry.file_type().is_some_and(|t| !t.is_dir()) && is_workspace_root(path)? {
+            manifests.push(path.to_path_buf());
+        }
+    }
+
+    manifests.sort();
+    Ok(manifests)
+}
+
+/// Maps a list of changed files to the root manifests of the workspaces that need to be checked
+///
+/// Only Cargo.toml files and configuration files are relevant, anything else is ignored along with
+/// files that no longer exist. Each relevant file is mapped back to its owning workspace the same way
+/// `resolve_manifest_path` does it, except for configuration files without a Cargo.toml next to them,
+/// which map to every workspace beneath them. Every workspace is returned once, in sorted order.
+pub fn find_owning_workspaces(files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut manifests = Vec::new();
+    for file in files {
+        let Some(file_name) = file.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+
+        let manifest_path = if file_name == "Cargo.toml" {
+            file.clone()
+        } else if CONFIG_FILE_NAMES.contains(&file_name) {
+            let manifest_path = file.with_file_name("Cargo.toml");
+
+            // A configuration file away from any manifest governs the workspaces beneath it
+            if !manifest_path.is_file()
+                && let Some(dir) = file.parent().filter(|dir| dir.is_dir())
+            {
+                for manifest_path in find_workspace_manifests(dir, &[])? {
+                    manifests.push(
+                        std::path::absolute(&manifest_path).with_context(|| format!("Failed to resolve {}", manifest_path.display()))?,
+                    );
+                }
+
+                continue;
+            }
+
+            manifest_path
+        } else {
+            continue;
+        };
+
+        if !manifest_path.is_file() {
+            continue;
+        }
+
+        let manifest_path = resolve_manifest_path(&manifest_path)?;
+        manifests.push(std::path::absolute(&manifest_path).with_context(|| format!("Failed to resolve {}", manifest_path.display()))?);
+    }
+
+    manifests.sort();
+    manifests.dedup();
+    Ok(manifests)
+}
+
+/// Returns whether the manifest at the given path declares a `[workspace]` section
+fn is_workspace_root(manifest_path: &Path) -> Result<bool> {
+    let content = std::fs::read_to_string(manifest_path).with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    let parsed: toml::Value = toml::from_str(&content).with_context(|| format!("Failed to parse {}", manifest_path.display()))?;
+    Ok(parsed.get("workspace").is_some())
+}
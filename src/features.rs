@@ -0,0 +1,579 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+
+use crate::rules::Violation;
+use crate::validation::{
+    DependencyProblem, ExceptionPattern, dependency_package_name, dependency_tables, enables_default_features,
+    is_member_dependency_excepted, member_dependency_table_paths, section_label,
+};
+
+/// Finds dependency entries whose `features` array lists the same feature more than once
+///
+/// The entries of `[workspace.dependencies]` are checked when `workspace_content` is `None`, and the
+/// dependency tables of the package otherwise. Features of member entries inherited with
+/// `workspace = true` also count as duplicates when the workspace entry already enables them.
+pub fn find_duplicate_features(content: &str, workspace_content: Option<&str>) -> Result<Vec<DependencyProblem>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+
+    let workspace = workspace_content
+        .map(toml::from_str::<toml::Value>)
+        .transpose()
+        .context("Failed to parse Cargo.toml")?;
+
+    let mut problems = Vec::new();
+    for (table_path, deps_table) in dependency_tables(&parsed, workspace.is_none()) {
+        for (name, value) in deps_table {
+            let inherited = match &workspace {
+                Some(workspace) if value.get("workspace").and_then(toml::Value::as_bool) == Some(true) => {
+                    workspace_features(workspace, name)
+                }
+                _ => Vec::new(),
+            };
+
+            problems.extend(duplicate_features_problem(&table_path, name, value, &inherited));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Finds dependency entries enabling one of the denied features
+///
+/// The entries of `[workspace.dependencies]` are checked when `workspace_table` is set, and the
+/// dependency tables of the package otherwise.
+pub fn find_denied_features(content: &str, workspace_table: bool, denied: &[String]) -> Result<Vec<Violation>> {
+    find_listed_features(content, workspace_table, denied, "denied")
+}
+
+/// Finds dependency entries enabling umbrella features, such as `full`, that bring back most of a crate
+///
+/// The tables checked are chosen the same way as for `find_denied_features`.
+pub fn find_umbrella_features(content: &str, workspace_table: bool, umbrella: &[String]) -> Result<Vec<Violation>> {
+    find_listed_features(content, workspace_table, umbrella, "umbrella")
+}
+
+/// Finds dependency entries enabling one of the listed features, describing them with the given kind
+fn find_listed_features(content: &str, workspace_table: bool, listed: &[String], kind: &str) -> Result<Vec<Violation>> {
+    if listed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+
+    let mut problems = Vec::new();
+    for (table_path, deps_table) in dependency_tables(&parsed, workspace_table) {
+        for (name, value) in deps_table {
+            let enabled: Vec<_> = features(value)
+                .into_iter()
+                .filter(|feature| listed.iter().any(|listed| listed == feature))
+                .map(|feature| format!("\"{feature}\""))
+                .collect();
+
+            if !enabled.is_empty() {
+                problems.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!(
+                        "'{name}' in {}: enables {kind} features {}",
+                        section_label(&table_path),
+                        enabled.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Finds dependency entries disabling default features without enabling the features required for them
+///
+/// `required` maps crate names to the features that must be enabled whenever the crate is declared with
+/// `default-features = false`. Member entries inherited with `workspace = true` aren't checked, since the
+/// workspace entry they extend is. The tables checked are chosen the same way as for
+/// `find_denied_features`.
+pub fn find_missing_required_features(
+    content: &str,
+    workspace_table: bool,
+    required: &BTreeMap<String, Vec<String>>,
+) -> Result<Vec<Violation>> {
+    if required.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+
+    let mut problems = Vec::new();
+    for (table_path, deps_table) in dependency_tables(&parsed, workspace_table) {
+        for (name, value) in deps_table {
+            if enables_default_features(value) || value.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+                continue;
+            }
+
+            let Some(required) = required.get(dependency_package_name(name, value)) else {
+                continue;
+            };
+
+            let enabled = features(value);
+            let missing: Vec<_> = required
+                .iter()
+                .filter(|feature| !enabled.contains(&feature.as_str()))
+                .map(|feature| format!("\"{feature}\""))
+                .collect();
+
+            if !missing.is_empty() {
+                problems.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!(
+                        "'{name}' in {}: disables default features without enabling required features {}",
+                        section_label(&table_path),
+                        missing.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Finds dependency entries enabling more features than their budget allows
+///
+/// The budget of a crate is taken from `per_crate` when listed there, and from `max_features` otherwise.
+/// Features of member entries inherited with `workspace = true` count towards the budget along with the
+/// member's own. The tables checked are chosen the same way as for `find_duplicate_features`.
+pub fn find_feature_budget_overruns(
+    content: &str,
+    workspace_content: Option<&str>,
+    max_features: Option<usize>,
+    per_crate: &BTreeMap<String, usize>,
+) -> Result<Vec<Violation>> {
+    if max_features.is_none() && per_crate.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let workspace = workspace_content
+        .map(toml::from_str::<toml::Value>)
+        .transpose()
+        .context("Failed to parse Cargo.toml")?;
+
+    let mut problems = Vec::new();
+    for (table_path, deps_table) in dependency_tables(&parsed, workspace.is_none()) {
+        for (name, value) in deps_table {
+            let mut enabled = features(value);
+            if let Some(workspace) = &workspace
+                && value.get("workspace").and_then(toml::Value::as_bool) == Some(true)
+            {
+                enabled.extend(workspace_features(workspace, name));
+            }
+
+            enabled.sort_unstable();
+            enabled.dedup();
+
+            let Some(budget) = per_crate.get(dependency_package_name(name, value)).copied().or(max_features) else {
+                continue;
+            };
+
+            if enabled.len() > budget {
+                problems.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!(
+                        "'{name}' in {}: enables {} features, more than the budget of {budget}",
+                        section_label(&table_path),
+                        enabled.len()
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Finds the dependencies of a `no_std` member enabling one of the forbidden features, such as `std`
+///
+/// Only `[dependencies]` and its target-specific variants are checked, since build and dev
+/// dependencies never end up in the member's own build. Entries inherited with `workspace = true`
+/// are checked along with the features they inherit from `[workspace.dependencies]`.
+pub fn find_no_std_violations(member_content: &str, workspace_content: &str, forbidden: &[String]) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+    let workspace: toml::Value = toml::from_str(workspace_content).context("Failed to parse Cargo.toml")?;
+
+    let mut violations = Vec::new();
+    for (table_path, deps_table) in member_dependency_table_paths(&member) {
+        if table_path.last().is_none_or(|section| section != "dependencies") {
+            continue;
+        }
+
+        for (name, value) in deps_table {
+            let inherited = if value.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+                workspace_features(&workspace, name)
+            } else {
+                Vec::new()
+            };
+
+            for feature in forbidden {
+                let source = if features(value).contains(&feature.as_str()) {
+                    ""
+                } else if inherited.contains(&feature.as_str()) {
+                    " through [workspace.dependencies]"
+                } else {
+                    continue;
+                };
+
+                violations.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!(
+                        "'{name}' in {}: enables \"{feature}\"{source} in a no_std member",
+                        section_label(&table_path)
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(violations)
+}
+
+/// Finds the entries of a member's own `default` feature that aren't in the allowed set
+///
+/// Whatever a published crate enables by default is pushed onto every consumer that doesn't opt out,
+/// so the `default` feature should stay empty or limited to a few features such as `std`.
+pub fn find_excessive_default_features(member_content: &str, allowed: &[String]) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+    let excess: Vec<_> = member
+        .get("features")
+        .and_then(|features| features.get("default"))
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+        .filter(|feature| !allowed.iter().any(|allowed| allowed == feature))
+        .map(|feature| format!("\"{feature}\""))
+        .collect();
+
+    if excess.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(vec![Violation::feature(
+        "default",
+        format!(
+            "the default feature enables {}, which aren't listed in the allowed-default-features setting",
+            excess.join(", ")
+        ),
+    )])
+}
+
+/// Finds optional member dependencies that no feature enables through `dep:name`
+///
+/// Without a `dep:` reference, Cargo creates an implicit feature named after the dependency, which
+/// becomes part of the crate's public feature set whether intended or not.
+pub fn find_ungated_optional_dependencies(member_content: &str, exceptions: &[ExceptionPattern]) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+    let gated: Vec<_> = member
+        .get("features")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|features| features.values())
+        .filter_map(toml::Value::as_array)
+        .flatten()
+        .filter_map(toml::Value::as_str)
+        .filter_map(|entry| entry.strip_prefix("dep:"))
+        .collect();
+
+    let mut ungated = Vec::new();
+    for (table_path, deps_table) in member_dependency_table_paths(&member) {
+        let section = section_label(&table_path);
+        for (name, value) in deps_table {
+            if value.get("optional").and_then(toml::Value::as_bool) == Some(true)
+                && !gated.contains(&name.as_str())
+                && !is_member_dependency_excepted(exceptions, &section, name, value)
+            {
+                ungated.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!(
+                        "'{name}' in {section}: is optional but no feature enables it through \"dep:{name}\", so Cargo creates an implicit '{name}' feature"
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(ungated)
+}
+
+/// Describes the duplicate features of a dependency entry, if it has any
+fn duplicate_features_problem(table_path: &[String], name: &str, value: &toml::Value, inherited: &[&str]) -> Option<DependencyProblem> {
+    let mut seen = inherited.to_vec();
+    let mut duplicates = Vec::new();
+    for feature in features(value) {
+        if seen.contains(&feature) {
+            if !duplicates.contains(&feature) {
+                duplicates.push(feature);
+            }
+        } else {
+            seen.push(feature);
+        }
+    }
+
+    if duplicates.is_empty() {
+        return None;
+    }
+
+    let duplicates: Vec<_> = duplicates.iter().map(|feature| format!("\"{feature}\"")).collect();
+    let origin = if inherited.is_empty() {
+        ""
+    } else {
+        ", counting the ones inherited from [workspace.dependencies]"
+    };
+
+    Some(DependencyProblem {
+        table_path: table_path.to_vec(),
+        name: name.to_string(),
+        message: format!(
+            "'{name}' in {}: lists {} more than once{origin}",
+            section_label(table_path),
+            duplicates.join(", ")
+        ),
+    })
+}
+
+/// Returns the features enabled by an entry of `[workspace.dependencies]`
+pub fn workspace_features<'a>(workspace: &'a toml::Value, name: &str) -> Vec<&'a str> {
+    workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(|deps| deps.get(name))
+        .map(features)
+        .unwrap_or_default()
+}
+
+/// Returns the features listed by a dependency entry
+pub fn features(value: &toml::Value) -> Vec<&str> {
+    value
+        .get("features")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::messages;
+    use crate::validation::compile_exceptions;
+
+    #[test]
+    fn test_find_duplicate_features_in_workspace() {
+        let content = r#"
+[workspace.dependencies]
+tokio = { version = "1.0", default-features = false, features = ["rt", "rt", "macros", "rt"] }
+serde = { version = "1.0", default-features = false, features = ["derive"] }
+"#;
+
+        let problems = find_duplicate_features(content, None).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].table_path, ["workspace", "dependencies"]);
+        assert_eq!(problems[0].name, "tokio");
+        assert_eq!(
+            problems[0].message,
+            "'tokio' in [workspace.dependencies]: lists \"rt\" more than once"
+        );
+    }
+
+    #[test]
+    fn test_find_denied_features() {
+        let content = r#"
+[workspace.dependencies]
+openssl = { version = "0.10", default-features = false, features = ["vendored"] }
+
+[dependencies]
+rusqlite = { version = "0.31", features = ["bundled", "backup", "vendored"] }
+"#;
+        let denied = ["vendored".to_string(), "bundled".to_string()];
+
+        assert_eq!(
+            messages(&find_denied_features(content, true, &denied).unwrap()),
+            ["'openssl' in [workspace.dependencies]: enables denied features \"vendored\""]
+        );
+        assert_eq!(
+            messages(&find_denied_features(content, false, &denied).unwrap()),
+            ["'rusqlite' in [dependencies]: enables denied features \"bundled\", \"vendored\""]
+        );
+        assert!(find_denied_features(content, true, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_umbrella_features() {
+        let content = r#"
+[workspace.dependencies]
+tokio = { version = "1", default-features = false, features = ["full"] }
+serde = { version = "1", default-features = false, features = ["derive"] }
+"#;
+        let umbrella = ["full".to_string(), "all".to_string()];
+
+        assert_eq!(
+            messages(&find_umbrella_features(content, true, &umbrella).unwrap()),
+            ["'tokio' in [workspace.dependencies]: enables umbrella features \"full\""]
+        );
+        assert!(find_umbrella_features(content, false, &umbrella).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_excessive_default_features() {
+        let content = r#"
+[features]
+default = ["std", "tokio", "dep:serde"]
+std = []
+"#;
+
+        assert_eq!(
+            messages(&find_excessive_default_features(content, &["std".to_string()]).unwrap()),
+            ["the default feature enables \"tokio\", \"dep:serde\", which aren't listed in the allowed-default-features setting"]
+        );
+        assert!(
+            find_excessive_default_features(content, &["std".to_string(), "tokio".to_string(), "dep:serde".to_string()])
+                .unwrap()
+                .is_empty()
+        );
+        assert!(find_excessive_default_features("[features]\nstd = []\n", &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_ungated_optional_dependencies() {
+        let content = r#"
+[features]
+json = ["dep:serde_json"]
+tls = ["rustls/ring"]
+
+[dependencies]
+serde_json = { workspace = true, optional = true }
+rustls = { workspace = true, optional = true }
+tokio = { workspace = true, optional = true }
+log = { workspace = true }
+"#;
+
+        assert_eq!(
+            messages(&find_ungated_optional_dependencies(content, &compile_exceptions(&["tokio".to_string()])).unwrap()),
+            [
+                "'rustls' in [dependencies]: is optional but no feature enables it through \"dep:rustls\", so Cargo creates an implicit 'rustls' feature"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_no_std_violations() {
+        let workspace = r#"
+[workspace.dependencies]
+serde = { version = "1", default-features = false, features = ["std"] }
+log = { version = "0.4", default-features = false }
+"#;
+        let member = r#"
+[dependencies]
+serde = { workspace = true, features = ["derive"] }
+log = { workspace = true, features = ["alloc"] }
+
+[target.'cfg(unix)'.dependencies]
+libc = { version = "0.2", default-features = false, features = ["std"] }
+
+[dev-dependencies]
+tokio = { version = "1", default-features = false, features = ["std"] }
+"#;
+
+        assert_eq!(
+            messages(&find_no_std_violations(member, workspace, &["std".to_string(), "alloc".to_string()]).unwrap()),
+            [
+                "'log' in [dependencies]: enables \"alloc\" in a no_std member",
+                "'serde' in [dependencies]: enables \"std\" through [workspace.dependencies] in a no_std member",
+                "'libc' in [target.'cfg(unix)'.dependencies]: enables \"std\" in a no_std member",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_missing_required_features() {
+        let content = r#"
+[workspace.dependencies]
+tokio = { version = "1", default-features = false, features = ["macros"] }
+serde = { version = "1", default-features = false }
+
+[dependencies]
+tokio = { workspace = true }
+runtime = { package = "tokio", version = "1", default-features = false, features = ["rt", "net"] }
+hyper = { version = "1", default-features = false }
+"#;
+        let required = BTreeMap::from([
+            ("tokio".to_string(), vec!["rt".to_string(), "net".to_string()]),
+            ("hyper".to_string(), vec!["client".to_string()]),
+        ]);
+
+        assert_eq!(
+            messages(&find_missing_required_features(content, true, &required).unwrap()),
+            ["'tokio' in [workspace.dependencies]: disables default features without enabling required features \"rt\", \"net\""]
+        );
+        assert_eq!(
+            messages(&find_missing_required_features(content, false, &required).unwrap()),
+            ["'hyper' in [dependencies]: disables default features without enabling required features \"client\""]
+        );
+    }
+
+    #[test]
+    fn test_find_feature_budget_overruns() {
+        let workspace = r#"
+[workspace.dependencies]
+tokio = { version = "1", default-features = false, features = ["rt", "net", "time"] }
+serde = { version = "1", default-features = false, features = ["derive", "std", "rc"] }
+"#;
+        let member = r#"
+[dependencies]
+tokio = { workspace = true, features = ["macros", "rt"] }
+"#;
+        let per_crate = BTreeMap::from([("tokio".to_string(), 3)]);
+
+        assert_eq!(
+            messages(&find_feature_budget_overruns(workspace, None, Some(2), &per_crate).unwrap()),
+            ["'serde' in [workspace.dependencies]: enables 3 features, more than the budget of 2"]
+        );
+        assert_eq!(
+            messages(&find_feature_budget_overruns(member, Some(workspace), None, &per_crate).unwrap()),
+            ["'tokio' in [dependencies]: enables 4 features, more than the budget of 3"]
+        );
+        assert!(
+            find_feature_budget_overruns(workspace, None, None, &BTreeMap::new())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_find_duplicate_features_in_member() {
+        let workspace = r#"
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false, features = ["derive"] }
+"#;
+        let member = r#"
+[dependencies]
+serde = { workspace = true, features = ["derive", "std"] }
+log = { version = "0.4", features = ["std", "std"] }
+"#;
+
+        let problems = find_duplicate_features(member, Some(workspace)).unwrap();
+        let messages: Vec<_> = problems.iter().map(|problem| problem.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "'log' in [dependencies]: lists \"std\" more than once",
+                "'serde' in [dependencies]: lists \"derive\" more than once, counting the ones inherited from [workspace.dependencies]",
+            ]
+        );
+    }
+}
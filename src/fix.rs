@@ -0,0 +1,403 @@
+use anyhow::{Context, Result, bail};
+use toml_edit::{DocumentMut, InlineTable, Item, Key, TableLike, Value};
+
+use crate::rules::{DEFAULT_FEATURES, Rule, UNUSED_WORKSPACE_DEPENDENCIES};
+
+/// Rewrites a dependency entry so it declares `default-features = false`
+///
+/// The table path lists the keys leading to the dependency table, for example
+/// `["workspace", "dependencies"]`. Simple version strings are turned into inline tables, and the
+/// formatting and comments of the rest of the document are preserved.
+pub fn set_default_features_false(content: &str, table_path: &[&str], name: &str) -> Result<String> {
+    let mut document: DocumentMut = content.parse().context("Failed to parse Cargo.toml")?;
+    let entry = dependency_entry(&mut document, table_path, name)?;
+
+    match entry {
+        Item::Value(Value::String(version)) => {
+            let decor = version.decor().clone();
+            let mut version = version.clone();
+            version.decor_mut().clear();
+
+            let mut inline = InlineTable::new();
+            let _ = inline.insert("version", Value::String(version));
+            let _ = inline.insert("default-features", Value::from(false));
+            inline.fmt();
+
+            let mut value = Value::InlineTable(inline);
+            *value.decor_mut() = decor;
+            *entry = Item::Value(value);
+        }
+
+        Item::Value(Value::InlineTable(inline)) => {
+            // The space before the closing brace is stored on the last value, so move it past the new key
+            if let Some((_, last)) = inline.iter_mut().last() {
+                last.decor_mut().set_suffix("");
+            }
+
+            let _ = inline.insert("default-features", Value::from(false));
+        }
+
+        Item::Table(table) => {
+            let _ = table.insert("default-features", toml_edit::value(false));
+        }
+
+        _ => bail!("Dependency '{name}' can't be fixed automatically"),
+    }
+
+    Ok(document.to_string())
+}
+
+/// Renames the legacy `default_features` key of a dependency entry to `default-features`
+///
+/// The key keeps its position and value. When the entry already has a `default-features` key, which
+/// Cargo gives precedence to, the legacy key is removed instead.
+pub fn rename_legacy_default_features(content: &str, table_path: &[&str], name: &str) -> Result<String> {
+    const LEGACY: &str = "default_features";
+    const HYPHENATED: &str = "default-features";
+
+    let mut document: DocumentMut = content.parse().context("Failed to parse Cargo.toml")?;
+    let entry = dependency_entry(&mut document, table_path, name)?;
+    let order: Vec<String> = entry
+        .as_table_like()
+        .with_context(|| format!("Dependency '{name}' can't be fixed automatically"))?
+        .iter()
+        .map(|(key, _)| if key == LEGACY { HYPHENATED.to_string() } else { key.to_string() })
+        .collect();
+    let legacy_last = entry
+        .as_table_like()
+        .and_then(|table| table.iter().last())
+        .is_some_and(|(key, _)| key == LEGACY);
+    let position = |key: &Key| order.iter().position(|ordered| ordered == key.get());
+
+    match entry {
+        Item::Value(Value::InlineTable(inline)) => {
+            let (key, value) = inline
+                .remove_entry(LEGACY)
+                .with_context(|| format!("Dependency '{name}' has no {LEGACY} key"))?;
+            if !inline.contains_key(HYPHENATED) {
+                let _ = inline.insert_formatted(&renamed_key(&key, HYPHENATED), value);
+                inline.sort_values_by(|a, _, b, _| position(a).cmp(&position(b)));
+            } else if legacy_last && let Some((_, last)) = inline.iter_mut().last() {
+                // The space before the closing brace was stored on the removed value
+                last.decor_mut().set_suffix(value.decor().suffix().cloned().unwrap_or_default());
+            }
+        }
+
+        Item::Table(table) => {
+            let (key, item) = table
+                .remove_entry(LEGACY)
+                .with_context(|| format!("Dependency '{name}' has no {LEGACY} key"))?;
+            if !table.contains_key(HYPHENATED) {
+                let _ = table.insert_formatted(&renamed_key(&key, HYPHENATED), item);
+                table.sort_values_by(|a, _, b, _| position(a).cmp(&position(b)));
+            }
+        }
+
+        _ => bail!("Dependency '{name}' can't be fixed automatically"),
+    }
+
+    Ok(document.to_string())
+}
+
+/// Rewrites a member's dependency entry so it inherits from `[workspace.dependencies]`
+///
+/// The entry becomes `workspace = true`, keeping only the `features` and `optional` keys since the
+/// rest of the declaration now comes from the workspace. Formatting and comments are preserved.
+pub fn inherit_from_workspace(content: &str, table_path: &[&str], name: &str) -> Result<String> {
+    const KEPT_KEYS: [&str; 2] = ["features", "optional"];
+
+    let mut document: DocumentMut = content.parse().context("Failed to parse Cargo.toml")?;
+    let entry = dependency_entry(&mut document, table_path, name)?;
+
+    match entry {
+        Item::Value(value @ (Value::String(_) | Value::InlineTable(_))) => {
+            let mut inline = InlineTable::new();
+            let _ = inline.insert("workspace", Value::from(true));
+            if let Value::InlineTable(original) = &*value {
+                for key in KEPT_KEYS {
+                    if let Some(kept) = original.get(key) {
+                        let mut kept = kept.clone();
+                        kept.decor_mut().clear();
+                        let _ = inline.insert(key, kept);
+                    }
+                }
+            }
+            inline.fmt();
+
+            let decor = value.decor().clone();
+            *value = Value::InlineTable(inline);
+            *value.decor_mut() = decor;
+        }
+
+        Item::Table(table) => {
+            table.retain(|key, _| KEPT_KEYS.contains(&key));
+            let _ = table.insert("workspace", toml_edit::value(true));
+        }
+
+        _ => bail!("Dependency '{name}' can't be fixed automatically"),
+    }
+
+    Ok(document.to_string())
+}
+
+/// Removes the duplicate features of a dependency entry, keeping the first occurrence of each
+///
+/// When the entry inherits from the workspace, the features already enabled by the workspace entry,
+/// given in `workspace_features`, are removed as well.
+pub fn dedup_features(content: &str, table_path: &[&str], name: &str, workspace_features: &[&str]) -> Result<String> {
+    let mut document: DocumentMut = content.parse().context("Failed to parse Cargo.toml")?;
+    let entry = dependency_entry(&mut document, table_path, name)?
+        .as_table_like_mut()
+        .with_context(|| format!("Dependency '{name}' can't be fixed automatically"))?;
+
+    let inherits = entry.get("workspace").and_then(Item::as_bool) == Some(true);
+    let features = entry
+        .get_mut("features")
+        .and_then(Item::as_array_mut)
+        .with_context(|| format!("Dependency '{name}' has no features array"))?;
+
+    let mut seen: Vec<String> = if inherits {
+        workspace_features.iter().map(ToString::to_string).collect()
+    } else {
+        Vec::new()
+    };
+
+    let first_prefix = features.get(0).and_then(|first| first.decor().prefix().cloned());
+    features.retain(|feature| match feature.as_str() {
+        Some(feature) if seen.iter().any(|seen| seen == feature) => false,
+        Some(feature) => {
+            seen.push(feature.to_string());
+            true
+        }
+        None => true,
+    });
+
+    // Keep the array's layout when its first element was removed
+    if let Some(first) = features.get_mut(0) {
+        first.decor_mut().set_prefix(first_prefix.unwrap_or_default());
+    }
+
+    Ok(document.to_string())
+}
+
+/// Removes a dependency entry, along with the comments attached to it
+pub fn remove_dependency(content: &str, table_path: &[&str], name: &str) -> Result<String> {
+    let mut document: DocumentMut = content.parse().context("Failed to parse Cargo.toml")?;
+    let _ = dependency_table(&mut document, table_path)?
+        .remove(name)
+        .with_context(|| format!("Dependency '{name}' not found in [{}]", table_path.join(".")))?;
+    Ok(document.to_string())
+}
+
+/// Applies the automatic fix of a rule to an entry of `[workspace.dependencies]`
+///
+/// # Returns
+///
+/// What the fix does along with the fixed content, or `None` when the rule has no automatic fix for
+/// such entries.
+pub fn fix_workspace_dependency(rule: &Rule, content: &str, name: &str) -> Result<Option<(&'static str, String)>> {
+    const WORKSPACE_DEPENDENCIES: &[&str] = &["workspace", "dependencies"];
+
+    if rule == &DEFAULT_FEATURES {
+        let fixed = set_default_features_false(content, WORKSPACE_DEPENDENCIES, name)?;
+        Ok(Some(("Set default-features = false", fixed)))
+    } else if rule == &UNUSED_WORKSPACE_DEPENDENCIES {
+        let fixed = remove_dependency(content, WORKSPACE_DEPENDENCIES, name)?;
+        Ok(Some(("Remove the unused dependency", fixed)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Sorts the entries of a dependency table alphabetically
+///
+/// Comments attached to an entry, such as the lines right above it, move along with it. Dependencies
+/// declared in their own sub-tables keep their place.
+pub fn sort_dependencies(content: &str, table_path: &[&str]) -> Result<String> {
+    let mut document: DocumentMut = content.parse().context("Failed to parse Cargo.toml")?;
+    dependency_table(&mut document, table_path)?.sort_values();
+    Ok(document.to_string())
+}
+
+/// Creates a key with a new name, keeping the whitespace and comments around the original one
+fn renamed_key(key: &Key, name: &str) -> Key {
+    let mut renamed = Key::new(name);
+    *renamed.leaf_decor_mut() = key.leaf_decor().clone();
+    *renamed.dotted_decor_mut() = key.dotted_decor().clone();
+    renamed
+}
+
+/// Finds a dependency table in a document, given the keys leading to it
+fn dependency_table<'a>(document: &'a mut DocumentMut, table_path: &[&str]) -> Result<&'a mut dyn TableLike> {
+    let mut table: &mut dyn TableLike = document.as_table_mut();
+    for segment in table_path {
+        table = table
+            .get_mut(segment)
+            .and_then(Item::as_table_like_mut)
+            .with_context(|| format!("No [{}] section found", table_path.join(".")))?;
+    }
+
+    Ok(table)
+}
+
+/// Finds a dependency entry in a document, given the keys leading to its dependency table
+fn dependency_entry<'a>(document: &'a mut DocumentMut, table_path: &[&str], name: &str) -> Result<&'a mut Item> {
+    dependency_table(document, table_path)?
+        .get_mut(name)
+        .with_context(|| format!("Dependency '{name}' not found in [{}]", table_path.join(".")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &[&str] = &["workspace", "dependencies"];
+
+    #[test]
+    fn test_fix_simple_version_string() {
+        let content = "[workspace.dependencies]\nserde = \"1.0\" # serialization\n";
+        let fixed = set_default_features_false(content, TABLE, "serde").unwrap();
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies]\nserde = { version = \"1.0\", default-features = false } # serialization\n"
+        );
+    }
+
+    #[test]
+    fn test_fix_inline_table() {
+        let content = "[workspace.dependencies]\ntokio = { version = \"1.0\", default-features = true }\nregex = { version = \"1.0\" }\n";
+        let fixed = set_default_features_false(content, TABLE, "tokio").unwrap();
+        let fixed = set_default_features_false(&fixed, TABLE, "regex").unwrap();
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies]\ntokio = { version = \"1.0\", default-features = false }\nregex = { version = \"1.0\", default-features = false }\n"
+        );
+    }
+
+    #[test]
+    fn test_fix_standard_table() {
+        let content = "[workspace.dependencies.serde]\nversion = \"1.0\"\n";
+        let fixed = set_default_features_false(content, TABLE, "serde").unwrap();
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies.serde]\nversion = \"1.0\"\ndefault-features = false\n"
+        );
+    }
+
+    #[test]
+    fn test_inherit_from_workspace() {
+        let content = "[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"], optional = true } # ser\nlog = \"0.4\"\n\n[dev-dependencies.tokio]\nversion = \"1.0\"\nfeatures = [\"rt\"]\n";
+        let fixed = inherit_from_workspace(content, &["dependencies"], "serde").unwrap();
+        let fixed = inherit_from_workspace(&fixed, &["dependencies"], "log").unwrap();
+        let fixed = inherit_from_workspace(&fixed, &["dev-dependencies"], "tokio").unwrap();
+        assert_eq!(
+            fixed,
+            "[dependencies]\nserde = { workspace = true, features = [\"derive\"], optional = true } # ser\nlog = { workspace = true }\n\n[dev-dependencies.tokio]\nfeatures = [\"rt\"]\nworkspace = true\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_dependencies() {
+        let content = "[workspace.dependencies]\n# Async runtime\ntokio = \"1.0\"\nanyhow = \"1.0\" # errors\n\n[workspace.dependencies.serde]\nversion = \"1.0\"\n";
+        let sorted = sort_dependencies(content, TABLE).unwrap();
+        assert_eq!(
+            sorted,
+            "[workspace.dependencies]\nanyhow = \"1.0\" # errors\n# Async runtime\ntokio = \"1.0\"\n\n[workspace.dependencies.serde]\nversion = \"1.0\"\n"
+        );
+    }
+
+    #[test]
+    fn test_dedup_features() {
+        let content = "[workspace.dependencies]\ntokio = { version = \"1.0\", features = [\"rt\", \"macros\", \"rt\"] }\n";
+        let fixed = dedup_features(content, TABLE, "tokio", &[]).unwrap();
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies]\ntokio = { version = \"1.0\", features = [\"rt\", \"macros\"] }\n"
+        );
+
+        let content = "[dependencies]\nserde = { workspace = true, features = [\"derive\", \"std\"] }\n";
+        let fixed = dedup_features(content, &["dependencies"], "serde", &["derive"]).unwrap();
+        assert_eq!(fixed, "[dependencies]\nserde = { workspace = true, features = [\"std\"] }\n");
+
+        let content = "[dependencies.serde]\nworkspace = true\nfeatures = [\n    \"derive\",\n    \"std\",\n    \"std\",\n]\n";
+        let fixed = dedup_features(content, &["dependencies"], "serde", &["derive"]).unwrap();
+        assert_eq!(fixed, "[dependencies.serde]\nworkspace = true\nfeatures = [\n    \"std\",\n]\n");
+    }
+
+    #[test]
+    fn test_rename_legacy_default_features() {
+        let content =
+            "[workspace.dependencies]\nserde = { version = \"1.0\", default_features = false, features = [\"derive\"] } # serialization\n";
+        let fixed = rename_legacy_default_features(content, TABLE, "serde").unwrap();
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies]\nserde = { version = \"1.0\", default-features = false, features = [\"derive\"] } # serialization\n"
+        );
+
+        let content = "[workspace.dependencies.tokio]\nversion = \"1.0\"\ndefault_features = false # keep it lean\nfeatures = [\"rt\"]\n";
+        let fixed = rename_legacy_default_features(content, TABLE, "tokio").unwrap();
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies.tokio]\nversion = \"1.0\"\ndefault-features = false # keep it lean\nfeatures = [\"rt\"]\n"
+        );
+
+        let content = "[workspace.dependencies]\nregex = { version = \"1.0\", default-features = false, default_features = true }\n";
+        let fixed = rename_legacy_default_features(content, TABLE, "regex").unwrap();
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies]\nregex = { version = \"1.0\", default-features = false }\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_dependency() {
+        let content = "[workspace.dependencies]\n# Serialization\nserde = { version = \"1.0\" }\ntokio = { version = \"1.0\" }\n\n[workspace.dependencies.regex]\nversion = \"1.0\"\n";
+        let fixed = remove_dependency(content, TABLE, "serde").unwrap();
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies]\ntokio = { version = \"1.0\" }\n\n[workspace.dependencies.regex]\nversion = \"1.0\"\n"
+        );
+
+        let fixed = remove_dependency(&fixed, TABLE, "regex").unwrap();
+        assert_eq!(fixed, "[workspace.dependencies]\ntokio = { version = \"1.0\" }\n");
+
+        let err = remove_dependency(&fixed, TABLE, "regex").unwrap_err();
+        assert!(err.to_string().contains("Dependency 'regex' not found"));
+    }
+
+    #[test]
+    fn test_fix_workspace_dependency() {
+        let content = "[workspace.dependencies]\nserde = \"1.0\"\n";
+        let (description, fixed) = fix_workspace_dependency(&DEFAULT_FEATURES, content, "serde").unwrap().unwrap();
+        assert_eq!(description, "Set default-features = false");
+        assert_eq!(
+            fixed,
+            "[workspace.dependencies]\nserde = { version = \"1.0\", default-features = false }\n"
+        );
+
+        let (_, fixed) = fix_workspace_dependency(&UNUSED_WORKSPACE_DEPENDENCIES, content, "serde")
+            .unwrap()
+            .unwrap();
+        assert_eq!(fixed, "[workspace.dependencies]\n");
+
+        assert!(
+            fix_workspace_dependency(&crate::rules::SORTED_DEPENDENCIES, content, "serde")
+                .unwrap()
+                .is_none()
+        );
+        let _ = fix_workspace_dependency(&DEFAULT_FEATURES, content, "tokio").unwrap_err();
+    }
+
+    #[test]
+    fn test_fix_errors() {
+        let content = "[workspace.dependencies]\nodd = [\"1.0\"]\n";
+        let err = set_default_features_false(content, TABLE, "odd").unwrap_err();
+        assert!(err.to_string().contains("can't be fixed automatically"));
+
+        let err = set_default_features_false(content, TABLE, "missing").unwrap_err();
+        assert!(err.to_string().contains("Dependency 'missing' not found"));
+
+        let err = set_default_features_false(content, &["dependencies"], "odd").unwrap_err();
+        assert!(err.to_string().contains("No [dependencies] section found"));
+    }
+}
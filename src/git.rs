@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, bail};
+use tempfile::TempDir;
+
+/// Runs git with the given arguments, in the given directory or the current one, and returns its output
+pub fn git(dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        let _ = command.current_dir(dir);
+    }
+
+    let output = command.args(args).output().context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    String::from_utf8(output.stdout).with_context(|| format!("git {} produced invalid UTF-8", args.join(" ")))
+}
+
+/// Returns the root of the repository holding the given directory, or the current one
+pub fn toplevel(dir: Option<&Path>) -> Result<PathBuf> {
+    let toplevel = git(dir, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(toplevel.trim_end()))
+}
+
+/// Returns the files of the current repository that changed since the given revision
+///
+/// Both committed changes and changes still in the working tree are included, along with the untracked
+/// files git doesn't ignore, such as the manifest of a workspace that was just created. The returned
+/// paths are absolute, which keeps them usable from any directory.
+pub fn changed_files(rev: &str) -> Result<Vec<PathBuf>> {
+    let toplevel = toplevel(None)?;
+    let changed = git(None, &["diff", "--name-only", rev, "--"])?;
+    let untracked = git(Some(&toplevel), &["ls-files", "--others", "--exclude-standard"])?;
+    Ok(changed.lines().chain(untracked.lines()).map(|file| toplevel.join(file)).collect())
+}
+
+/// Returns the content a file had at the given revision, or `None` when it didn't exist then
+pub fn show_file(rev: &str, path: &Path) -> Result<Option<String>> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid file path {}", path.display()))?
+        .to_string_lossy();
+
+    // Verify the revision first, so a bad revision isn't mistaken for a missing file
+    let _ = git(dir, &["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+        .with_context(|| format!("Unknown git revision '{rev}'"))?;
+
+    Ok(git(dir, &["show", &format!("{rev}:./{file_name}")]).ok())
+}
+
+/// Copies of repositories as they were at a git revision, shared by the checks of a run
+///
+/// Each repository is exported once, the first time it's asked for, and the copies are removed from
+/// disk when this is dropped.
+#[derive(Debug)]
+pub struct RevisionExports {
+    rev: String,
+    exports: Mutex<HashMap<PathBuf, TempDir>>,
+}
+
+impl RevisionExports {
+    /// Prepares exporting repositories as they were at the given revision, which nothing is done for yet
+    pub fn new(rev: &str) -> Self {
+        Self {
+            rev: rev.to_string(),
+            exports: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the directory holding the repository rooted at `toplevel` as it was at the revision
+    ///
+    /// The directory holds an empty `.git` directory, so configuration files are looked up in parent
+    /// directories up to its root, like they are in the repository.
+    pub fn export(&self, toplevel: &Path) -> Result<PathBuf> {
+        let exports = || self.exports.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(export) = exports().get(toplevel) {
+            return Ok(export.path().to_path_buf());
+        }
+
+        let export = TempDir::new().context("Failed to create a temporary directory")?;
+        export_revision(&self.rev, toplevel, export.path())?;
+        std::fs::create_dir(export.path().join(".git")).context("Failed to mark the root of the exported revision")?;
+        Ok(exports().entry(toplevel.to_path_buf()).or_insert(export).path().to_path_buf())
+    }
+}
+
+/// Writes the files of the repository rooted at `toplevel`, as they were at the given revision, into `dest`
+///
+/// The archive produced by git is extracted as it's produced, rather than being held in memory.
+fn export_revision(rev: &str, toplevel: &Path, dest: &Path) -> Result<()> {
+    let _ = git(Some(toplevel), &["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+        .with_context(|| format!("Unknown git revision '{rev}'"))?;
+
+    let mut child = Command::new("git")
+        .current_dir(toplevel)
+        .args(["archive", "--format=tar", rev])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run git")?;
+    let archive = child.stdout.take().context("Failed to read the output of git")?;
+    let unpacked = tar::Archive::new(archive).unpack(dest);
+
+    let output = child.wait_with_output().context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("git archive {rev} failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    unpacked.with_context(|| format!("Failed to extract revision '{rev}' into {}", dest.display()))
+}
@@ -0,0 +1,133 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+
+use crate::git::git;
+
+/// Marker identifying hooks written by this tool, so they can be updated without --force
+const HOOK_MARKER: &str = "# Installed by cargo-ensure-no-default-features";
+
+/// The git hooks that can be installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookKind {
+    /// Check the workspaces owning the staged manifests before each commit
+    PreCommit,
+
+    /// Check the whole workspace before each push
+    PrePush,
+}
+
+impl HookKind {
+    const fn file_name(self) -> &'static str {
+        match self {
+            Self::PreCommit => "pre-commit",
+            Self::PrePush => "pre-push",
+        }
+    }
+}
+
+/// Writes a git hook running the tool with the given arguments into the current repository
+///
+/// An existing hook is only replaced when it was written by this tool or when `force` is set.
+///
+/// # Returns
+///
+/// The path of the installed hook.
+pub fn install_hook(kind: HookKind, args: &[String], force: bool) -> Result<PathBuf> {
+    let hook_path = hooks_dir()?.join(kind.file_name());
+    if hook_path.exists() && !force {
+        let existing = std::fs::read_to_string(&hook_path).unwrap_or_default();
+        if !existing.contains(HOOK_MARKER) {
+            bail!(
+                "A {} hook already exists at {}, use --force to overwrite it",
+                kind.file_name(),
+                hook_path.display()
+            );
+        }
+    }
+
+    if let Some(dir) = hook_path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    std::fs::write(&hook_path, hook_script(kind, args)).with_context(|| format!("Failed to write {}", hook_path.display()))?;
+    make_executable(&hook_path)?;
+    Ok(hook_path)
+}
+
+/// Returns the directory git runs hooks from, honoring `core.hooksPath`
+fn hooks_dir() -> Result<PathBuf> {
+    let dir = git(None, &["rev-parse", "--git-path", "hooks"]).context("Not in a git repository")?;
+    Ok(PathBuf::from(dir.trim_end()))
+}
+
+/// Generates the shell script of a hook
+fn hook_script(kind: HookKind, args: &[String]) -> String {
+    let command = core::iter::once("exec cargo ensure-no-default-features".to_string())
+        .chain(args.iter().map(|arg| shell_quote(arg)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    match kind {
+        HookKind::PreCommit => format!(
+            "#!/bin/sh\n{HOOK_MARKER}\n\
+             files=$(git diff --cached --name-only --diff-filter=ACMR -- '*Cargo.toml' '*ensure-no-default-features.toml')\n\
+             [ -z \"$files\" ] && exit 0\n\
+             {command} --files $files\n"
+        ),
+        HookKind::PrePush => format!("#!/bin/sh\n{HOOK_MARKER}\n{command}\n"),
+    }
+}
+
+/// Quotes an argument for a POSIX shell when it contains anything but safe characters
+fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ',' | '=' | ':'));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+#[expect(clippy::unnecessary_wraps, reason = "Matches the signature of the unix version")]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_script() {
+        let script = hook_script(HookKind::PrePush, &["--exceptions".to_string(), "serde,tokio".to_string()]);
+        assert_eq!(
+            script,
+            format!("#!/bin/sh\n{HOOK_MARKER}\nexec cargo ensure-no-default-features --exceptions serde,tokio\n")
+        );
+
+        let script = hook_script(HookKind::PreCommit, &[]);
+        assert!(script.contains("git diff --cached --name-only"));
+        assert!(script.ends_with("exec cargo ensure-no-default-features --files $files\n"));
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("--follow-path-deps"), "--follow-path-deps");
+        assert_eq!(shell_quote("my dir"), "'my dir'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
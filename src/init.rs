@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use crate::config::{CONFIG_FILE_NAMES, METADATA_TABLE};
+use crate::rules::DEFAULT_FEATURES;
+use crate::validation::failing_workspace_dependencies;
+
+/// Writes a starter configuration for the workspace whose root manifest is at the given path
+///
+/// The settings go into a configuration file next to the manifest, or into the manifest's
+/// `[workspace.metadata.ensure-no-default-features]` table when `metadata` is set. The dependencies
+/// currently failing the default-features check are listed as exceptions, so the check passes right
+/// away and the list can be worked through over time. An existing configuration file is only replaced
+/// when `force` is set, while an existing metadata table is never replaced.
+///
+/// # Returns
+///
+/// The path of the file written, along with the number of exceptions listed.
+pub fn init(manifest_path: &Path, metadata: bool, force: bool) -> Result<(PathBuf, usize)> {
+    let content = std::fs::read_to_string(manifest_path).with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    let failing = failing_workspace_dependencies(&content, &[], false)?;
+
+    if metadata {
+        let parsed: toml::Table = toml::from_str(&content).with_context(|| format!("Failed to parse {}", manifest_path.display()))?;
+        if parsed
+            .get("workspace")
+            .and_then(|workspace| workspace.get("metadata"))
+            .and_then(|metadata| metadata.get(METADATA_TABLE))
+            .is_some()
+        {
+            bail!(
+                "{} already has a [workspace.metadata.{METADATA_TABLE}] table",
+                manifest_path.display()
+            );
+        }
+
+        let settings = starter_settings(&failing, &format!("workspace.metadata.{METADATA_TABLE}"));
+        let separator = if content.ends_with('\n') { "\n" } else { "\n\n" };
+        std::fs::write(manifest_path, format!("{content}{separator}{settings}"))
+            .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+        return Ok((manifest_path.to_path_buf(), failing.len()));
+    }
+
+    let root = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+    if !force && let Some(existing) = CONFIG_FILE_NAMES.iter().map(|name| root.join(name)).find(|path| path.is_file()) {
+        bail!(
+            "A configuration file already exists at {}, use --force to overwrite it",
+            existing.display()
+        );
+    }
+
+    let config_path = root.join(CONFIG_FILE_NAMES[0]);
+    std::fs::write(&config_path, starter_settings(&failing, "")).with_context(|| format!("Failed to write {}", config_path.display()))?;
+    Ok((config_path, failing.len()))
+}
+
+/// Commented settings opening every starter configuration
+const STARTER_SETTINGS: &str = "\
+# Settings of cargo-ensure-no-default-features, see `cargo ensure-no-default-features --help`
+# and the README for the complete list.
+
+# Rules to run on top of the ones enabled by default, and rules not to run, by ID or name.
+# `cargo ensure-no-default-features --list-rules` lists every rule.
+# enable-rules = [\"ENDF026\"]
+# disable-rules = []
+
+# Features no dependency may enable.
+# denied-features = [\"vendored\"]
+
+# Dependencies excluded from specific rules, keyed by rule ID or name, or by \"*\" for every rule.
+";
+
+/// Generates the commented starter settings, listing the given dependencies as exceptions
+///
+/// `table` is the name of the table holding the settings, empty for a configuration file.
+fn starter_settings(failing: &[String], table: &str) -> String {
+    let (header, prefix) = if table.is_empty() {
+        (String::new(), String::new())
+    } else {
+        (format!("[{table}]\n"), format!("{table}."))
+    };
+
+    let exceptions = if failing.is_empty() {
+        format!("# [{prefix}exceptions]\n# {} = [\"serde\"]\n", DEFAULT_FEATURES.name)
+    } else {
+        format!(
+            "# These dependencies were failing the default-features check when this configuration was\n\
+             # created. Remove them as they're fixed.\n{}",
+            exceptions_table(failing, &prefix)
+        )
+    };
+
+    format!("{header}{STARTER_SETTINGS}{exceptions}")
+}
+
+/// Formats the dependencies failing the default-features check in the given workspaces as exceptions
+///
+/// The result is an `[exceptions]` table ready to be pasted into a configuration file, listing each
+/// failing dependency once, in alphabetical order.
+pub fn emit_exceptions(manifest_paths: &[PathBuf]) -> Result<(String, usize)> {
+    let mut failing = BTreeSet::new();
+    for manifest_path in manifest_paths {
+        let content = std::fs::read_to_string(manifest_path).with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+        failing.extend(failing_workspace_dependencies(&content, &[], false)?);
+    }
+
+    let failing: Vec<_> = failing.into_iter().collect();
+    Ok((exceptions_table(&failing, ""), failing.len()))
+}
+
+/// Formats an `exceptions` table excluding the given dependencies from the default-features check
+///
+/// `prefix` is prepended to the table's name, such as `workspace.metadata.ensure-no-default-features.`.
+fn exceptions_table(names: &[String], prefix: &str) -> String {
+    let names: Vec<_> = names
+        .iter()
+        .map(|name| format!("    {},\n", toml::Value::String(name.clone())))
+        .collect();
+    format!("[{prefix}exceptions]\n{} = [\n{}]\n", DEFAULT_FEATURES.name, names.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    const MANIFEST: &str =
+        "[workspace]\n\n[workspace.dependencies]\nserde = \"1.0\"\ntokio = { version = \"1\", default-features = false }\n";
+
+    #[test]
+    fn test_init_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, MANIFEST).unwrap();
+
+        let (config_path, count) = init(&manifest_path, false, false).unwrap();
+        assert_eq!(config_path, dir.path().join("ensure-no-default-features.toml"));
+        assert_eq!(count, 1);
+
+        let config = Config::discover(&manifest_path, None).unwrap();
+        assert_eq!(config.rule_exceptions().unwrap()[DEFAULT_FEATURES.id], ["serde"]);
+
+        let err = init(&manifest_path, false, false).unwrap_err();
+        assert!(err.to_string().contains("use --force to overwrite it"));
+
+        std::fs::write(&manifest_path, "[workspace]\n\n[workspace.dependencies]\n").unwrap();
+        let (_, count) = init(&manifest_path, false, true).unwrap();
+        assert_eq!(count, 0);
+        assert!(Config::discover(&manifest_path, None).unwrap().exceptions.is_empty());
+    }
+
+    #[test]
+    fn test_emit_exceptions() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first.toml");
+        let second = dir.path().join("second.toml");
+        std::fs::write(&first, MANIFEST).unwrap();
+        std::fs::write(&second, "[workspace]\n\n[workspace.dependencies]\nclap = \"4\"\nserde = \"1.0\"\n").unwrap();
+
+        let (table, count) = emit_exceptions(&[first, second]).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(table, "[exceptions]\ndefault-features = [\n    \"clap\",\n    \"serde\",\n]\n");
+
+        let config: Config = toml::from_str(&table).unwrap();
+        assert_eq!(config.rule_exceptions().unwrap()[DEFAULT_FEATURES.id], ["clap", "serde"]);
+    }
+
+    #[test]
+    fn test_init_metadata_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, MANIFEST).unwrap();
+
+        let (path, count) = init(&manifest_path, true, false).unwrap();
+        assert_eq!(path, manifest_path);
+        assert_eq!(count, 1);
+
+        let content = std::fs::read_to_string(&manifest_path).unwrap();
+        assert!(content.starts_with(MANIFEST));
+        assert!(content.contains("\n[workspace.metadata.ensure-no-default-features]\n"));
+
+        let config = Config::discover(&manifest_path, None).unwrap();
+        assert_eq!(config.rule_exceptions().unwrap()[DEFAULT_FEATURES.id], ["serde"]);
+
+        let err = init(&manifest_path, true, true).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("already has a [workspace.metadata.ensure-no-default-features] table")
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::cache::ManifestCache;
+use crate::features::features;
+use crate::metadata::cargo_metadata;
+use crate::rules::Violation;
+use crate::validation::{ExceptionPattern, dependency_package_name, enables_default_features, is_excepted};
+
+/// Finds dependencies declared with `default-features = false` whose default features are enabled anyway
+///
+/// Cargo.lock doesn't record features, so the dependency graph resolved from it is obtained through
+/// `cargo metadata`. A warning is produced for each package declared in `[workspace.dependencies]`
+/// with `default-features = false` whose resolved feature set includes `default`, meaning the default
+/// features leak in from elsewhere in the graph.
+pub fn find_leaked_defaults(manifest_path: &Path, content: &str, cache: Option<&ManifestCache>) -> Result<Vec<Violation>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let Some(deps_table) = parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let metadata = cargo_metadata(manifest_path, false, cache)?;
+    Ok(leaked_defaults(deps_table, &metadata))
+}
+
+/// Compares the workspace's declared intent against the output of `cargo metadata`
+fn leaked_defaults(deps_table: &toml::Table, metadata: &Value) -> Vec<Violation> {
+    let packages = array(metadata, "/packages");
+    let nodes = array(metadata, "/resolve/nodes");
+
+    let mut warnings = Vec::new();
+    for (name, value) in deps_table {
+        if enables_default_features(value) {
+            continue;
+        }
+
+        let package_name = dependency_package_name(name, value);
+        for node in nodes {
+            let Some(package) = packages.iter().find(|package| package.get("id") == node.get("id")) else {
+                continue;
+            };
+
+            let enables_default = array(node, "/features").iter().any(|feature| feature == "default");
+            if str_field(package, "name") != package_name || !enables_default {
+                continue;
+            }
+
+            let consumers: BTreeSet<_> = packages
+                .iter()
+                .filter(|consumer| {
+                    array(consumer, "/dependencies").iter().any(|dependency| {
+                        str_field(dependency, "name") == package_name
+                            && dependency.get("uses_default_features").and_then(Value::as_bool) == Some(true)
+                    })
+                })
+                .map(|consumer| format!("{} {}", str_field(consumer, "name"), str_field(consumer, "version")))
+                .collect();
+
+            let mut warning = format!(
+                "'{name}' is declared with default-features = false, but the resolved dependency graph enables the default features of {package_name} {}",
+                str_field(package, "version")
+            );
+            if !consumers.is_empty() {
+                warning = format!("{warning} through {}", consumers.into_iter().collect::<Vec<_>>().join(", "));
+            }
+
+            warnings.push(Violation::workspace_dependency(name, warning));
+        }
+    }
+
+    warnings
+}
+
+/// Finds dependencies declared with `default-features = false` whose `features` list is exactly the
+/// dependency's default feature set
+///
+/// The default feature sets are read from the package metadata reported by `cargo metadata`. Such
+/// declarations build the same thing as enabling the defaults, while suggesting a minimized build and
+/// drifting from upstream whenever its defaults change.
+pub fn find_redundant_default_features(manifest_path: &Path, content: &str, cache: Option<&ManifestCache>) -> Result<Vec<Violation>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let Some(deps_table) = parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let metadata = cargo_metadata(manifest_path, false, cache)?;
+    Ok(redundant_default_features(deps_table, &metadata))
+}
+
+/// Compares the features listed in `[workspace.dependencies]` with the packages' default feature sets
+fn redundant_default_features(deps_table: &toml::Table, metadata: &Value) -> Vec<Violation> {
+    let packages = array(metadata, "/packages");
+
+    let mut warnings = Vec::new();
+    for (name, value) in deps_table {
+        let listed: BTreeSet<_> = features(value).into_iter().collect();
+        if enables_default_features(value) || listed.is_empty() {
+            continue;
+        }
+
+        let package_name = dependency_package_name(name, value);
+        for package in packages.iter().filter(|package| str_field(package, "name") == package_name) {
+            let defaults: BTreeSet<_> = array(package, "/features/default").iter().filter_map(Value::as_str).collect();
+            if defaults == listed {
+                warnings.push(Violation::workspace_dependency(
+                    name,
+                    format!(
+                        "'{name}' is declared with default-features = false, but its features are exactly the default features of {package_name} {}",
+                        str_field(package, "version")
+                    ),
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Finds crates appearing at several semver-incompatible versions in the workspace's Cargo.lock
+///
+/// Versions are compatible when they share their leftmost non-zero component, the way Cargo decides
+/// whether a single version can satisfy both requirements. Each duplicate tree is built separately,
+/// adding to compile times much like unneeded default features do. Crates matching an exception are
+/// skipped.
+pub fn find_duplicate_versions(manifest_path: &Path, exceptions: &[ExceptionPattern]) -> Result<Vec<Violation>> {
+    let lockfile_path = manifest_path.with_file_name("Cargo.lock");
+    let content = std::fs::read_to_string(&lockfile_path).with_context(|| format!("Failed to read {}", lockfile_path.display()))?;
+    let lockfile: toml::Value = toml::from_str(&content).with_context(|| format!("Failed to parse {}", lockfile_path.display()))?;
+    Ok(duplicate_versions(&lockfile, exceptions))
+}
+
+/// Groups the packages of a parsed Cargo.lock by name and compatibility range
+fn duplicate_versions(lockfile: &toml::Value, exceptions: &[ExceptionPattern]) -> Vec<Violation> {
+    let mut versions: BTreeMap<&str, BTreeMap<String, &str>> = BTreeMap::new();
+    for package in lockfile.get("package").and_then(toml::Value::as_array).into_iter().flatten() {
+        let (Some(name), Some(version)) = (
+            package.get("name").and_then(toml::Value::as_str),
+            package.get("version").and_then(toml::Value::as_str),
+        ) else {
+            continue;
+        };
+
+        let _ = versions.entry(name).or_default().insert(compatibility_range(version), version);
+    }
+
+    versions
+        .into_iter()
+        .filter(|(name, ranges)| ranges.len() > 1 && !is_excepted(exceptions, name))
+        .map(|(name, ranges)| {
+            let versions = ranges.into_values().collect::<Vec<_>>().join(", ");
+            Violation::package(name, format!("'{name}' appears at incompatible versions {versions}"))
+        })
+        .collect()
+}
+
+/// Returns the part of a version that compatible versions share, such as `1` for `1.2.3` and `0.4` for
+/// `0.4.1`
+fn compatibility_range(version: &str) -> String {
+    let components: Vec<_> = version.split(['-', '+']).next().unwrap_or_default().split('.').collect();
+    let significant = components
+        .iter()
+        .position(|component| *component != "0")
+        .unwrap_or(components.len() - 1);
+    components[..=significant].join(".")
+}
+
+fn array<'a>(value: &'a Value, pointer: &str) -> &'a [Value] {
+    value.pointer(pointer).and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+fn str_field<'a>(value: &'a Value, field: &str) -> &'a str {
+    value.get(field).and_then(Value::as_str).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::rules::messages;
+    use crate::validation::compile_exceptions;
+
+    fn package(name: &str, dependencies: &[(&str, bool)]) -> Value {
+        let dependencies: Vec<_> = dependencies
+            .iter()
+            .map(|(name, uses_default_features)| json!({ "name": name, "uses_default_features": uses_default_features }))
+            .collect();
+        json!({ "id": format!("{name}#1.0.0"), "name": name, "version": "1.0.0", "dependencies": dependencies })
+    }
+
+    fn node(name: &str, features: &[&str]) -> Value {
+        json!({ "id": format!("{name}#1.0.0"), "features": features })
+    }
+
+    #[test]
+    fn test_leaked_defaults() {
+        let deps_table: toml::Table = toml::from_str(
+            r#"
+serde = { version = "1.0", default-features = false }
+json = { package = "serde_json", version = "1.0", default-features = false }
+regex = { version = "1.0", default-features = false }
+tokio = "1.0"
+"#,
+        )
+        .unwrap();
+
+        let metadata = json!({
+            "packages": [
+                package("app", &[("serde", false), ("serde_json", false), ("regex", false), ("tokio", true)]),
+                package("helper", &[("serde", true)]),
+                package("serde", &[]),
+                package("serde_json", &[]),
+                package("regex", &[]),
+                package("tokio", &[]),
+            ],
+            "resolve": {
+                "nodes": [
+                    node("app", &[]),
+                    node("helper", &[]),
+                    node("serde", &["default", "std"]),
+                    node("serde_json", &["default"]),
+                    node("regex", &["std"]),
+                    node("tokio", &["default"]),
+                ],
+            },
+        });
+
+        let warnings = leaked_defaults(&deps_table, &metadata);
+        assert_eq!(
+            messages(&warnings),
+            [
+                "'json' is declared with default-features = false, but the resolved dependency graph enables the default features of serde_json 1.0.0",
+                "'serde' is declared with default-features = false, but the resolved dependency graph enables the default features of serde 1.0.0 through helper 1.0.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redundant_default_features() {
+        let deps_table: toml::Table = toml::from_str(
+            r#"
+tokio = { version = "1.0", default-features = false, features = ["macros", "rt"] }
+serde = { version = "1.0", default-features = false, features = ["std"] }
+regex = { version = "1.0", default-features = false }
+log = { version = "0.4", features = ["std"] }
+"#,
+        )
+        .unwrap();
+
+        let metadata = json!({
+            "packages": [
+                { "name": "tokio", "version": "1.0.0", "features": { "default": ["rt", "macros"], "rt": [], "macros": [] } },
+                { "name": "serde", "version": "1.0.0", "features": { "default": ["std", "derive"], "std": [], "derive": [] } },
+                { "name": "regex", "version": "1.0.0", "features": { "default": [] } },
+                { "name": "log", "version": "0.4.0", "features": { "default": ["std"], "std": [] } },
+            ],
+        });
+
+        assert_eq!(
+            messages(&redundant_default_features(&deps_table, &metadata)),
+            ["'tokio' is declared with default-features = false, but its features are exactly the default features of tokio 1.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_compatibility_range() {
+        assert_eq!(compatibility_range("1.2.3"), "1");
+        assert_eq!(compatibility_range("0.4.1"), "0.4");
+        assert_eq!(compatibility_range("0.0.3"), "0.0.3");
+        assert_eq!(compatibility_range("2.0.0-rc.1"), "2");
+    }
+
+    #[test]
+    fn test_duplicate_versions() {
+        let lockfile: toml::Value = toml::from_str(
+            r#"
+version = 4
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+
+[[package]]
+name = "syn"
+version = "2.0.48"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+
+[[package]]
+name = "rand"
+version = "0.8.4"
+
+[[package]]
+name = "bitflags"
+version = "1.3.2"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            messages(&duplicate_versions(&lockfile, &[])),
+            ["'syn' appears at incompatible versions 1.0.109, 2.0.48"]
+        );
+        assert!(duplicate_versions(&lockfile, &compile_exceptions(&["syn".to_string()])).is_empty());
+    }
+
+    #[test]
+    fn test_leaked_defaults_without_resolve() {
+        let deps_table: toml::Table = toml::from_str("serde = { version = \"1.0\", default-features = false }").unwrap();
+        assert!(leaked_defaults(&deps_table, &json!({})).is_empty());
+    }
+}
@@ -0,0 +1,527 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+
+use crate::check::{CheckOptions, check_workspace_dependencies};
+use crate::fix::set_default_features_false;
+use crate::rules::{RuleSet, Severity};
+use crate::spans::dependency_key_span;
+use crate::validation::validate_exceptions;
+
+/// Name reported as the source of every diagnostic
+const SOURCE: &str = "ensure-no-default-features";
+
+/// The table holding the dependencies diagnosed by the server
+const WORKSPACE_DEPENDENCIES: &[&str] = &["workspace", "dependencies"];
+
+/// LSP `DiagnosticSeverity.Error`
+const SEVERITY_ERROR: u8 = 1;
+
+/// LSP `DiagnosticSeverity.Warning`
+const SEVERITY_WARNING: u8 = 2;
+
+/// LSP `TextDocumentSyncKind.Full`
+const SYNC_FULL: u8 = 1;
+
+/// Runs a Language Server Protocol server over stdin and stdout until the client asks it to exit
+///
+/// The server publishes diagnostics for every `[workspace.dependencies]` entry of an open Cargo.toml
+/// that doesn't have `default-features = false`, and offers a quick fix that adds it. The configuration
+/// governing the manifest on disk applies, along with its exceptions and the suppression comments of
+/// the document.
+pub fn run(exceptions: &[String]) -> Result<()> {
+    validate_exceptions(exceptions)?;
+    let mut reader = std::io::stdin().lock();
+    let mut writer = std::io::stdout().lock();
+    let mut server = Server::new(exceptions);
+
+    while let Some(message) = read_message(&mut reader)? {
+        for outgoing in server.handle(&message) {
+            write_message(&mut writer, &outgoing)?;
+        }
+
+        if server.exit_requested {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// State of the language server
+#[derive(Debug)]
+struct Server<'a> {
+    exceptions: &'a [String],
+    documents: HashMap<String, String>,
+    exit_requested: bool,
+}
+
+impl<'a> Server<'a> {
+    fn new(exceptions: &'a [String]) -> Self {
+        Self {
+            exceptions,
+            documents: HashMap::new(),
+            exit_requested: false,
+        }
+    }
+
+    /// Handles one incoming message and returns the messages to send back
+    fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or_default();
+        let params = message.get("params").unwrap_or(&Value::Null);
+        let id = message.get("id");
+
+        match method {
+            "initialize" => vec![response(id, &initialize_result())],
+            "shutdown" => vec![response(id, &Value::Null)],
+            "exit" => {
+                self.exit_requested = true;
+                Vec::new()
+            }
+
+            "textDocument/didOpen" => {
+                let uri = params.pointer("/textDocument/uri").and_then(Value::as_str);
+                let text = params.pointer("/textDocument/text").and_then(Value::as_str);
+                match (uri, text) {
+                    (Some(uri), Some(text)) => self.update(uri, text.to_string()),
+                    _ => Vec::new(),
+                }
+            }
+
+            "textDocument/didChange" => {
+                let uri = params.pointer("/textDocument/uri").and_then(Value::as_str);
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Value::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Value::as_str);
+                match (uri, text) {
+                    (Some(uri), Some(text)) => self.update(uri, text.to_string()),
+                    _ => Vec::new(),
+                }
+            }
+
+            "textDocument/didClose" => {
+                let Some(uri) = params.pointer("/textDocument/uri").and_then(Value::as_str) else {
+                    return Vec::new();
+                };
+
+                let _ = self.documents.remove(uri);
+                vec![publish_diagnostics(uri, &[])]
+            }
+
+            "textDocument/codeAction" => {
+                let uri = params.pointer("/textDocument/uri").and_then(Value::as_str).unwrap_or_default();
+                vec![response(id, &Value::Array(self.code_actions(uri, params)))]
+            }
+
+            // Unknown requests must be answered, while unknown notifications are ignored
+            _ if id.is_some() => vec![error_response(id, -32601, &format!("Method not found: {method}"))],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Stores the latest text of a document and publishes its diagnostics
+    fn update(&mut self, uri: &str, text: String) -> Vec<Value> {
+        let diagnostics = if is_manifest(uri) {
+            diagnostics(uri, &text, self.exceptions)
+        } else {
+            Vec::new()
+        };
+
+        let _ = self.documents.insert(uri.to_string(), text);
+        vec![publish_diagnostics(uri, &diagnostics)]
+    }
+
+    /// Builds the quick fixes for the diagnostics included in a code action request
+    fn code_actions(&self, uri: &str, params: &Value) -> Vec<Value> {
+        let Some(text) = self.documents.get(uri) else {
+            return Vec::new();
+        };
+
+        params
+            .pointer("/context/diagnostics")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|diagnostic| diagnostic.get("source").and_then(Value::as_str) == Some(SOURCE))
+            .filter_map(|diagnostic| {
+                let name = diagnostic.pointer("/data/dependency").and_then(Value::as_str)?;
+                let fixed = set_default_features_false(text, WORKSPACE_DEPENDENCIES, name).ok()?;
+                Some(json!({
+                    "title": format!("Set default-features = false for '{name}'"),
+                    "kind": "quickfix",
+                    "diagnostics": [diagnostic],
+                    "isPreferred": true,
+                    "edit": {
+                        "changes": {
+                            uri: [{
+                                "range": { "start": position(text, 0), "end": position(text, text.len()) },
+                                "newText": fixed,
+                            }],
+                        },
+                    },
+                }))
+            })
+            .collect()
+    }
+}
+
+/// Returns whether a document URI refers to a Cargo manifest
+fn is_manifest(uri: &str) -> bool {
+    uri.rsplit('/').next().is_some_and(|file| file == "Cargo.toml")
+}
+
+/// Returns the path of the file a `file:` URI refers to, decoding its percent-encoded bytes
+fn file_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut index = 0;
+    while let Some(&byte) = encoded.get(index) {
+        let escaped = encoded
+            .get(index + 1..index + 3)
+            .filter(|_| byte == b'%')
+            .and_then(|hex| u8::from_str_radix(core::str::from_utf8(hex).ok()?, 16).ok());
+        if let Some(escaped) = escaped {
+            decoded.push(escaped);
+            index += 3;
+        } else {
+            decoded.push(byte);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+/// Computes the diagnostics for the content of a Cargo.toml file
+///
+/// The content is checked like `check_workspace` checks the manifest on disk, using the configuration
+/// found from the document's path. A configuration that can't be loaded is reported at the start of
+/// the document.
+fn diagnostics(uri: &str, content: &str, exceptions: &[String]) -> Vec<Value> {
+    let parsed: toml::Value = match toml::from_str(content) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            let span = err.span().unwrap_or(0..0);
+            return vec![json!({
+                "range": { "start": position(content, span.start), "end": position(content, span.end) },
+                "severity": SEVERITY_ERROR,
+                "source": SOURCE,
+                "message": format!("Failed to parse Cargo.toml: {}", err.message()),
+            })];
+        }
+    };
+
+    if parsed.get("workspace").and_then(|w| w.get("dependencies")).is_none() {
+        return Vec::new();
+    }
+
+    let manifest_path = file_path(uri).unwrap_or_else(|| PathBuf::from("Cargo.toml"));
+    let options = CheckOptions {
+        exceptions,
+        replace_exceptions: false,
+        require_exception_reasons: false,
+        rules: &RuleSet::default(),
+        preset: None,
+        vendor_dir: None,
+        deadline: None,
+        config_path: None,
+        cache: None,
+        changed_since: None,
+        no_std: false,
+        sandboxed: false,
+    };
+
+    let findings = match check_workspace_dependencies(&manifest_path, content, &options) {
+        Ok(findings) => findings,
+        Err(err) => {
+            return vec![json!({
+                "range": { "start": position(content, 0), "end": position(content, 0) },
+                "severity": SEVERITY_ERROR,
+                "source": SOURCE,
+                "message": format!("{err:#}"),
+            })];
+        }
+    };
+
+    findings
+        .iter()
+        .filter_map(|finding| {
+            let name = finding.dependency.as_deref()?;
+            let span = dependency_key_span(content, WORKSPACE_DEPENDENCIES, name).unwrap_or(0..0);
+            let severity = match finding.severity {
+                Severity::Error => SEVERITY_ERROR,
+                Severity::Warning => SEVERITY_WARNING,
+            };
+
+            Some(json!({
+                "range": { "start": position(content, span.start), "end": position(content, span.end) },
+                "severity": severity,
+                "source": SOURCE,
+                "code": finding.rule.id,
+                "message": finding.message,
+                "data": { "dependency": name },
+            }))
+        })
+        .collect()
+}
+
+/// Converts a byte offset into an LSP position, whose character offsets count UTF-16 code units
+fn position(content: &str, offset: usize) -> Value {
+    let before = content.get(..offset).unwrap_or(content);
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    let character = before.get(line_start..).unwrap_or_default().encode_utf16().count();
+    json!({ "line": line, "character": character })
+}
+
+/// The result of the `initialize` request, advertising the server's capabilities
+fn initialize_result() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": SYNC_FULL,
+            "codeActionProvider": true,
+        },
+        "serverInfo": {
+            "name": SOURCE,
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn response(id: Option<&Value>, result: &Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Option<&Value>, code: i32, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: &[Value]) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Reads one message framed with a `Content-Length` header, returning `None` at end of input
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).context("Failed to read LSP message header")? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = Some(value.trim().parse::<usize>().context("Invalid Content-Length header")?);
+        }
+    }
+
+    let content_length = content_length.context("LSP message is missing a Content-Length header")?;
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).context("Failed to read LSP message body")?;
+    serde_json::from_slice(&body).context("Failed to parse LSP message").map(Some)
+}
+
+/// Writes one message framed with a `Content-Length` header
+fn write_message(writer: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_string(message).context("Failed to serialize LSP message")?;
+    write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len()).context("Failed to write LSP message")?;
+    writer.flush().context("Failed to write LSP message")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "file:///ws/Cargo.toml";
+    const CONTENT: &str = "[workspace.dependencies]\nserde = \"1.0\"\ntokio = { version = \"1.0\", default-features = false }\n";
+
+    fn open(server: &mut Server<'_>, text: &str) -> Vec<Value> {
+        server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": URI, "languageId": "toml", "version": 1, "text": text } },
+        }))
+    }
+
+    #[test]
+    fn test_initialize_and_shutdown() {
+        let mut server = Server::new(&[]);
+        let responses = server.handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }));
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["capabilities"]["codeActionProvider"], true);
+
+        let responses = server.handle(&json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }));
+        assert_eq!(responses[0]["result"], Value::Null);
+        assert!(!server.exit_requested);
+
+        let responses = server.handle(&json!({ "jsonrpc": "2.0", "method": "exit" }));
+        assert!(responses.is_empty());
+        assert!(server.exit_requested);
+    }
+
+    #[test]
+    fn test_unknown_request_is_rejected() {
+        let mut server = Server::new(&[]);
+        let responses = server.handle(&json!({ "jsonrpc": "2.0", "id": 7, "method": "textDocument/hover" }));
+        assert_eq!(responses[0]["error"]["code"], -32601);
+
+        let responses = server.handle(&json!({ "jsonrpc": "2.0", "method": "$/cancelRequest" }));
+        assert!(responses.is_empty());
+    }
+
+    #[test]
+    fn test_did_open_publishes_diagnostics() {
+        let mut server = Server::new(&[]);
+        let messages = open(&mut server, CONTENT);
+        let diagnostics = messages[0]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 1, "character": 0 }));
+        assert_eq!(diagnostics[0]["range"]["end"], json!({ "line": 1, "character": 5 }));
+        assert_eq!(diagnostics[0]["data"]["dependency"], "serde");
+        assert!(
+            diagnostics[0]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("'serde': uses simple version string")
+        );
+
+        let exceptions = vec!["serde".to_string()];
+        let mut server = Server::new(&exceptions);
+        let messages = open(&mut server, CONTENT);
+        assert!(messages[0]["params"]["diagnostics"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diagnostics_follow_configuration() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let uri = format!("file://{}/Cargo.toml", dir.path().display()).replace(' ', "%20");
+        let severity = |content: &str| {
+            diagnostics(&uri, content, &[])
+                .iter()
+                .map(|diagnostic| (diagnostic["data"]["dependency"].clone(), diagnostic["severity"].clone()))
+                .collect::<Vec<_>>()
+        };
+
+        std::fs::write(
+            dir.path().join("ensure-no-default-features.toml"),
+            "[severity]\nENDF001 = \"warn\"\n",
+        )
+        .unwrap();
+        assert_eq!(severity(CONTENT), [(json!("serde"), json!(SEVERITY_WARNING))]);
+
+        std::fs::write(dir.path().join("ensure-no-default-features.toml"), "exceptions = [\"serde\"]\n").unwrap();
+        assert!(severity(CONTENT).is_empty());
+
+        std::fs::write(dir.path().join("ensure-no-default-features.toml"), "").unwrap();
+        let suppressed = CONTENT.replace("serde =", "# endf: allow(ENDF001)\nserde =");
+        assert!(severity(&suppressed).is_empty());
+
+        // The metadata table of the document is used even before it's saved
+        let metadata = format!("{CONTENT}\n[workspace.metadata.ensure-no-default-features]\ndisable-rules = [\"ENDF001\"]\n");
+        assert!(severity(&metadata).is_empty());
+
+        std::fs::write(dir.path().join("ensure-no-default-features.toml"), "unknown-setting = 1\n").unwrap();
+        let diagnostics = diagnostics(&uri, CONTENT, &[]);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0]["message"].as_str().unwrap().contains("unknown-setting"));
+    }
+
+    #[test]
+    fn test_file_path() {
+        assert_eq!(
+            file_path("file:///ws/my%20crate/Cargo.toml"),
+            Some(PathBuf::from("/ws/my crate/Cargo.toml"))
+        );
+        assert_eq!(file_path("file:///ws/100%/Cargo.toml"), Some(PathBuf::from("/ws/100%/Cargo.toml")));
+        assert_eq!(file_path("untitled:Cargo.toml"), None);
+    }
+
+    #[test]
+    fn test_parse_error_diagnostic() {
+        let mut server = Server::new(&[]);
+        let messages = open(&mut server, "[workspace\n");
+        let diagnostics = messages[0]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(
+            diagnostics[0]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Failed to parse Cargo.toml")
+        );
+    }
+
+    #[test]
+    fn test_code_action_applies_fix() {
+        let mut server = Server::new(&[]);
+        let messages = open(&mut server, CONTENT);
+        let diagnostics = messages[0]["params"]["diagnostics"].clone();
+
+        let responses = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "textDocument/codeAction",
+            "params": {
+                "textDocument": { "uri": URI },
+                "range": { "start": { "line": 1, "character": 0 }, "end": { "line": 1, "character": 0 } },
+                "context": { "diagnostics": diagnostics },
+            },
+        }));
+
+        let actions = responses[0]["result"].as_array().unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0]["title"], "Set default-features = false for 'serde'");
+        let edit = &actions[0]["edit"]["changes"][URI][0];
+        assert_eq!(edit["range"]["end"], json!({ "line": 3, "character": 0 }));
+        assert!(
+            edit["newText"]
+                .as_str()
+                .unwrap()
+                .contains("serde = { version = \"1.0\", default-features = false }")
+        );
+    }
+
+    #[test]
+    fn test_non_manifest_documents_are_ignored() {
+        let mut server = Server::new(&[]);
+        let messages = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": "file:///ws/other.toml", "text": CONTENT } },
+        }));
+        assert!(messages[0]["params"]["diagnostics"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_position_counts_utf16_units() {
+        let content = "a = \"é😀\"\nb";
+        assert_eq!(position(content, content.len()), json!({ "line": 1, "character": 1 }));
+        assert_eq!(position(content, 11), json!({ "line": 0, "character": 8 }));
+    }
+
+    #[test]
+    fn test_message_framing_round_trip() {
+        let mut buffer = Vec::new();
+        let message = json!({ "jsonrpc": "2.0", "method": "exit" });
+        write_message(&mut buffer, &message).unwrap();
+
+        let mut reader = buffer.as_slice();
+        assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+}
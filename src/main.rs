@@ -21,18 +21,643 @@
 //! ```
 //!
 //! The --manifest-path option lets you specify an explicit Cargo.toml file to check. Without this
-//! option, it defaults to the Cargo.toml in the current directory.
+//! option, it defaults to the Cargo.toml in the current directory. When the path is a directory,
+//! the Cargo.toml inside it is used. When the manifest belongs to a workspace member rather than
+//! the workspace root, the root is found automatically, just like Cargo does, so the tool can be run
+//! from within any member's directory. The option can be repeated (or given several space-separated
+//! paths) to check multiple workspaces and get one aggregated report.
 //!
 //! The --exceptions option lets you specify a comma-separated list of dependencies to exclude from
 //! the default-features check. This is useful for dependencies that you explicitly want to have
-//! default features enabled.
+//! default features enabled. Long lists are easier to review in a file checked into the repository,
+//! passed with --exceptions-file: it lists one dependency per line, with `#` starting a comment, or
+//! holds an `exceptions` array when its extension is `.toml`. Both options can be combined.
+//!
+//! The --exceptions option can also be repeated, as in `-e serde -e tokio`, which is easier to build
+//! from shell variables and CI pipelines. Whitespace around each entry is ignored, as are empty and
+//! duplicate entries, so `-e "serde, tokio,"` excludes the same two dependencies.
+//!
+//! Exceptions can be glob patterns, such as `windows-*` or `my-org-*`, so a family of related crates
+//! doesn't need an entry per crate. More complex cases can use a regular expression prefixed with
+//! `regex:`, such as `regex:^winapi(-.*)?$`, which matches anywhere in the name unless anchored.
+//! Patterns are accepted wherever exceptions are, configuration files included, and invalid ones are
+//! reported before anything is checked.
+//!
+//! Exceptions match a renamed dependency through either its key or the real name of the package given
+//! by its `package` field, so `serde_json` covers `json = { package = "serde_json", version = "1.0" }`.
+//!
+//! An exception can be limited to a version requirement with `@`, such as `serde@^1.0`, so the
+//! dependency is only exempt while the lowest version its `[workspace.dependencies]` entry allows
+//! matches the requirement. Once the dependency is bumped past it, the exception expires and the
+//! dependency is checked again, instead of staying exempt long after the reason for it is gone.
+//!
+//! An exception given on the command line that doesn't match any dependency of `[workspace.dependencies]`
+//! is reported with a warning, which is easy to miss in CI. The --strict-exceptions option turns it into
+//! a failure instead, keeping the list of exceptions from accumulating stale entries.
+//!
+//! Exceptions covering only dependencies that already have `default-features = false` are reported as
+//! obsolete, since the carve-out is no longer needed and should be removed. This is a warning as well,
+//! and a failure under --strict-exceptions.
+//!
+//! Security-sensitive CI gates can force every exemption through the reviewed configuration file with
+//! --no-cli-exceptions. The run then fails when exceptions are given through --exceptions,
+//! --exceptions-file, --extend-exceptions, or their environment variables, rather than letting a
+//! pipeline quietly widen the policy.
+//!
+//! A single dependency entry can be exempted right where it's declared, which is easier to review than
+//! a distant list, with a comment on the line preceding it naming the rules it allows by ID or name:
+//!
+//! ```toml
+//! [workspace.dependencies]
+//! # endf: allow(default-features) reason="needs std"
+//! serde = "1.0"
+//! ```
+//!
+//! Anything after the closing parenthesis, such as the reason, is left for reviewers. Suppression
+//! comments are honored in `[workspace.dependencies]` and in the dependency tables of members. They
+//! can only name the rules honoring exceptions, listed below, and naming any other rule is an error.
+//!
+//! Options shared by every run can also be given through environment variables, which lets shared CI
+//! templates configure the tool without editing each pipeline's arguments: `ENDF_MANIFEST_PATH`,
+//! `ENDF_EXCEPTIONS` (comma-separated), `ENDF_EXCEPTIONS_FILE`, `ENDF_CONFIG`, `ENDF_PRESET`, and
+//! `ENDF_FORMAT`. An option given on the command line takes precedence over its environment variable,
+//! which otherwise counts as if the option was given. `ENDF_MANIFEST_PATH` is ignored by --recursive,
+//! --files, and --changed-since, which find the manifests to check themselves, and `ENDF_FORMAT` by
+//! --emit-exceptions and --message-template.
+//!
+//! The --follow-path-deps option makes the tool also check the manifests of crates referenced
+//! through `path` dependencies that live outside of the workspace. Those crates are effectively part
+//! of the build graph, so their `[dependencies]` and `[build-dependencies]` must also use
+//! `default-features = false`.
+//!
+//! The --check-path-defaults option connects the producer and consumer sides of the policy for
+//! crates published from the workspace. For every `[workspace.dependencies]` entry with both `path`
+//! and `version`, the crate's own `default` feature is read, and a warning is reported when it's
+//! non-empty while the workspace table or a member still consumes the crate with default features.
+//!
+//! The --check-packaged option verifies that the policy survives publishing. Cargo rewrites Cargo.toml
+//! when packaging a crate, inlining everything inherited from the workspace, so each publishable member
+//! inheriting dependencies with `workspace = true` is packaged with `cargo package --no-verify`, and
+//! those dependencies are checked in the normalized manifest found in the resulting `.crate` file.
+//!
+//! The --check-lockfile option compares the workspace's intent with the dependency graph resolved from
+//! Cargo.lock, as reported by `cargo metadata`. A warning is reported when a dependency declared with
+//! `default-features = false` still has its default features enabled, naming the packages that enable
+//! them, since that means the defaults are leaking in from elsewhere in the graph.
+//!
+//! The --vendor-dir option points the tool at a directory produced by `cargo vendor` and reports
+//! which vendored crates are consumed with their default features enabled, either by the workspace
+//! or by other vendored crates. This report is informational and doesn't affect the exit status.
+//!
+//! The manifests of workspace members are also inspected. A warning is reported when a member
+//! declares the same crate in both `[dependencies]` and `[build-dependencies]` with different
+//! default-features settings, since Cargo unifies both declarations outside of cross-compilation.
+//!
+//! The --deadline option puts an upper bound on how long the tool runs, for example `--deadline 30s`.
+//! Once the deadline passes, the remaining work is skipped, the partial report is printed with a
+//! note that it's truncated, and the tool exits with status 3 instead of the usual 0 or 1.
+//!
+//! Members must inherit crates governed by `[workspace.dependencies]` with `workspace = true`. An
+//! error is reported when a member instead uses the `package` key to declare such a crate directly,
+//! since that entry bypasses the workspace's default-features settings. The error includes the exact
+//! `workspace = true` entry to use instead.
+//!
+//! A warning is also reported when members import the same package under different names, for
+//! example `prost` in one member and `proto = { package = "prost" }` in another. Inconsistent
+//! aliases make feature governance and searching the code base harder.
+//!
+//! The --recursive option searches a directory tree for every workspace root (a Cargo.toml with a
+//! `[workspace]` section) and checks them all, producing a single combined report. It defaults to
+//! the current directory when no directory is given. `target` directories, hidden directories, and
+//! anything ignored by `.gitignore` files are skipped. Additional paths can be skipped with the
+//! --exclude option, which takes a gitignore-style glob and can be repeated.
+//!
+//! The --files option takes a list of changed files, the way pre-commit frameworks pass them, and
+//! only checks the workspaces owning the Cargo.toml files (and configuration files) among them, so
+//! hook runs stay fast in large repos. Other files are ignored, and each manifest is mapped back to its
+//! workspace root the same way as for --manifest-path.
+//!
+//! The --changed-since option takes a git revision, for example `--changed-since origin/main`, and only
+//! checks the workspaces whose manifests changed since then, including uncommitted changes and new
+//! files git doesn't track yet. Each of these workspaces is also checked as it was at that revision,
+//! exported once for the whole run, and the findings of every rule that were already present then, as
+//! identified by their fingerprints, are left out of the report, so pull request builds only fail on
+//! the violations they introduce.
+//!
+//! The `diff` subcommand compares two versions of a manifest, each given as a git revision or as the
+//! path of a manifest file, for example `cargo ensure-no-default-features diff origin/main HEAD`. When
+//! the second version is omitted, the working tree is used. It reports the dependencies that regressed
+//! (compliant before, but not anymore) and the ones that improved, and exits with status 1 when there
+//! are regressions, which makes it a good fit for pull request status summaries.
+//!
+//! The `install-hook` subcommand writes a git hook running the tool. By default it installs a
+//! pre-commit hook that checks the workspaces owning the staged manifests using --files, while
+//! `--hook pre-push` installs a hook checking the whole workspace. Arguments given after `--` are
+//! passed to the tool whenever the hook runs, for example
+//! `cargo ensure-no-default-features install-hook -- --exceptions serde`. An existing hook is only
+//! replaced when it was installed by the tool itself or when --force is given.
+//!
+//! The `init` subcommand writes a commented starter configuration file next to the workspace's
+//! Cargo.toml, or into its `[workspace.metadata.ensure-no-default-features]` table with --metadata. The
+//! dependencies currently failing the default-features check are listed as exceptions, giving new
+//! adopters a passing baseline to work through over time. An existing configuration file is only
+//! replaced when --force is given.
+//!
+//! When a configuration already exists, the --emit-exceptions option prints the dependencies currently
+//! failing the default-features check as an `[exceptions]` table instead of reporting them, or writes
+//! it to the file given as its value, making it one command to bootstrap adoption on a legacy
+//! workspace.
+//!
+//! Legacy violations can be tracked as they're burned down with a baseline. The --write-baseline option
+//! records the violations found in a JSON file, and later runs given that file with --baseline report
+//! how they compare to it, such as "3 fixed, 1 new, 12 remaining". Manifest paths are recorded relative
+//! to the baseline file, so it can be checked into the repository.
+//!
+//! Each recorded violation carries a fingerprint, a hash of its rule ID, its manifest's path, the table
+//! holding the entry it's about, and the name of that dependency or feature, along with the dependency
+//! a feature enables default features in. Violations are matched by fingerprint, so they're still
+//! recognized after the manifest's lines shift or a message is reworded.
+//! Violations that aren't about a single entry are identified by their whole message instead.
+//!
+//! In the human-readable report, each finding about a single dependency comes with a frame showing the
+//! manifest line declaring it, underlined, followed by a help line. The help shows the entry as --fix
+//! would rewrite it when the finding can be fixed automatically, and the rule's summary otherwise:
+//!
+//! ```text
+//! ❌ Found 1 dependencies without default-features = false [ENDF001]:
+//!
+//!   - 'tokio': uses simple version string, should be a table with default-features = false
+//!      --> Cargo.toml:5:1
+//!       |
+//!     5 | tokio = "1.0"
+//!       | ^^^^^^^^^^^^^
+//!       = help: set default-features = false: `tokio = { version = "1.0", default-features = false }`
+//! ```
+//!
+//! Within each rule, findings are grouped by the manifest they were found in, under an `In <path>:`
+//! header giving the manifest's path relative to the current directory. The headers are left out only
+//! when every finding is in the workspace's root manifest.
+//!
+//! The human-readable report lists errors by rule. `--group-by crate` lists every finding under the
+//! dependency it's about instead, `--group-by file` under the manifest it was found in, and
+//! `--group-by severity` under errors and warnings, so crate owners and auditors can each get their
+//! view from the same run. `--sort name` orders the groups and their findings alphabetically,
+//! `--sort severity` puts errors first, and `--sort count` puts the largest groups first.
+//!
+//! When there are errors, the human-readable report starts with a line counting them by kind, such as
+//! `📊 12 missing default-features, 4 simple version strings across 3 manifests`, so the scale of the
+//! problem is visible before the detailed listing. Entries of `[workspace.dependencies]` are counted
+//! by what's wrong with them, and the findings of other rules by rule.
+//!
+//! `--top N` follows that summary with the N dependencies and the N manifests accounting for the
+//! most errors, to help prioritize cleanup in big monorepos. Manifests are named after their
+//! package when they define one.
+//!
+//! Identical findings of several members, such as twenty members repeating the same inline
+//! declaration, are collapsed into one in the human-readable report, annotated with
+//! `(seen in 20 members)` and followed by a line naming the first few manifests. The structured
+//! formats still report every finding.
+//!
+//! `--summary-file PATH` writes a small JSON document with the number of findings in total, by
+//! severity and by rule, the exit status, whether the run was truncated, the time spent on the run
+//! and on each workspace in milliseconds, and the tool version, so pipelines can gate or chart on
+//! aggregates without parsing the full report. It's written whatever the `--format`.
+//!
+//! `--message-template` shapes the findings for in-house log parsers without a dedicated format:
+//! each finding is written to stdout on a line where `{dep}`, `{rule}`, `{rule_name}`, `{file}`,
+//! `{line}`, `{column}`, `{severity}`, and `{message}` are replaced by what the finding is about,
+//! and `{{` and `}}` stand for literal braces. For example,
+//! `--message-template '{severity}|{rule}|{dep}|{file}:{line}'` writes
+//! `error|ENDF001|tokio|Cargo.toml:5`. As with the structured formats, the human-readable report
+//! goes to stderr.
+//!
+//! The --format option picks how the report is written. The default, `human`, is the text shown
+//! throughout this documentation. With `json`, stdout holds a single JSON document for automation to
+//! consume, while the human-readable report goes to stderr:
+//!
+//! ```json
+//! {
+//!   "version": "1.0.0",
+//!   "manifest_paths": ["Cargo.toml"],
+//!   "truncated": false,
+//!   "findings": [
+//!     {
+//!       "fingerprint": "5c6f1e0b2a9d4e83",
+//!       "rule": "ENDF001",
+//!       "rule_name": "default-features",
+//!       "severity": "error",
+//!       "manifest_path": "Cargo.toml",
+//!       "dependency": "tokio",
+//!       "section": "[workspace.dependencies]",
+//!       "message": "'tokio': uses simple version string, should be a table with default-features = false",
+//!       "location": { "line": 6, "column": 1 }
+//!     }
+//!   ],
+//!   "exceptions": []
+//! }
+//! ```
+//!
+//! `section` names the table holding the entry the finding is about, such as `[dev-dependencies]`,
+//! and `location` gives the line and column of the key of that dependency or feature. Both are
+//! `null` when a finding isn't about a single entry. `exceptions` lists the exceptions of the
+//! configuration that give a reason or an owner, or that have expired.
+//!
+//! Findings that --fix knows how to fix carry a `fix` object, with a `description` of the fix and the
+//! `manifest_hash` of the manifest's content it was suggested for, and `null` otherwise. The `apply`
+//! subcommand applies the fixes recorded in such a report, so they can be reviewed before any file is
+//! touched, for example `cargo ensure-no-default-features apply --from report.json`. It's run from the
+//! directory the report was written in, and rewrites nothing when a manifest changed since then. Like
+//! with --fix, the fixes of rule ENDF029 removing unused entries are only applied when `apply` is also
+//! given --remove-unused.
+//!
+//! With `--format sarif`, stdout holds a SARIF 2.1.0 log instead, ready to upload to GitHub code scanning
+//! so findings show up as annotations on the `Cargo.toml` lines of pull requests. The log describes every
+//! rule along with its explanation. Each result points at the line declaring its dependency, relative to
+//! the current directory, and carries the finding's fingerprint. Findings of rules ENDF001 and ENDF029
+//! come with the fix --fix would apply.
+//!
+//! ```yaml
+//! - run: cargo ensure-no-default-features --format sarif > endf.sarif
+//! - uses: github/codeql-action/upload-sarif@v3
+//!   if: always()
+//!   with:
+//!     sarif_file: endf.sarif
+//! ```
+//!
+//! With `--format junit`, stdout holds a `JUnit` XML report for the test report views of Jenkins, GitLab,
+//! and other CI systems. Each workspace is a test suite holding a test case per finding, named after its
+//! dependency and classified by rule, such as `ENDF001.default-features`. Findings with error severity
+//! are failures, and when rule ENDF001 ran, the dependencies that passed it are listed as passing test
+//! cases.
+//!
+//! With `--format teamcity`, stdout holds `TeamCity` service messages instead, so builds on `TeamCity`
+//! list the findings in their inspections tab. Every rule with findings is declared as an inspection
+//! type, and each finding is an inspection pointing at the line declaring its dependency.
+//!
+//! With `--format markdown`, stdout holds a Markdown report to paste into a pull request description or
+//! post from a bot. It opens with the number of errors and warnings found, lists the findings in a table
+//! for each manifest, and closes with the exceptions that give a reason or an owner, or that have
+//! expired, along with whether they still apply.
+//!
+//! With `--format html`, stdout holds a self-contained HTML page to share with people who won't read CI
+//! logs, for example with `--format html > report.html`. The page counts the errors and warnings of each
+//! manifest, lists the findings in a table that sorts by clicking a column and filters as you type, and
+//! embeds the explanation of every rule with findings.
+//!
+//! With `--format csv`, stdout holds a matrix of the workspace's dependencies instead of its findings,
+//! for spreadsheets and supply-chain audits. There's a row for each dependency of each member, with the
+//! section declaring it, whether it inherits from `[workspace.dependencies]`, whether its default
+//! features end up enabled, and the features it enables separated by spaces. Entries of
+//! `[workspace.dependencies]` no member uses get a row with an empty member.
+//!
+//! With `--format dot`, stdout holds a Graphviz DOT graph of the same matrix, for example to render with
+//! `--format dot | dot -Tsvg > features.svg`. Each workspace is a cluster where members point at their
+//! dependencies, which point at the features they get enabled. Edges along which default features stay
+//! enabled are drawn in red, showing where the extra features come from.
+//!
+//! With `--format badge`, stdout holds a shields.io endpoint document, such as
+//! `{"schemaVersion":1,"label":"default features","message":"passing","color":"brightgreen"}`. CI can
+//! publish it wherever the repository's README badge reads from. The badge shows the number of
+//! violations in red, or of warnings in yellow when there are only warnings, and reads "incomplete"
+//! when the report was truncated.
+//!
+//! With `--format compact`, stdout holds one line per finding in the style of compiler errors, such as
+//! `Cargo.toml:5:1: error[ENDF001]: 'tokio': uses simple version string`, for grep and simple CI log
+//! parsers. Paths are relative to the current directory, and findings that aren't about a single
+//! dependency point at the start of their manifest.
+//!
+//! With `--format quickfix`, stdout holds lines such as
+//! `Cargo.toml:5:1: error: 'tokio': uses simple version string [ENDF001]`, which Vim's quickfix list and
+//! Emacs's compilation mode parse as they are, so `:make` style workflows jump straight to the offending
+//! entry. In Vim, `set errorformat=%f:%l:%c:\ %t%*[a-z]:\ %m` also tells errors from warnings.
+//!
+//! Every check is a rule with a stable ID and a default severity. Findings of rules with error
+//! severity fail the check, while warnings are only reported. The rules are:
+//!
+//! | ID | Name | Default |
+//! |----|------|---------|
+//! | ENDF001 | default-features | error |
+//! | ENDF002 | workspace-bypass | error |
+//! | ENDF003 | path-dependencies | error, enabled by --follow-path-deps |
+//! | ENDF004 | packaged-manifests | error, enabled by --check-packaged |
+//! | ENDF005 | build-dependency-mismatch | warning |
+//! | ENDF006 | inconsistent-aliases | warning |
+//! | ENDF007 | path-defaults | warning, enabled by --check-path-defaults |
+//! | ENDF008 | leaked-defaults | warning, enabled by --check-lockfile |
+//! | ENDF009 | default-in-features | error |
+//! | ENDF010 | default-feature-references | error |
+//! | ENDF011 | member-default-overrides | error |
+//! | ENDF012 | decentralized-dependencies | error, disabled by default |
+//! | ENDF013 | workspace-inheritance | error |
+//! | ENDF014 | version-precision | error, only reports when version-precision is configured |
+//! | ENDF015 | git-dependencies | error, disabled by default |
+//! | ENDF016 | unversioned-path-dependencies | error, disabled by default |
+//! | ENDF017 | sorted-dependencies | warning, disabled by default |
+//! | ENDF018 | duplicate-features | warning |
+//! | ENDF019 | denied-features | error |
+//! | ENDF020 | required-features | error |
+//! | ENDF021 | feature-budget | warning |
+//! | ENDF022 | umbrella-features | warning |
+//! | ENDF023 | member-default-features | warning, disabled by default |
+//! | ENDF024 | optional-dependency-gating | warning |
+//! | ENDF025 | legacy-default-features | warning |
+//! | ENDF026 | redundant-default-features | warning, disabled by default |
+//! | ENDF027 | no-std | error |
+//! | ENDF028 | version-mismatch | warning |
+//! | ENDF029 | unused-workspace-dependencies | warning |
+//! | ENDF030 | duplicate-versions | warning, disabled by default |
+//! | ENDF031 | custom-rules | error |
+//!
+//! The --enable and --disable options turn a rule on or off, and --warn and --deny change the severity
+//! of its findings. Each option takes a rule ID or name and can be repeated. Rules can also be turned
+//! on or off through the `enable-rules` and `disable-rules` settings of the configuration file, and
+//! the `severity` table changes the severity of the rules it names, such as `severity.ENDF002 = "warn"`,
+//! so teams can adopt a rule gradually without disabling it. The command-line options override them.
+//!
+//! The --preset option, or the `preset` setting, picks a curated starting point the other rule options
+//! and settings build on:
+//!
+//! - `minimal` only runs ENDF001 and ENDF002, along with the rules reporting what the configuration
+//!   asks for, such as `denied-features`.
+//! - `standard`, the default, runs the rules enabled by default with their default severities.
+//! - `strict` runs every rule checking the workspace's own manifests, reporting all findings as errors.
+//!   The rules running cargo or reading files besides the manifests are left to their defaults.
+//!
+//! The --fix option fixes the problems that can be fixed automatically before running the check.
+//! Manifests are rewritten in place, preserving their formatting and comments. The fixable rules are:
+//!
+//! - ENDF001: `default-features = false` is added to workspace dependencies.
+//! - ENDF013: member dependencies declared directly are switched to `workspace = true`.
+//! - ENDF017: `[workspace.dependencies]` is sorted alphabetically, when the rule is enabled.
+//! - ENDF018: duplicate features are removed from every dependency entry.
+//! - ENDF025: legacy `default_features` keys are renamed to `default-features`.
+//! - ENDF029: workspace dependencies no member inherits are removed, only when --remove-unused is
+//!   also given, since an entry a member forgot to inherit is lost otherwise.
+//!
+//! The --list-rules option prints every rule with its default severity, whether it's enabled for the
+//! workspace given the configuration file and the other options, and a short description.
+//!
+//! The --explain option prints an extended explanation of a rule, like `rustc --explain` does: why the
+//! problem matters, what the fix looks like, and when an exception is appropriate. For example,
+//! `cargo ensure-no-default-features --explain ENDF001`.
+//!
+//! Additional settings can be provided through a configuration file named
+//! `ensure-no-default-features.toml` (or `.ensure-no-default-features.toml`) placed next to the
+//! workspace's Cargo.toml. The --config option points at a different file instead.
+//!
+//! Settings can also live in the `[workspace.metadata.ensure-no-default-features]` table of the
+//! workspace's Cargo.toml, next to the manifest they govern:
+//!
+//! ```toml
+//! [workspace.metadata.ensure-no-default-features]
+//! enable-rules = ["ENDF026"]
+//! exceptions = { "*" = ["serde"], "ENDF003" = ["openssl"] }
+//! ```
+//!
+//! Configuration files are also looked up in the parent directories, up to the root of the enclosing
+//! git repository, the way rustfmt and clippy find theirs, so a single file at the root of a monorepo
+//! can govern every workspace in it.
+//!
+//! These sources are layered, each one overriding the settings of the ones below it, one setting at a
+//! time:
+//!
+//! 1. The command line.
+//! 2. The configuration file next to the workspace's Cargo.toml, or the one given by --config.
+//! 3. The `[workspace.metadata.ensure-no-default-features]` table.
+//! 4. The nearest configuration file in the parent directories, unless --config is given.
+//!
+//! A setting found in a higher layer replaces the whole value of the lower ones, lists and tables
+//! included. On the command line, --enable, --disable, --warn, and --deny override the configured
+//! state of the rules they name only. The --exceptions option replaces every exception of the
+//! configuration, while --extend-exceptions adds to them instead.
+//!
+//! The `extends` setting names another configuration file, relative to the file declaring it, or to
+//! the workspace root for the metadata table. The extended file's settings apply beneath the ones of
+//! the file extending it, so several workspaces of a monorepo can share one policy and only override
+//! what differs locally. Extended files can extend others in turn.
+//!
+//! Each layer is validated on its own, and an unknown setting or a value of the wrong type makes the
+//! check fail with an error naming the setting, suggesting the closest valid name for likely typos.
+//!
+//! The `max-violations` setting defines per-directory violation budgets, which let critical parts of
+//! a monorepo be held to a stricter standard while the rest catches up. Keys are paths or glob
+//! patterns relative to the workspace root, with `*` matching everything, and the most specific key
+//! applies to each violation:
+//!
+//! ```toml
+//! max-violations = { "services/payments" = 0, "*" = 10 }
+//! ```
+//!
+//! When budgets are configured, violations covered by a budget that isn't exceeded are tolerated. If
+//! any budget is exceeded, the tool exits with status 4.
+//!
+//! The `version-precision` setting requires the version requirements in `[workspace.dependencies]` to
+//! specify at least the given components, either `major` (the default), `major.minor`, or
+//! `major.minor.patch`. Under `version-precision = "major.minor"`, `serde = { version = "1", ... }` is
+//! reported by rule ENDF014.
+//!
+//! The `allowed-git-hosts` and `allowed-git-crates` settings list the repository hosts and the crates
+//! that rule ENDF015, which forbids git dependencies in `[workspace.dependencies]`, lets through.
+//!
+//! The `denied-features` setting lists feature names, such as `openssl`, `vendored`, or `bundled`, that
+//! no dependency may enable. Rule ENDF019 reports every dependency whose `features` list includes one
+//! of them, both in `[workspace.dependencies]` and in the members' dependency tables.
+//!
+//! The `required-features` setting maps crate names to the features that must be enabled whenever the
+//! crate is declared with `default-features = false`, so `required-features.tokio = ["rt"]` makes rule
+//! ENDF020 report any `tokio` dependency without default features that doesn't enable `rt`.
+//!
+//! The `max-features` setting caps the number of features any dependency may enable, and
+//! `max-features-per-crate` sets the cap for specific crates. Rule ENDF021 reports dependencies going
+//! over their budget, counting the features member entries inherit from the workspace.
+//!
+//! Rule ENDF022 reports dependencies enabling umbrella features such as `full` that bring back most of
+//! a crate. The `umbrella-features` setting replaces the default list of `full`, `all`, and `complete`.
+//!
+//! The `allowed-default-features` setting lists the features, such as `std`, that a member's own
+//! `default` feature may enable. Rule ENDF023, which is disabled by default, reports members whose
+//! default feature enables anything else.
+//!
+//! Rule ENDF024 reports optional dependencies that no feature enables through `dep:name`, which makes
+//! Cargo create an implicit feature named after the dependency.
+//!
+//! The legacy `default_features` spelling still accepted by Cargo counts the same as `default-features`,
+//! but rule ENDF025 reports it so it can be migrated.
+//!
+//! Rule ENDF026, which is disabled by default, uses the package metadata reported by `cargo metadata`
+//! to find workspace dependencies declared with `default-features = false` whose `features` list
+//! exactly reproduces the crate's default features.
+//!
+//! The `no-std-members` setting designates members that must build without the standard library, and
+//! the --no-std option designates every member. Rule ENDF027 reports the dependencies of these members
+//! enabling `std`, directly or through the workspace entry they inherit. The `no-std-features` setting
+//! replaces the list of forbidden features, for example to also forbid `alloc`.
+//!
+//! Rule ENDF028 reports member dependencies declared inline with a version requirement that differs
+//! from the `[workspace.dependencies]` entry for the same crate.
+//!
+//! Rule ENDF029 reports entries of `[workspace.dependencies]` that no member inherits. Members are
+//! found the way Cargo finds them, so path dependencies living under the workspace root count even
+//! when `workspace.members` doesn't list them.
+//!
+//! Rule ENDF030, which is disabled by default, reads Cargo.lock to report crates appearing at several
+//! semver-incompatible versions in the workspace's dependency graph.
+//!
+//! Exceptions are honored by rules ENDF001, ENDF003, ENDF004, ENDF009, ENDF010, ENDF011, ENDF012,
+//! ENDF024, ENDF029, and ENDF030. The other rules report every entry they find, whatever the
+//! exceptions.
+//!
+//! The `exceptions` table of the configuration file scopes exceptions to specific rules, keyed by rule
+//! ID or name. For example, `exceptions = { "ENDF003" = ["openssl"] }` lets `openssl` through rule
+//! ENDF003 while rule ENDF001 still checks it, and the `*` key covers all of the rules above.
+//! Per-rule exceptions add to the ones given through --extend-exceptions, which cover all of them.
+//!
+//! An exception of the configuration can also be a table giving the reason for it, such as
+//! `{ crate = "serde", reason = "Needs std until the no_std port lands" }`. Reasons are echoed in the
+//! report, so auditors can see why each exemption exists, and the --require-exception-reasons option
+//! fails the check when an exception of the configuration doesn't give one.
+//!
+//! Long-lived exemptions can name who to ask about them with `owner`, as in
+//! `{ crate = "openssl", reason = "Vendored builds", owner = "@platform-team" }`. The owner is echoed
+//! along with the reason, and in the warning reporting an expired exception, so audits know who to
+//! follow up with.
+//!
+//! Temporary carve-outs can say when they end with `expires = "2025-12-31"` in their table. Past that
+//! day the exception no longer applies, so the dependency is checked again and the check fails, with a
+//! warning naming the expired exception, rather than letting the carve-out live forever.
+//!
+//! An exception's `members` field limits it to the manifests of the named members, so
+//! `exceptions = [{ crate = "clap", members = ["cli"] }]` lets only the `cli` member use `clap` with
+//! its default features. Such exceptions apply to the rules checking members' manifests, ENDF009,
+//! ENDF010, ENDF011, ENDF012, and ENDF024. As shown here, `exceptions` can also be an array, which
+//! is shorthand for a table with a single `*` key.
+//!
+//! Teams already maintaining a cargo-deny configuration can point the `cargo-deny` setting at their
+//! `deny.toml`, relative to the workspace root, so the policy stays in one place. Crates listed under
+//! `bans.skip` or `bans.skip-tree` become exceptions to rule ENDF030, and crates whose
+//! `[[bans.features]]` entry allows the `default` feature become exceptions to every rule honoring
+//! them. These add to the exceptions of the configuration, so the --exceptions option replaces
+//! them as well.
+//!
+//! An exception's `kinds` field limits it to dependencies of the given kinds, `normal`, `dev`, or
+//! `build`, so `exceptions = [{ crate = "criterion", kinds = ["dev"] }]` lets benchmarking tools keep
+//! their default features in `[dev-dependencies]` without exempting a regular dependency of the same
+//! name. Such exceptions apply where the checked entry sits in a member's dependency table, for
+//! rules ENDF009, ENDF011, ENDF012, and ENDF024, and can be combined with `members`.
+//!
+//! The `plugins` setting lists custom rules compiled to WebAssembly, relative to the workspace root.
+//! Each plugin receives the workspace's parsed manifests and returns findings, which are reported
+//! under rule ENDF031. Running plugins requires building the tool with the `wasm-plugins` feature, see
+//! `--explain ENDF031` for the plugin interface.
+//!
+//! The `scripts` setting names a directory, relative to the workspace root, of custom rules written as
+//! Rhai scripts. Each script sees the workspace's parsed manifests and reports findings with `report`,
+//! under rule ENDF031. Running scripts requires building the tool with the `scripting` feature.
+//!
+//! The --daemon option starts a long-running process that keeps the contents of manifests and the
+//! output of `cargo metadata` cached in memory and answers checks sent to it by other invocations
+//! using the --connect option, which print the daemon's report and exit with its status. This keeps
+//! repeated runs from hooks and CI steps fast in large monorepos. Both options accept a `host:port`
+//! address and default to `127.0.0.1:7491`. Cached manifests are reread whenever they change on disk,
+//! and `cargo metadata` is rerun whenever Cargo.lock or a local manifest changes, while manifests are
+//! still parsed by every check. Each connection is served on its own thread, and connections left idle
+//! for a minute are closed.
+//!
+//! To run the daemon as a shared service, --metrics-addr names another loopback address answering
+//! HTTP requests for `/healthz`, which responds `ok` while the daemon runs, and `/metrics`, which
+//! responds with Prometheus counters of the checks answered and of those that failed, the time taken
+//! by the last check, and the hits and misses of the manifest and `cargo metadata` caches.
+//!
+//! Since anyone able to connect can send it checks, the daemon only listens on loopback addresses and
+//! never writes files or runs code supplied by a workspace. Checks using --fix, --write-baseline,
+//! --summary-file, --emit-exceptions with a file, --config, or --check-packaged are refused, and so are
+//! workspaces whose configuration sets `plugins` or `scripts` or enables rule ENDF004, which packages
+//! members with `cargo package`. Such checks are run without --connect instead.
+//!
+//! The `lsp` subcommand runs a Language Server Protocol server over stdin and stdout, so editors can
+//! report violations while Cargo.toml is being edited:
+//!
+//! ```bash
+//! cargo ensure-no-default-features lsp
+//! ```
+//!
+//! Every `[workspace.dependencies]` entry without `default-features = false` is reported as a
+//! diagnostic on the dependency's name, together with a quick fix that adds the setting while
+//! preserving the file's formatting. Diagnostics follow the configuration of the workspace like the
+//! check does, including its exceptions, the severity it gives rule ENDF001, and the suppression
+//! comments of the edited file. The --exceptions option is supported as well.
 
+mod apply;
+mod baseline;
+mod budget;
+mod cache;
+mod check;
+mod config;
+mod daemon;
+mod deadline;
+mod deny;
+mod diff;
+mod discovery;
+mod features;
+mod fix;
+mod git;
+mod hook;
+mod init;
+mod lockfile;
+mod lsp;
+mod members;
+mod metadata;
+mod output;
+mod package;
+mod path_deps;
+mod plugins;
+mod rules;
+mod scripts;
+mod spans;
+mod suppressions;
 mod validation;
+mod vendor;
+mod versions;
+
+use anyhow::{Context, Result, bail};
+use apply::apply_report;
+use baseline::{compare_to_baseline, write_baseline};
+use cache::ManifestCache;
+use check::{CheckOptions, WorkspaceReport, check_workspace, fix_workspace};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use config::{Config, Exception, load_exceptions_file};
+use core::time::Duration;
+use daemon::DEFAULT_ADDR;
+use deadline::parse_duration;
+use diff::{diff_manifests, load_manifest_version};
+use discovery::{find_owning_workspaces, find_workspace_manifests, resolve_manifest_path};
+use git::{RevisionExports, changed_files};
+use hook::{HookKind, install_hook};
+use init::{emit_exceptions, init};
+use output::{
+    CodeFrames, CollapsedFinding, FindingGroup, GroupBy, OutputFormat, SortOrder, collapse_duplicates, group_findings,
+    parse_message_template, relative_path, top_offenders, violation_summary, write_report, write_summary_file, write_template,
+};
+use rules::{
+    Finding, LEAKED_DEFAULTS, PACKAGED_MANIFESTS, PATH_DEFAULTS, PATH_DEPENDENCIES, Preset, RULES, RuleSet, Severity, find_rule, parse_rule,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use validation::{ExceptionPattern, normalize_exceptions, split_version_constraint, validate_exceptions};
 
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
-use validation::validate_workspace_dependencies;
+/// Exit status used when violations are found
+const EXIT_VIOLATIONS: i32 = 1;
+
+/// Exit status used when a violation budget configured through `max-violations` is exceeded
+const EXIT_BUDGET: i32 = 4;
+
+/// Exit status used when the --deadline is exceeded before all the work completes
+///
+/// Status 2 is avoided since clap already uses it for command-line usage errors.
+const EXIT_DEADLINE: i32 = 3;
 
 /// Cargo subcommand to ensure workspace dependencies have default-features = false
 #[derive(Parser)]
@@ -46,15 +671,256 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Ensure all workspace dependencies have default-features = false
-    EnsureNoDefaultFeatures {
-        /// Path to Cargo.toml
+    EnsureNoDefaultFeatures(EnsureArgs),
+}
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+struct EnsureArgs {
+    #[command(subcommand)]
+    action: Option<Action>,
+
+    #[command(flatten)]
+    check: CheckArgs,
+}
+
+#[derive(Subcommand)]
+enum Action {
+    /// Run a Language Server Protocol server over stdin and stdout to report violations in editors
+    Lsp {
+        /// List of dependencies to exclude from default-features check
+        #[arg(long, short = 'e', value_delimiter = ',', env = "ENDF_EXCEPTIONS")]
+        exceptions: Option<Vec<String>>,
+    },
+
+    /// Compare the manifest at two git revisions or two file paths, reporting regressions and improvements
+    Diff {
+        /// The old version, as a git revision or the path of a manifest
+        old: String,
+
+        /// The new version, as a git revision or the path of a manifest (defaults to the working tree)
+        new: Option<String>,
+
+        /// Path to the Cargo.toml to compare when the versions are git revisions
         #[arg(long, default_value = "Cargo.toml", value_name = "PATH")]
         manifest_path: PathBuf,
 
         /// List of dependencies to exclude from default-features check
-        #[arg(long, short = 'e', value_delimiter = ',')]
+        #[arg(long, short = 'e', value_delimiter = ',', env = "ENDF_EXCEPTIONS")]
         exceptions: Option<Vec<String>>,
     },
+
+    /// Write a starter configuration listing the dependencies currently failing the check as exceptions
+    Init {
+        /// Path to the Cargo.toml of the workspace to configure
+        #[arg(long, default_value = "Cargo.toml", value_name = "PATH")]
+        manifest_path: PathBuf,
+
+        /// Write the settings into the manifest's metadata table instead of a configuration file
+        #[arg(long)]
+        metadata: bool,
+
+        /// Overwrite an existing configuration file
+        #[arg(long, conflicts_with = "metadata")]
+        force: bool,
+    },
+
+    /// Apply the automatic fixes recorded in a report written by `--format json`
+    Apply {
+        /// Path to the JSON report
+        #[arg(long, value_name = "PATH")]
+        from: PathBuf,
+
+        /// Also remove the [workspace.dependencies] entries no member inherits
+        #[arg(long)]
+        remove_unused: bool,
+    },
+
+    /// Install a git hook running the check, passing it any arguments given after `--`
+    InstallHook {
+        /// The hook to install
+        #[arg(long, value_enum, default_value = "pre-commit")]
+        hook: HookKind,
+
+        /// Overwrite an existing hook that wasn't installed by this tool
+        #[arg(long)]
+        force: bool,
+
+        /// Arguments passed to the check whenever the hook runs
+        #[arg(last = true, value_name = "ARGS")]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Args, Clone, Serialize, Deserialize)]
+#[expect(clippy::struct_excessive_bools, reason = "Command-line flags are naturally bools")]
+struct CheckArgs {
+    /// Path to Cargo.toml (can be repeated, or given several space-separated paths), defaulting to `ENDF_MANIFEST_PATH`, then Cargo.toml
+    #[arg(long, value_name = "PATH", num_args = 1..)]
+    manifest_path: Vec<PathBuf>,
+
+    /// List of dependencies to exclude from the rules honoring exceptions, replacing the exceptions of the configuration (can be repeated)
+    #[arg(long, short = 'e', value_delimiter = ',', env = "ENDF_EXCEPTIONS")]
+    exceptions: Option<Vec<String>>,
+
+    /// File listing dependencies to exclude from the rules honoring exceptions, one per line or as a TOML `exceptions` array
+    #[arg(long, value_name = "PATH", env = "ENDF_EXCEPTIONS_FILE")]
+    exceptions_file: Option<PathBuf>,
+
+    /// List of dependencies to exclude from the rules honoring exceptions, on top of the exceptions of the configuration
+    #[arg(long, value_delimiter = ',', value_name = "EXCEPTIONS")]
+    extend_exceptions: Vec<String>,
+
+    /// Fail when an exception of the configuration doesn't give a reason
+    #[arg(long)]
+    require_exception_reasons: bool,
+
+    /// Fail when an exception doesn't match any dependency or is no longer needed, instead of warning about it
+    #[arg(long)]
+    strict_exceptions: bool,
+
+    /// Fail when exceptions are given on the command line, so every exemption goes through the configuration
+    #[arg(long)]
+    no_cli_exceptions: bool,
+
+    /// Also check the manifests of path dependencies located outside the workspace
+    #[arg(long)]
+    follow_path_deps: bool,
+
+    /// Warn when published path dependencies have default features that consumers leave enabled
+    #[arg(long)]
+    check_path_defaults: bool,
+
+    /// Run `cargo package` for publishable members and check the normalized manifests it produces
+    #[arg(long)]
+    check_packaged: bool,
+
+    /// Warn when the dependency graph resolved from Cargo.lock enables defaults declared as disabled
+    #[arg(long)]
+    check_lockfile: bool,
+
+    /// Hold every member to the `no_std` rules, forbidding dependency features such as `std`
+    #[arg(long)]
+    no_std: bool,
+
+    /// Start from a curated set of rules and severities, replacing the configured preset
+    #[arg(long, value_enum, env = "ENDF_PRESET")]
+    preset: Option<Preset>,
+
+    /// Format of the report written to stdout, the human-readable report going to stderr otherwise, defaulting to `ENDF_FORMAT`, then human
+    #[arg(long, value_enum, conflicts_with = "emit_exceptions")]
+    format: Option<OutputFormat>,
+
+    /// Write each finding to stdout on a line shaped by a template, with placeholders `{dep}`, `{rule}`, `{rule_name}`, `{file}`, `{line}`, `{column}`, `{severity}`, and `{message}`
+    #[arg(long, value_name = "TEMPLATE", value_parser = parse_message_template, conflicts_with_all = ["format", "emit_exceptions"])]
+    message_template: Option<String>,
+
+    /// Group the findings of the human-readable report by rule, by the crate they're about, by severity, or by manifest
+    #[arg(long, value_enum, default_value_t = GroupBy::Rule)]
+    group_by: GroupBy,
+
+    /// Order the groups of the human-readable report and their findings by name, with errors first, or with the largest groups first
+    #[arg(long, value_enum)]
+    sort: Option<SortOrder>,
+
+    /// List the N dependencies and manifests accounting for the most errors after the summary of the human-readable report
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
+
+    /// Run a rule that is disabled by default, by ID or name (can be repeated)
+    #[arg(long, value_name = "RULE", value_parser = parse_rule)]
+    enable: Vec<String>,
+
+    /// Don't run a rule, by ID or name (can be repeated)
+    #[arg(long, value_name = "RULE", value_parser = parse_rule)]
+    disable: Vec<String>,
+
+    /// Report the findings of a rule as warnings, by ID or name (can be repeated)
+    #[arg(long, value_name = "RULE", value_parser = parse_rule)]
+    warn: Vec<String>,
+
+    /// Report the findings of a rule as errors, by ID or name (can be repeated)
+    #[arg(long, value_name = "RULE", value_parser = parse_rule)]
+    deny: Vec<String>,
+
+    /// Fix the problems that can be fixed automatically before checking, rewriting the manifests in place
+    #[arg(long)]
+    fix: bool,
+
+    /// With --fix, also remove the [workspace.dependencies] entries no member inherits
+    #[arg(long, requires = "fix")]
+    remove_unused: bool,
+
+    /// List every rule along with whether it's enabled, then exit
+    #[arg(long)]
+    #[serde(skip)]
+    list_rules: bool,
+
+    /// Print an extended explanation of a rule, by ID or name, then exit
+    #[arg(long, value_name = "RULE", value_parser = parse_rule)]
+    #[serde(skip)]
+    explain: Option<String>,
+
+    /// Directory produced by `cargo vendor` to audit for crates consumed with default features
+    #[arg(long, value_name = "DIR")]
+    vendor_dir: Option<PathBuf>,
+
+    /// Check every workspace found beneath a directory (defaults to the current directory)
+    #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".", conflicts_with = "manifest_path")]
+    recursive: Option<PathBuf>,
+
+    /// Only check the workspaces owning the Cargo.toml files among these changed files
+    #[arg(long, value_name = "FILE", num_args = 1.., conflicts_with_all = ["manifest_path", "recursive"])]
+    files: Option<Vec<PathBuf>>,
+
+    /// Record the violations found in a baseline file, to later compare runs against with --baseline
+    #[arg(long, value_name = "PATH")]
+    write_baseline: Option<PathBuf>,
+
+    /// Write a JSON summary of the run, with the number of findings by severity and rule, the exit status, and durations, to the given file
+    #[arg(long, value_name = "PATH")]
+    summary_file: Option<PathBuf>,
+
+    /// Compare the violations found to the ones recorded in a baseline file, reporting how many were
+    /// fixed, are new, and remain
+    #[arg(long, value_name = "PATH")]
+    baseline: Option<PathBuf>,
+
+    /// Print the dependencies failing the default-features check as configuration exceptions instead of
+    /// reporting them, or write them to the given file
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = "-")]
+    emit_exceptions: Option<PathBuf>,
+
+    /// Only check workspaces whose manifests changed since this git revision, reporting only new violations
+    #[arg(long, value_name = "GITREF", conflicts_with_all = ["manifest_path", "recursive", "files"])]
+    changed_since: Option<String>,
+
+    /// Glob of paths to skip during a recursive search (can be repeated)
+    #[arg(long, value_name = "GLOB", requires = "recursive")]
+    exclude: Vec<String>,
+
+    /// Path to a configuration file, instead of the one found next to each workspace manifest
+    #[arg(long, value_name = "PATH", env = "ENDF_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Abort the remaining work once this much time has elapsed (e.g. 30s, 500ms, 2m)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    deadline: Option<Duration>,
+
+    /// Run as a daemon keeping manifests cached in memory, answering checks sent with --connect
+    #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = DEFAULT_ADDR)]
+    #[serde(skip)]
+    daemon: Option<String>,
+
+    /// With --daemon, also answer HTTP requests for /healthz and /metrics on this address
+    #[arg(long, value_name = "ADDR", requires = "daemon")]
+    #[serde(skip)]
+    metrics_addr: Option<String>,
+
+    /// Send the check to a daemon started with --daemon instead of running it in this process
+    #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = DEFAULT_ADDR, conflicts_with = "daemon")]
+    #[serde(skip)]
+    connect: Option<String>,
 }
 
 // tested by integration tests
@@ -62,29 +928,758 @@ enum Commands {
 fn main() -> Result<()> {
     let cli = Cli::parse();
     match cli.command {
-        Commands::EnsureNoDefaultFeatures { manifest_path, exceptions } => {
-            let content = std::fs::read_to_string(&manifest_path).with_context(|| format!("Failed to read {}", manifest_path.display()))?;
-            let exceptions = exceptions.unwrap_or_default();
-
-            let (errors, found_deps) = validate_workspace_dependencies(&content, &exceptions)?;
-            if !errors.is_empty() {
-                eprintln!("❌ Found {} dependencies without default-features = false:\n", errors.len());
-                for error in &errors {
-                    eprintln!("{error}");
-                }
-                std::process::exit(1);
+        Commands::EnsureNoDefaultFeatures(args) => match args.action {
+            Some(Action::Lsp { exceptions }) => lsp::run(&normalize_exceptions(exceptions.unwrap_or_default())),
+            Some(Action::Diff {
+                old,
+                new,
+                manifest_path,
+                exceptions,
+            }) => run_diff(
+                &old,
+                new.as_deref(),
+                &manifest_path,
+                &normalize_exceptions(exceptions.unwrap_or_default()),
+            ),
+            Some(Action::Init {
+                manifest_path,
+                metadata,
+                force,
+            }) => {
+                let (path, count) = init(&resolve_manifest_path(&manifest_path)?, metadata, force)?;
+                println!("✅ Wrote {} with {count} exception(s)", path.display());
+                Ok(())
+            }
+            Some(Action::Apply { from, remove_unused }) => run_apply(&from, remove_unused),
+            Some(Action::InstallHook { hook, force, args }) => {
+                let path = install_hook(hook, &args, force)?;
+                println!("✅ Installed hook at {}", path.display());
+                Ok(())
             }
+            None => run_check(&args.check),
+        },
+    }
+}
+
+/// Compares two versions of a manifest and exits with a nonzero status when it regressed
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_diff(old: &str, new: Option<&str>, manifest_path: &Path, exceptions: &[String]) -> Result<()> {
+    validate_exceptions(exceptions)?;
+    let old_content = load_manifest_version(old, manifest_path)?;
+    let new_content = match new {
+        Some(new) => load_manifest_version(new, manifest_path)?,
+        None => std::fs::read_to_string(manifest_path).with_context(|| format!("Failed to read {}", manifest_path.display()))?,
+    };
 
-            // Warn if any exception was not found in the dependencies
-            for exception in &exceptions {
-                if !found_deps.contains(exception) {
-                    eprintln!("⚠️ Warning: exception '{exception}' was not found in [workspace.dependencies]");
+    let new = new.unwrap_or("the working tree");
+    let diff = diff_manifests(&old_content, &new_content, exceptions)?;
+    if !diff.improvements.is_empty() {
+        println!("📈 Found {} improvements between {old} and {new}:\n", diff.improvements.len());
+        for improvement in &diff.improvements {
+            println!("  - {improvement}");
+        }
+
+        println!();
+    }
+
+    if !diff.regressions.is_empty() {
+        eprintln!("❌ Found {} regressions between {old} and {new}:\n", diff.regressions.len());
+        for regression in &diff.regressions {
+            eprintln!("  - {regression}");
+        }
+
+        std::process::exit(EXIT_VIOLATIONS);
+    }
+
+    println!("✅ No regressions between {old} and {new}");
+    Ok(())
+}
+
+/// Applies the fixes recorded in a JSON report and prints what was applied
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_apply(report_path: &Path, remove_unused: bool) -> Result<()> {
+    let applied = apply_report(report_path, remove_unused)?;
+    if applied.is_empty() {
+        println!("✅ No fixes to apply in {}", report_path.display());
+        return Ok(());
+    }
+
+    println!("🔧 Applied {} fixes:\n", applied.len());
+    for fix in &applied {
+        println!("  - {fix}");
+    }
+
+    Ok(())
+}
+
+/// Checks the selected workspaces and exits with a nonzero status when the check fails
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_check(args: &CheckArgs) -> Result<()> {
+    if args.list_rules {
+        return list_rules(args);
+    }
+
+    if let Some(key) = &args.explain {
+        let rule = find_rule(key)?;
+        let default = if rule.enabled_by_default { "enabled" } else { "disabled" };
+        println!("{} ({}): {}\n", rule.id, rule.name, rule.summary);
+        println!("Default severity: {}, {default} by default\n", rule.severity);
+        print!("{}", rule.explanation);
+        return Ok(());
+    }
+
+    if let Some(addr) = &args.daemon {
+        let cache = ManifestCache::default();
+        return daemon::serve(
+            addr,
+            args.metrics_addr.as_deref(),
+            |args: CheckArgs, stdout, stderr| {
+                args.ensure_daemon_safe()?;
+                execute(&args, stdout, stderr, Some(&cache))
+            },
+            |out| cache.write_metrics(out),
+        );
+    }
+
+    if let Some(addr) = &args.connect {
+        let response = daemon::send(addr, &args.absolute()?)?;
+        print!("{}", response.stdout);
+        eprint!("{}", response.stderr);
+        if let Some(error) = response.error {
+            bail!(error);
+        }
+
+        std::process::exit(response.status);
+    }
+
+    let status = execute(args, &mut std::io::stdout().lock(), &mut std::io::stderr().lock(), None)?;
+    if status != 0 {
+        std::process::exit(status);
+    }
+
+    Ok(())
+}
+
+/// Prints every rule with its default severity and whether it's enabled for the selected workspace
+///
+/// The rules enabled in the configuration file found next to the first manifest path are taken
+/// into account, along with the rule options given on the command line.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn list_rules(args: &CheckArgs) -> Result<()> {
+    let manifest_path = resolve_manifest_path(&args.manifest_paths()[0])?;
+    let mut rules = Config::discover(&manifest_path, args.config.as_deref())?.rules(args.preset)?;
+    rules.merge(&args.rules()?);
+
+    let name_width = RULES.iter().map(|rule| rule.name.len()).max().unwrap_or_default();
+    for rule in RULES {
+        let status = rules
+            .severity(rule)
+            .map_or_else(|| "disabled".to_string(), |severity| format!("enabled ({severity})"));
+        println!(
+            "{}  {:name_width$}  {:7}  {status:17}  {}",
+            rule.id,
+            rule.name,
+            rule.severity.to_string(),
+            rule.summary
+        );
+    }
+
+    Ok(())
+}
+
+impl CheckArgs {
+    /// Returns the manifest paths given with --manifest-path, or else through `ENDF_MANIFEST_PATH`
+    ///
+    /// The environment variable is read here rather than by clap, which would otherwise count it as
+    /// conflicting with --recursive, --files, and --changed-since, and it's only used without them.
+    fn manifest_paths(&self) -> Vec<PathBuf> {
+        if !self.manifest_path.is_empty() {
+            return self.manifest_path.clone();
+        }
+
+        let path = std::env::var_os("ENDF_MANIFEST_PATH").filter(|path| !path.is_empty());
+        vec![path.map_or_else(|| PathBuf::from("Cargo.toml"), PathBuf::from)]
+    }
+
+    /// Returns the format given with --format, or else through `ENDF_FORMAT`
+    ///
+    /// Like `ENDF_MANIFEST_PATH`, the environment variable is read here rather than by clap, so it
+    /// doesn't conflict with --emit-exceptions and --message-template, which take precedence over it.
+    fn format(&self) -> Result<OutputFormat> {
+        if let Some(format) = self.format {
+            return Ok(format);
+        }
+
+        match std::env::var("ENDF_FORMAT") {
+            Ok(format) if !format.is_empty() => {
+                OutputFormat::from_str(&format, true).map_err(|err| anyhow::anyhow!("Invalid ENDF_FORMAT '{format}': {err}"))
+            }
+            _ => Ok(OutputFormat::Human),
+        }
+    }
+
+    /// Resolves the paths given on the command line against the current directory, and settles the
+    /// options read from the environment
+    ///
+    /// Used before sending a check to the daemon, whose working directory and environment usually
+    /// differ from the client's.
+    fn absolute(&self) -> Result<Self> {
+        let absolute = |path: &PathBuf| std::path::absolute(path).with_context(|| format!("Failed to resolve {}", path.display()));
+        let mut args = self.clone();
+        args.manifest_path = self.manifest_paths().iter().map(absolute).collect::<Result<_>>()?;
+        args.format = Some(self.format()?);
+        args.vendor_dir = self.vendor_dir.as_ref().map(absolute).transpose()?;
+        args.recursive = self.recursive.as_ref().map(absolute).transpose()?;
+        args.config = self.config.as_ref().map(absolute).transpose()?;
+        args.exceptions_file = self.exceptions_file.as_ref().map(absolute).transpose()?;
+        args.emit_exceptions = self
+            .emit_exceptions
+            .as_ref()
+            .map(|path| if path == Path::new("-") { Ok(path.clone()) } else { absolute(path) })
+            .transpose()?;
+        args.baseline = self.baseline.as_ref().map(absolute).transpose()?;
+        args.write_baseline = self.write_baseline.as_ref().map(absolute).transpose()?;
+        args.summary_file = self.summary_file.as_ref().map(absolute).transpose()?;
+
+        // The daemon doesn't run in the client's repository, so the changed files are determined here
+        args.files = match (&self.files, &self.changed_since) {
+            (Some(files), _) => Some(files.iter().map(absolute).collect::<Result<_>>()?),
+            (None, Some(rev)) => Some(changed_files(rev)?),
+            (None, None) => None,
+        };
+
+        args.daemon = None;
+        args.metrics_addr = None;
+        args.connect = None;
+        Ok(args)
+    }
+
+    /// Fails for the checks a daemon mustn't run on behalf of the clients connecting to it
+    ///
+    /// Anyone able to connect can send a check, so checks writing files or naming a configuration file,
+    /// which may load plugins and scripts, are refused.
+    fn ensure_daemon_safe(&self) -> Result<()> {
+        let refused = [
+            (self.fix, "--fix"),
+            (self.write_baseline.is_some(), "--write-baseline"),
+            (self.summary_file.is_some(), "--summary-file"),
+            (
+                self.emit_exceptions.as_ref().is_some_and(|path| path != Path::new("-")),
+                "--emit-exceptions with a file",
+            ),
+            (self.config.is_some(), "--config"),
+            (self.check_packaged, "--check-packaged"),
+        ];
+
+        if let Some((_, option)) = refused.iter().find(|(given, _)| *given) {
+            bail!("The daemon doesn't accept {option}, run the check without --connect instead");
+        }
+
+        Ok(())
+    }
+
+    /// Builds the rule overrides requested on the command line
+    fn rules(&self) -> Result<RuleSet> {
+        let mut rules = RuleSet::default();
+        let switches = [
+            (self.follow_path_deps, &PATH_DEPENDENCIES),
+            (self.check_path_defaults, &PATH_DEFAULTS),
+            (self.check_packaged, &PACKAGED_MANIFESTS),
+            (self.check_lockfile, &LEAKED_DEFAULTS),
+        ];
+        for (_, rule) in switches.into_iter().filter(|(enabled, _)| *enabled) {
+            rules.enable(rule);
+        }
+
+        for key in &self.enable {
+            rules.enable(find_rule(key)?);
+        }
+
+        for key in &self.warn {
+            rules.set_severity(find_rule(key)?, Severity::Warning);
+        }
+
+        for key in &self.deny {
+            rules.set_severity(find_rule(key)?, Severity::Error);
+        }
+
+        for key in &self.disable {
+            rules.disable(find_rule(key)?);
+        }
+
+        Ok(rules)
+    }
+}
+
+/// Checks the selected workspaces, prints the report, and returns the resulting exit status
+///
+/// With a structured --format or a --message-template, the document or the templated lines are the
+/// only thing written to stdout, while the human-readable report goes to stderr.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn execute(args: &CheckArgs, stdout: &mut dyn Write, stderr: &mut dyn Write, cache: Option<&ManifestCache>) -> Result<i32> {
+    let started = Instant::now();
+    let format = args.format()?;
+    let (status, reports) = if format == OutputFormat::Human && args.message_template.is_none() {
+        check_and_print(args, stdout, stderr, cache)?
+    } else {
+        let mut human = Vec::new();
+        let (status, reports) = check_and_print(args, &mut human, stderr, cache)?;
+        stderr.write_all(&human)?;
+        match &args.message_template {
+            Some(template) => write_template(template, &reports, stdout)?,
+            None => write_report(format, &reports, status == EXIT_DEADLINE, stdout)?,
+        }
+
+        (status, reports)
+    };
+
+    if let Some(path) = &args.summary_file {
+        write_summary_file(path, &reports, status, status == EXIT_DEADLINE, started.elapsed())?;
+    }
+
+    Ok(status)
+}
+
+/// Checks the selected workspaces and prints the human-readable report
+///
+/// # Returns
+///
+/// The resulting exit status, along with the reports of the checked workspaces.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn check_and_print(
+    args: &CheckArgs,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+    cache: Option<&ManifestCache>,
+) -> Result<(i32, Vec<WorkspaceReport>)> {
+    let deadline = args.deadline.map(|deadline| Instant::now() + deadline);
+    if args.no_cli_exceptions && (args.exceptions.is_some() || args.exceptions_file.is_some() || !args.extend_exceptions.is_empty()) {
+        bail!("Exceptions can't be given on the command line under --no-cli-exceptions, add them to the configuration file instead");
+    }
+
+    let mut exceptions = args.exceptions.clone().unwrap_or_default();
+    if let Some(path) = &args.exceptions_file {
+        exceptions.extend(load_exceptions_file(path)?);
+    }
+
+    exceptions.extend(args.extend_exceptions.iter().cloned());
+    let exceptions = normalize_exceptions(exceptions);
+    validate_exceptions(&exceptions)?;
+    let rules = args.rules()?;
+    let exports = args.changed_since.as_deref().map(RevisionExports::new);
+    let options = CheckOptions {
+        exceptions: &exceptions,
+        replace_exceptions: args.exceptions.is_some() || args.exceptions_file.is_some(),
+        require_exception_reasons: args.require_exception_reasons,
+        rules: &rules,
+        preset: args.preset,
+        vendor_dir: args.vendor_dir.as_deref(),
+        deadline,
+        config_path: args.config.as_deref(),
+        cache,
+        changed_since: exports.as_ref(),
+        no_std: args.no_std,
+
+        // Only the daemon keeps a cache
+        sandboxed: cache.is_some(),
+    };
+
+    let manifest_paths = match (&args.recursive, &args.files, &args.changed_since) {
+        (Some(dir), _, _) => find_workspace_manifests(dir, &args.exclude)?,
+        (None, Some(files), _) => find_owning_workspaces(files)?,
+        (None, None, Some(rev)) => find_owning_workspaces(&changed_files(rev)?)?,
+        (None, None, None) => args
+            .manifest_paths()
+            .iter()
+            .map(|path| resolve_manifest_path(path))
+            .collect::<Result<Vec<_>>>()?,
+    };
+
+    if let Some(path) = &args.emit_exceptions {
+        let (table, count) = emit_exceptions(&manifest_paths)?;
+        if path == Path::new("-") {
+            write!(stdout, "{table}")?;
+        } else {
+            std::fs::write(path, table).with_context(|| format!("Failed to write {}", path.display()))?;
+            writeln!(stdout, "✅ Wrote {} with {count} exception(s)", path.display())?;
+        }
+
+        return Ok((0, Vec::new()));
+    }
+
+    if args.fix {
+        let mut fixes = Vec::new();
+        for path in &manifest_paths {
+            fixes.extend(fix_workspace(path, &options, args.remove_unused)?);
+        }
+
+        if !fixes.is_empty() {
+            writeln!(stdout, "🔧 Applied {} fixes:\n", fixes.len())?;
+            for fix in &fixes {
+                writeln!(stdout, "  - {fix}")?;
+            }
+            writeln!(stdout)?;
+        }
+    }
+
+    let mut reports = Vec::new();
+    for path in &manifest_paths {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
+        let started = Instant::now();
+        let mut report = check_workspace(path, &options)?;
+        report.duration = started.elapsed();
+        reports.push(report);
+    }
+
+    let truncated = reports.len() < manifest_paths.len() || reports.iter().any(|report| report.truncated);
+    let violations: Vec<_> = reports.iter().flat_map(WorkspaceReport::errors).collect();
+    if let Some(path) = &args.baseline {
+        let comparison = compare_to_baseline(path, &violations)?;
+        writeln!(
+            stdout,
+            "📊 Compared to the baseline: {} fixed, {} new, {} remaining",
+            comparison.fixed, comparison.new, comparison.remaining
+        )?;
+    }
+
+    if let Some(path) = &args.write_baseline {
+        let count = write_baseline(path, &violations)?;
+        writeln!(stdout, "✅ Wrote {} with {count} violation(s)", path.display())?;
+    }
+
+    let status = print_reports(&reports, &exceptions, args, stdout, stderr)?;
+    if truncated {
+        writeln!(
+            stderr,
+            "⏱️ Deadline exceeded: this report is truncated, only {} of {} workspaces were fully checked",
+            reports.iter().filter(|report| !report.truncated).count(),
+            manifest_paths.len(),
+        )?;
+        return Ok((EXIT_DEADLINE, reports));
+    }
+
+    Ok((status, reports))
+}
+
+/// Prints the usage of the configured violation budgets and returns the resulting exit status
+///
+/// Violations are tolerated when every one of them is covered by a budget that isn't exceeded.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn budget_status(reports: &[WorkspaceReport], stdout: &mut dyn Write, stderr: &mut dyn Write) -> Result<i32> {
+    let budgets: Vec<_> = reports.iter().flat_map(|report| &report.budgets).collect();
+    if budgets.is_empty() {
+        return Ok(EXIT_VIOLATIONS);
+    }
+
+    writeln!(stderr, "\n📊 Violation budgets:\n")?;
+    for budget in &budgets {
+        let verdict = if budget.exceeded() { "exceeded" } else { "ok" };
+        writeln!(
+            stderr,
+            "  - '{}': {} of {} allowed ({verdict})",
+            budget.pattern, budget.used, budget.allowed
+        )?;
+    }
+
+    if budgets.iter().any(|budget| budget.exceeded()) {
+        writeln!(stderr, "\n❌ Violation budget exceeded")?;
+        return Ok(EXIT_BUDGET);
+    }
+
+    let unbudgeted: usize = reports.iter().map(|report| report.unbudgeted).sum();
+    if unbudgeted > 0 {
+        writeln!(stderr, "\n❌ Found {unbudgeted} violations not covered by any budget")?;
+        return Ok(EXIT_VIOLATIONS);
+    }
+
+    writeln!(stdout, "✅ All violations are within the configured budgets")?;
+    Ok(0)
+}
+
+/// Prints the dependencies and manifests accounting for the most errors, to help prioritize cleanup
+fn print_top_offenders(reports: &[WorkspaceReport], limit: usize, stderr: &mut dyn Write) -> Result<()> {
+    let top = top_offenders(reports, limit);
+    for (heading, offenders) in [("Dependencies", &top.dependencies), ("Manifests", &top.manifests)] {
+        if offenders.is_empty() {
+            continue;
+        }
+
+        writeln!(stderr, "🔝 {heading} with the most errors:")?;
+        for (name, count) in offenders {
+            writeln!(stderr, "  - {name}: {count}")?;
+        }
+
+        writeln!(stderr)?;
+    }
+
+    Ok(())
+}
+
+/// Prints a group of findings under a header saying what they share and how many there are
+///
+/// Each finding is prefixed with its severity and rule ID, and with its manifest's relative path unless
+/// findings are grouped by manifest.
+fn print_group(group: &FindingGroup<'_>, group_by: GroupBy, frames: &mut CodeFrames, stderr: &mut dyn Write) -> Result<()> {
+    let label = match (group_by, group.label.as_str()) {
+        (GroupBy::Crate, "") => "Findings not about a single crate".to_string(),
+        (GroupBy::Crate, name) => format!("'{name}'"),
+        (GroupBy::Severity, "error") => "Errors".to_string(),
+        (GroupBy::Severity, _) => "Warnings".to_string(),
+        (_, label) => label.to_string(),
+    };
+    let icon = if group.has_errors() { "❌" } else { "⚠️" };
+    writeln!(stderr, "{icon} {label} ({} finding(s)):\n", group.findings.len())?;
+
+    for collapsed in collapse_duplicates(&group.findings) {
+        let (_, finding) = collapsed.finding;
+        let icon = match (group_by, finding.severity) {
+            (GroupBy::Severity, _) => "",
+            (_, Severity::Error) => "❌ ",
+            (_, Severity::Warning) => "⚠️ ",
+        };
+        let path = if group_by == GroupBy::File || collapsed.manifests.len() > 1 {
+            String::new()
+        } else {
+            format!("{}: ", relative_path(&finding.manifest_path))
+        };
+
+        writeln!(
+            stderr,
+            "  - {icon}[{}] {path}{}{}",
+            finding.rule.id,
+            finding.message,
+            seen_in(&collapsed)
+        )?;
+        write!(stderr, "{}", frames.render(finding, "    "))?;
+        write!(stderr, "{}", seen_in_manifests(&collapsed))?;
+    }
+
+    writeln!(stderr)?;
+    Ok(())
+}
+
+/// Prints findings grouped by the manifest they were found in, with a header naming each manifest
+///
+/// Headers give paths relative to the current directory. They're left out when every finding is in the
+/// root manifest of its workspace and the workspace needn't be named.
+fn print_by_manifest(
+    findings: &[(&WorkspaceReport, &Finding)],
+    show_workspace: bool,
+    frames: &mut CodeFrames,
+    stderr: &mut dyn Write,
+) -> Result<()> {
+    let (repeated, single): (Vec<_>, Vec<_>) = collapse_duplicates(findings)
+        .into_iter()
+        .partition(|collapsed| collapsed.manifests.len() > 1);
+
+    let mut by_manifest: Vec<(&Path, Vec<&Finding>)> = Vec::new();
+    for (_, finding) in single.iter().map(|collapsed| collapsed.finding) {
+        match by_manifest.iter_mut().find(|(path, _)| *path == finding.manifest_path) {
+            Some((_, group)) => group.push(finding),
+            None => by_manifest.push((&finding.manifest_path, vec![finding])),
+        }
+    }
+
+    let headers = show_workspace
+        || !repeated.is_empty()
+        || by_manifest.len() > 1
+        || findings
+            .iter()
+            .any(|(report, finding)| finding.manifest_path != report.manifest_path);
+    if !repeated.is_empty() {
+        writeln!(stderr, "\nIn several members:")?;
+        for collapsed in &repeated {
+            let (_, finding) = collapsed.finding;
+            writeln!(stderr, "  - {}{}", finding.message, seen_in(collapsed))?;
+            write!(stderr, "{}", frames.render(finding, "    "))?;
+            write!(stderr, "{}", seen_in_manifests(collapsed))?;
+        }
+    }
+
+    for (path, group) in by_manifest {
+        writeln!(stderr)?;
+        if headers {
+            writeln!(stderr, "In {}:", relative_path(path))?;
+        }
+
+        for finding in group {
+            writeln!(stderr, "  - {}", finding.message)?;
+            write!(stderr, "{}", frames.render(finding, "    "))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the annotation of a finding seen in several members, or nothing for a finding seen in one
+fn seen_in(collapsed: &CollapsedFinding<'_>) -> String {
+    match collapsed.manifests.len() {
+        1 => String::new(),
+        n => format!(" (seen in {n} members)"),
+    }
+}
+
+/// Returns a line listing the manifests a finding seen in several members was found in, naming the first few
+fn seen_in_manifests(collapsed: &CollapsedFinding<'_>) -> String {
+    const LISTED: usize = 3;
+
+    if collapsed.manifests.len() < 2 {
+        return String::new();
+    }
+
+    let listed: Vec<_> = collapsed.manifests.iter().take(LISTED).map(|path| relative_path(path)).collect();
+    let more = match collapsed.manifests.len().saturating_sub(LISTED) {
+        0 => String::new(),
+        n => format!(" and {n} more"),
+    };
+    format!("    = seen in: {}{more}\n", listed.join(", "))
+}
+
+/// Prints the combined report for all checked workspaces and returns the resulting exit status
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_reports(
+    reports: &[WorkspaceReport],
+    exceptions: &[String],
+    args: &CheckArgs,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> Result<i32> {
+    let strict_exceptions = args.strict_exceptions;
+    let mut frames = CodeFrames::default();
+    for report in reports {
+        if let Some(audit) = &report.vendor_audit {
+            if audit.is_empty() {
+                writeln!(stdout, "📦 No vendored crates are consumed with default features enabled")?;
+            } else {
+                writeln!(
+                    stdout,
+                    "📦 Found {} vendored crates consumed with default features enabled:\n",
+                    audit.len()
+                )?;
+                for (name, consumers) in audit {
+                    writeln!(stdout, "  - '{name}': by {}", consumers.join(", "))?;
                 }
+                writeln!(stdout)?;
+            }
+        }
+
+        print_exception_notes(report, stdout, stderr)?;
+
+        if args.group_by == GroupBy::Rule {
+            for finding in report.warnings() {
+                writeln!(stderr, "⚠️ Warning: {} [{}]", finding.message, finding.rule.id)?;
+                write!(stderr, "{}", frames.render(finding, "    "))?;
             }
+        }
+    }
 
-            println!("✅ All required workspace dependencies have default-features = false");
+    if let Some(summary) = violation_summary(reports) {
+        writeln!(stderr, "📊 {summary}\n")?;
+        if let Some(limit) = args.top {
+            print_top_offenders(reports, limit, stderr)?;
         }
     }
 
+    if args.group_by == GroupBy::Rule {
+        let show_workspace = args.recursive.is_some() || reports.len() > 1;
+        let errors = reports
+            .iter()
+            .flat_map(|report| report.errors().map(move |finding| (report, finding)));
+        for group in group_findings(errors, GroupBy::Rule, args.sort) {
+            let rule = group.findings[0].1.rule;
+            writeln!(stderr, "❌ Found {} {} [{}]:", group.findings.len(), rule.heading, rule.id)?;
+            print_by_manifest(&group.findings, show_workspace, &mut frames, stderr)?;
+            writeln!(stderr)?;
+        }
+    } else {
+        let findings = reports
+            .iter()
+            .flat_map(|report| report.findings.iter().map(move |finding| (report, finding)));
+        for group in group_findings(findings, args.group_by, args.sort) {
+            print_group(&group, args.group_by, &mut frames, stderr)?;
+        }
+    }
+
+    // Exceptions not matching any dependency, or no longer needed, are stale
+    let mut stale: Vec<_> = exceptions
+        .iter()
+        .filter(|exception| {
+            let (pattern, _) = split_version_constraint(exception);
+            let pattern = ExceptionPattern::new(pattern);
+            !reports
+                .iter()
+                .any(|report| report.found_deps.iter().any(|name| pattern.matches(name)))
+        })
+        .map(|exception| format!("'{exception}' was not found in [workspace.dependencies]"))
+        .collect();
+
+    let obsolete: BTreeSet<_> = reports.iter().flat_map(|report| &report.obsolete_exceptions).collect();
+    stale.extend(
+        obsolete
+            .into_iter()
+            .map(|exception| format!("'{exception}' is obsolete, the dependencies it covers already have default-features = false")),
+    );
+
+    if strict_exceptions {
+        for message in &stale {
+            writeln!(stderr, "❌ Exception {message}")?;
+        }
+    }
+
+    if reports.iter().any(|report| report.error_count() > 0) {
+        let status = budget_status(reports, stdout, stderr)?;
+        return Ok(if status == 0 && strict_exceptions && !stale.is_empty() {
+            EXIT_VIOLATIONS
+        } else {
+            status
+        });
+    }
+
+    if strict_exceptions && !stale.is_empty() {
+        return Ok(EXIT_VIOLATIONS);
+    }
+
+    for message in stale {
+        writeln!(stderr, "⚠️ Warning: exception {message}")?;
+    }
+
+    if reports.is_empty() {
+        writeln!(stdout, "✅ No workspaces found to check")?;
+    } else {
+        writeln!(stdout, "✅ All required workspace dependencies have default-features = false")?;
+    }
+
+    Ok(0)
+}
+
+/// Prints the reasons and owners of the exceptions in effect, and warns about the expired ones
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_exception_notes(report: &WorkspaceReport, stdout: &mut dyn Write, stderr: &mut dyn Write) -> Result<()> {
+    let owner = |exception: &Exception| {
+        exception
+            .owner
+            .as_ref()
+            .map(|owner| format!(" (owner: {owner})"))
+            .unwrap_or_default()
+    };
+
+    for (key, exception) in &report.exception_reasons {
+        let reason = exception.reason.as_ref().map(|reason| format!(": {reason}")).unwrap_or_default();
+        writeln!(stdout, "📝 Exception '{}' for '{key}'{reason}{}", exception.name, owner(exception))?;
+    }
+
+    for (key, exception) in &report.expired_exceptions {
+        let expires = exception.expires.map(|expires| expires.to_string()).unwrap_or_default();
+        writeln!(
+            stderr,
+            "⚠️ Warning: exception '{}' for '{key}' expired on {expires}{}",
+            exception.name,
+            owner(exception)
+        )?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,138 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::cache::{ManifestCache, read_manifest};
+use crate::validation::member_dependency_tables;
+
+/// A member crate of the workspace being validated
+#[derive(Debug)]
+pub struct Member {
+    /// The package name of the member
+    pub name: String,
+
+    /// Path to the member's Cargo.toml
+    pub manifest_path: PathBuf,
+
+    /// Raw content of the member's Cargo.toml
+    pub content: String,
+}
+
+/// Loads the manifests of all workspace members
+///
+/// Member paths are taken from `workspace.members`, with glob patterns expanded the same way Cargo
+/// does, minus anything listed in `workspace.exclude`. The root manifest itself is included when it
+/// also defines a `[package]`. Like Cargo, path dependencies of members that live under the root
+/// directory are members too, even when `workspace.members` doesn't list them. Member entries whose
+/// directory doesn't contain a Cargo.toml are silently skipped. Member manifests are read through the
+/// cache when one is given.
+pub fn load_members(manifest_path: &Path, content: &str, cache: Option<&ManifestCache>) -> Result<Vec<Member>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let root = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+    let workspace = parsed.get("workspace");
+
+    let excluded: Vec<PathBuf> = string_array(workspace.and_then(|w| w.get("exclude")))
+        .map(|path| normalize(&root.join(path)))
+        .collect();
+    let is_excluded = |path: &Path| excluded.iter().any(|excluded| path.starts_with(excluded));
+
+    let mut member_dirs = Vec::new();
+    if parsed.get("package").is_some() {
+        member_dirs.push(root.to_path_buf());
+    }
+
+    for pattern in string_array(workspace.and_then(|w| w.get("members"))) {
+        let full_pattern = root.join(pattern);
+        let full_pattern = full_pattern.to_string_lossy();
+        let paths = glob::glob(&full_pattern).with_context(|| format!("Invalid workspace member pattern '{pattern}'"))?;
+        for path in paths {
+            let path = path.with_context(|| format!("Failed to expand workspace member pattern '{pattern}'"))?;
+            let path = normalize(&path);
+            if !is_excluded(&path) && !member_dirs.contains(&path) {
+                member_dirs.push(path);
+            }
+        }
+    }
+
+    let root_dir = normalize(root);
+    let workspace_dependencies = workspace.and_then(|w| w.get("dependencies"));
+    let mut members = Vec::new();
+    let mut index = 0;
+    while let Some(dir) = member_dirs.get(index).cloned() {
+        index += 1;
+        let member_manifest = dir.join("Cargo.toml");
+        if !member_manifest.is_file() {
+            continue;
+        }
+
+        let member_content = read_manifest(&member_manifest, cache)?;
+        let member_parsed: toml::Value =
+            toml::from_str(&member_content).with_context(|| format!("Failed to parse {}", member_manifest.display()))?;
+        let name = member_parsed
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(toml::Value::as_str)
+            .map_or_else(|| dir.display().to_string(), str::to_string);
+
+        for path in path_dependencies(&member_parsed, &dir, root, workspace_dependencies) {
+            if path.starts_with(&root_dir) && !is_excluded(&path) && !member_dirs.contains(&path) {
+                member_dirs.push(path);
+            }
+        }
+
+        members.push(Member {
+            name,
+            manifest_path: member_manifest,
+            content: member_content,
+        });
+    }
+
+    Ok(members)
+}
+
+/// Iterates over the string elements of an optional TOML array
+fn string_array(value: Option<&toml::Value>) -> impl Iterator<Item = &str> {
+    value
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+}
+
+/// Returns the directories of a member's path dependencies
+///
+/// Dependencies inherited with `workspace = true` are resolved through `[workspace.dependencies]`,
+/// whose paths are relative to the workspace root rather than to the member.
+fn path_dependencies(member: &toml::Value, dir: &Path, root: &Path, workspace_dependencies: Option<&toml::Value>) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for (_, table) in member_dependency_tables(member) {
+        for (name, value) in table {
+            let path = if value.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+                workspace_dependencies
+                    .and_then(|deps| deps.get(name))
+                    .and_then(|dep| dep.get("path"))
+                    .and_then(toml::Value::as_str)
+                    .map(|path| root.join(path))
+            } else {
+                value.get("path").and_then(toml::Value::as_str).map(|path| dir.join(path))
+            };
+            paths.extend(path.map(|path| normalize(&path)));
+        }
+    }
+    paths
+}
+
+/// Resolves `.` and `..` components and trailing separators so equivalent member paths compare equal
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+                let _ = normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
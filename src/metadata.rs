@@ -0,0 +1,33 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use crate::cache::ManifestCache;
+
+/// Runs `cargo metadata` for a workspace and returns its parsed output
+///
+/// With `no_deps` set, only the workspace members are described and no dependency resolution happens.
+/// The output is reused from the cache when one is given and nothing it depends on changed.
+pub fn cargo_metadata(manifest_path: &Path, no_deps: bool, cache: Option<&ManifestCache>) -> Result<serde_json::Value> {
+    let run = || run_cargo_metadata(manifest_path, no_deps);
+    cache.map_or_else(run, |cache| cache.metadata(manifest_path, no_deps, run))
+}
+
+/// Runs `cargo metadata` for a workspace, bypassing any cache
+fn run_cargo_metadata(manifest_path: &Path, no_deps: bool) -> Result<serde_json::Value> {
+    let mut command = Command::new("cargo");
+    let _ = command
+        .args(["metadata", "--format-version", "1", "--manifest-path"])
+        .arg(manifest_path);
+    if no_deps {
+        let _ = command.arg("--no-deps");
+    }
+
+    let output = command.output().context("Failed to run cargo metadata")?;
+    if !output.status.success() {
+        bail!("cargo metadata failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    serde_json::from_slice(&output.stdout).context("Failed to parse the output of cargo metadata")
+}
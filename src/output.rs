@@ -0,0 +1,275 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::check::WorkspaceReport;
+use crate::fix::fix_workspace_dependency;
+use crate::rules::Finding;
+
+mod badge;
+mod compact;
+mod csv;
+mod dot;
+mod frame;
+mod group;
+mod html;
+mod json;
+mod junit;
+mod markdown;
+mod sarif;
+mod summary;
+mod teamcity;
+mod template;
+mod top;
+mod usage;
+
+pub use frame::CodeFrames;
+pub use group::{CollapsedFinding, FindingGroup, GroupBy, SortOrder, collapse_duplicates, group_findings};
+pub use summary::{violation_summary, write_summary_file};
+pub use template::{parse_message_template, write_template};
+pub use top::top_offenders;
+
+/// Format of the report written to stdout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable text, with the findings on stderr
+    #[default]
+    Human,
+
+    /// A JSON document describing the findings
+    Json,
+
+    /// A SARIF 2.1.0 log, as uploaded to GitHub code scanning
+    Sarif,
+
+    /// A `JUnit` XML report, as rendered by CI systems
+    Junit,
+
+    /// `TeamCity` service messages reporting the findings as inspections
+    Teamcity,
+
+    /// A Markdown report, as pasted into pull request descriptions
+    Markdown,
+
+    /// A self-contained HTML page, as shared with people who won't read CI logs
+    Html,
+
+    /// A CSV matrix of every dependency of every member, with its default-features status and features
+    Csv,
+
+    /// A Graphviz DOT graph of the features members enable on their dependencies
+    Dot,
+
+    /// A shields.io endpoint document for a compliance badge
+    Badge,
+
+    /// One line per finding, as `path:line:column: severity[ID]: message`
+    Compact,
+
+    /// One line per finding, as `path:line:column: severity: message [ID]` for editor quickfix lists
+    Quickfix,
+}
+
+/// A finding along with what the structured formats report about it
+#[derive(Debug)]
+struct ReportedFinding<'a> {
+    /// The finding itself
+    finding: &'a Finding,
+
+    /// Fingerprint identifying the violation across runs, relative to the workspace root
+    fingerprint: String,
+
+    /// Content of the manifest the finding is about, when it could be read
+    content: Option<Rc<str>>,
+}
+
+/// Writes the findings of the checked workspaces to `out` in the given structured format
+///
+/// Nothing is written for the human-readable format, which is printed as the workspaces are checked.
+/// `truncated` tells whether the deadline passed before every workspace was fully checked.
+pub fn write_report(format: OutputFormat, reports: &[WorkspaceReport], truncated: bool, out: &mut dyn Write) -> Result<()> {
+    match format {
+        OutputFormat::Human => Ok(()),
+        OutputFormat::Json => json::write_json(reports, truncated, out),
+        OutputFormat::Sarif => sarif::write_sarif(reports, out),
+        OutputFormat::Junit => junit::write_junit(reports, out),
+        OutputFormat::Teamcity => teamcity::write_teamcity(reports, out),
+        OutputFormat::Markdown => markdown::write_markdown(reports, truncated, out),
+        OutputFormat::Html => html::write_html(reports, truncated, out),
+        OutputFormat::Csv => csv::write_csv(reports, out),
+        OutputFormat::Dot => dot::write_dot(reports, out),
+        OutputFormat::Badge => badge::write_badge(reports, truncated, out),
+        OutputFormat::Compact => compact::write_lines(reports, compact::LineLayout::Compiler, out),
+        OutputFormat::Quickfix => compact::write_lines(reports, compact::LineLayout::Quickfix, out),
+    }
+}
+
+/// Pairs every finding of the reports with its fingerprint and the content of its manifest
+fn reported_findings(reports: &[WorkspaceReport]) -> Vec<ReportedFinding<'_>> {
+    let mut contents: BTreeMap<PathBuf, Option<Rc<str>>> = BTreeMap::new();
+    reports
+        .iter()
+        .flat_map(|report| {
+            let root = report.manifest_path.parent().unwrap_or_else(|| Path::new(""));
+            report.findings.iter().map(move |finding| (root, finding))
+        })
+        .map(|(root, finding)| {
+            let content = contents
+                .entry(finding.manifest_path.clone())
+                .or_insert_with(|| std::fs::read_to_string(&finding.manifest_path).ok().map(Rc::from))
+                .clone();
+
+            ReportedFinding {
+                finding,
+                fingerprint: finding.fingerprint(root),
+                content,
+            }
+        })
+        .collect()
+}
+
+/// Returns what the automatic fix of a finding does and the manifest content it produces
+///
+/// Returns `None` unless the finding is about an entry of `[workspace.dependencies]` of a rule the tool
+/// knows how to fix.
+fn automatic_fix(finding: &Finding, content: &str) -> Option<(&'static str, String)> {
+    let name = finding.dependency.as_deref()?;
+    if finding.section.as_deref() != Some("[workspace.dependencies]") {
+        return None;
+    }
+
+    fix_workspace_dependency(finding.rule, content, name).ok().flatten()
+}
+
+/// Returns a path relative to the current directory with `/` separators when the path is beneath it,
+/// or the absolute path otherwise
+pub fn relative_path(path: &Path) -> String {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    let Ok(relative) = absolute.strip_prefix(&current_dir) else {
+        return absolute.display().to_string();
+    };
+
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns a path as a URI, relative to the current directory when the path is beneath it
+fn relative_uri(path: &Path) -> String {
+    let relative = relative_path(path);
+    if !Path::new(&relative).is_absolute() {
+        return relative;
+    }
+
+    let absolute = relative.replace('\\', "/");
+    format!("file://{}{absolute}", if absolute.starts_with('/') { "" } else { "/" })
+}
+
+/// Replaces the line breaks of a message with spaces, for formats writing each finding on one line
+fn single_line(message: &str) -> String {
+    message.replace(['\r', '\n'], " ")
+}
+
+/// Escapes the characters of a text that are special in XML and HTML
+fn escape_markup(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Findings and reports shared by the tests of the output formats
+#[cfg(test)]
+pub mod fixtures {
+    use std::path::Path;
+
+    use crate::check::WorkspaceReport;
+    use crate::rules::{Finding, Rule, Severity, Violation};
+    use crate::validation::DefaultFeaturesProblem;
+
+    /// Builds a finding of a rule in the given manifest, without a location
+    pub fn finding(rule: &'static Rule, severity: Severity, manifest_path: impl AsRef<Path>, violation: Violation) -> Finding {
+        Finding::new(rule, severity, manifest_path.as_ref(), violation, None)
+    }
+
+    /// Builds a violation about the `[workspace.dependencies]` entry of a dependency
+    pub fn entry(name: &str, message: &str) -> Violation {
+        Violation::workspace_dependency(name, message.to_string())
+    }
+
+    /// Builds a violation about a `[workspace.dependencies]` entry that doesn't disable default features
+    pub fn lacking(name: &str, problem: DefaultFeaturesProblem) -> Violation {
+        Violation::default_features(&["workspace".to_string(), "dependencies".to_string()], name, problem)
+    }
+
+    /// Builds a violation that isn't about a single entry of the manifest
+    pub fn general(message: &str) -> Violation {
+        Violation::manifest(message.to_string())
+    }
+
+    /// Builds the report of the workspace at the given manifest, holding the given findings
+    pub fn report(manifest_path: &Path, findings: Vec<Finding>) -> WorkspaceReport {
+        WorkspaceReport {
+            manifest_path: manifest_path.to_path_buf(),
+            findings,
+            ..WorkspaceReport::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::{entry, finding, report};
+    use super::*;
+    use crate::rules::{DEFAULT_FEATURES, Severity};
+
+    #[test]
+    fn test_reported_findings() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "[dependencies]\ntokio = \"1\"\n").unwrap();
+
+        let report = report(
+            &manifest_path,
+            vec![finding(
+                &DEFAULT_FEATURES,
+                Severity::Error,
+                &manifest_path,
+                entry("tokio", "'tokio': uses simple version string"),
+            )],
+        );
+
+        let reported = reported_findings(core::slice::from_ref(&report));
+        assert_eq!(reported[0].fingerprint, report.findings[0].fingerprint(dir.path()));
+        assert_eq!(reported[0].content.as_deref(), Some("[dependencies]\ntokio = \"1\"\n"));
+    }
+
+    #[test]
+    fn test_escape_markup() {
+        assert_eq!(
+            escape_markup(r#"'a' < "b" & c > d"#),
+            "&apos;a&apos; &lt; &quot;b&quot; &amp; c &gt; d"
+        );
+    }
+}
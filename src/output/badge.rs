@@ -0,0 +1,94 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::check::WorkspaceReport;
+
+/// The shields.io endpoint document describing the badge
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Badge {
+    /// Version of the endpoint schema, always 1
+    schema_version: u8,
+
+    /// Text on the left side of the badge
+    label: &'static str,
+
+    /// Text on the right side of the badge
+    message: String,
+
+    /// Background color of the right side of the badge
+    color: &'static str,
+}
+
+/// Writes the compliance status of the checked workspaces as a shields.io endpoint badge
+///
+/// The badge reads "passing" in green when nothing was found, the number of warnings in yellow when
+/// only warnings were found, and the number of violations in red otherwise. A truncated report reads
+/// "incomplete" in grey, since its counts can't be trusted.
+pub fn write_badge(reports: &[WorkspaceReport], truncated: bool, out: &mut dyn Write) -> Result<()> {
+    let errors: usize = reports.iter().map(WorkspaceReport::error_count).sum();
+    let warnings: usize = reports.iter().map(|report| report.warnings().count()).sum();
+
+    let (message, color) = if truncated {
+        ("incomplete".to_string(), "lightgrey")
+    } else if errors > 0 {
+        (format!("{errors} violation{}", if errors == 1 { "" } else { "s" }), "red")
+    } else if warnings > 0 {
+        (format!("{warnings} warning{}", if warnings == 1 { "" } else { "s" }), "yellow")
+    } else {
+        ("passing".to_string(), "brightgreen")
+    };
+
+    let badge = Badge {
+        schema_version: 1,
+        label: "default features",
+        message,
+        color,
+    };
+
+    serde_json::to_writer(&mut *out, &badge).context("Failed to write the badge")?;
+    writeln!(out)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::output::fixtures::{entry, finding, report};
+    use crate::rules::{DEFAULT_FEATURES, Finding, Severity};
+
+    fn badge(findings: Vec<Finding>, truncated: bool) -> String {
+        let report = report(Path::new("Cargo.toml"), findings);
+
+        let mut out = Vec::new();
+        write_badge(&[report], truncated, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_write_badge() {
+        let finding = |severity| {
+            finding(
+                &DEFAULT_FEATURES,
+                severity,
+                Path::new("Cargo.toml"),
+                entry("tokio", "'tokio': uses simple version string"),
+            )
+        };
+
+        assert_eq!(
+            badge(Vec::new(), false),
+            "{\"schemaVersion\":1,\"label\":\"default features\",\"message\":\"passing\",\"color\":\"brightgreen\"}\n"
+        );
+        assert!(
+            badge(vec![finding(Severity::Error), finding(Severity::Error)], false)
+                .contains("\"message\":\"2 violations\",\"color\":\"red\"")
+        );
+        assert!(badge(vec![finding(Severity::Warning)], false).contains("\"message\":\"1 warning\",\"color\":\"yellow\""));
+        assert!(badge(Vec::new(), true).contains("\"message\":\"incomplete\",\"color\":\"lightgrey\""));
+    }
+}
@@ -0,0 +1,123 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{relative_path, single_line};
+use crate::check::WorkspaceReport;
+use crate::spans::Location;
+
+/// How a one-line format lays out each finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineLayout {
+    /// `path:line:column: severity[ID]: message`, the way compilers report errors
+    Compiler,
+
+    /// `path:line:column: severity: message [ID]`, which Vim's quickfix list and Emacs's compilation mode
+    /// parse without configuration, with Vim's `errorformat` telling errors from warnings through
+    /// `%f:%l:%c: %t%*[a-z]: %m`
+    Quickfix,
+}
+
+/// Writes each finding of the checked workspaces on a single line with the given layout
+///
+/// Paths are relative to the current directory, and line breaks in messages become spaces. Every
+/// line carries a position, the start of the manifest for findings that aren't about a single
+/// dependency, so there's always somewhere to jump to.
+pub fn write_lines(reports: &[WorkspaceReport], layout: LineLayout, out: &mut dyn Write) -> Result<()> {
+    for finding in reports.iter().flat_map(|report| &report.findings) {
+        let location = finding.location.unwrap_or(Location { line: 1, column: 1 });
+        let path = relative_path(&finding.manifest_path);
+        let message = single_line(&finding.message);
+        let (line, column, severity, id) = (location.line, location.column, finding.severity, finding.rule.id);
+        match layout {
+            LineLayout::Compiler => writeln!(out, "{path}:{line}:{column}: {severity}[{id}]: {message}")?,
+            LineLayout::Quickfix => writeln!(out, "{path}:{line}:{column}: {severity}: {message} [{id}]")?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, finding, general, report};
+    use crate::rules::{DEFAULT_FEATURES, Finding, INCONSISTENT_ALIASES, SORTED_DEPENDENCIES, Severity};
+
+    #[test]
+    fn test_write_compact() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace.dependencies]\n  tokio = \"1.0\"\n").unwrap();
+
+        let report = report(
+            &manifest_path,
+            vec![
+                Finding::new(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    &manifest_path,
+                    entry("tokio", "'tokio': uses simple version string"),
+                    Some(Location { line: 2, column: 3 }),
+                ),
+                finding(
+                    &INCONSISTENT_ALIASES,
+                    Severity::Warning,
+                    &manifest_path,
+                    general("package 'log' is imported under inconsistent names"),
+                ),
+            ],
+        );
+
+        let mut out = Vec::new();
+        write_lines(&[report], LineLayout::Compiler, &mut out).unwrap();
+        let path = manifest_path.display();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "{path}:2:3: error[ENDF001]: 'tokio': uses simple version string\n\
+                 {path}:1:1: warning[ENDF006]: package 'log' is imported under inconsistent names\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_quickfix() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace.dependencies]\ntokio = \"1.0\"\nserde = \"1.0\"\n").unwrap();
+
+        let report = report(
+            &manifest_path,
+            vec![
+                Finding::new(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    &manifest_path,
+                    entry("tokio", "'tokio': uses simple version string"),
+                    Some(Location { line: 2, column: 1 }),
+                ),
+                Finding::new(
+                    &SORTED_DEPENDENCIES,
+                    Severity::Warning,
+                    &manifest_path,
+                    entry("serde", "'serde' should come\nbefore 'tokio'"),
+                    Some(Location { line: 3, column: 1 }),
+                ),
+            ],
+        );
+
+        let mut out = Vec::new();
+        write_lines(&[report], LineLayout::Quickfix, &mut out).unwrap();
+        let path = manifest_path.display();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "{path}:2:1: error: 'tokio': uses simple version string [ENDF001]\n\
+                 {path}:3:1: warning: 'serde' should come before 'tokio' [ENDF017]\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::relative_uri;
+use super::usage::dependency_usages;
+use crate::check::WorkspaceReport;
+
+/// Columns of the CSV export
+const HEADER: [&str; 7] = [
+    "workspace",
+    "dependency",
+    "member",
+    "section",
+    "inherited",
+    "default_features",
+    "features",
+];
+
+/// Writes the dependency and feature matrix of the checked workspaces as CSV
+///
+/// There's a row for each dependency of each member, telling whether it inherits from the workspace,
+/// whether its default features end up enabled, and the features it enables separated by spaces.
+/// Dependencies of `[workspace.dependencies]` no member uses get a row with an empty member.
+pub fn write_csv(reports: &[WorkspaceReport], out: &mut dyn Write) -> Result<()> {
+    writeln!(out, "{}", HEADER.join(","))?;
+    for report in reports {
+        let workspace = relative_uri(&report.manifest_path);
+        for usage in dependency_usages(&report.manifest_path)? {
+            let fields = [
+                workspace.as_str(),
+                &usage.dependency,
+                usage.member.as_deref().unwrap_or_default(),
+                &usage.section,
+                if usage.inherited { "true" } else { "false" },
+                if usage.default_features { "true" } else { "false" },
+                &usage.features.join(" "),
+            ];
+            let row: Vec<_> = fields.iter().map(|field| escape(field)).collect();
+            writeln!(out, "{}", row.join(","))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Quotes a field when it holds a character that's special in CSV
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::report;
+
+    #[test]
+    fn test_write_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &manifest_path,
+            "[package]\nname = \"app\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"std\", \"derive\"] }\n",
+        )
+        .unwrap();
+
+        let report = report(&manifest_path, Vec::new());
+
+        let mut out = Vec::new();
+        write_csv(&[report], &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(lines[0], "workspace,dependency,member,section,inherited,default_features,features");
+        assert!(lines[1].ends_with(",serde,app,[dependencies],false,true,derive std"), "{csv}");
+        assert_eq!(lines.len(), 2, "{csv}");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
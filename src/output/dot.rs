@@ -0,0 +1,118 @@
+use core::fmt::Write as _;
+use std::collections::BTreeSet;
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::relative_uri;
+use super::usage::dependency_usages;
+use crate::check::WorkspaceReport;
+
+/// Writes the feature enablement of the checked workspaces as a Graphviz DOT graph
+///
+/// Each workspace is a cluster where members point at their dependencies, which point at the features
+/// they get enabled. Edges along which a member leaves default features enabled are drawn in red and
+/// labeled, so it's easy to see where the extra features come from. Dependencies of
+/// `[workspace.dependencies]` no member uses are drawn dashed.
+pub fn write_dot(reports: &[WorkspaceReport], out: &mut dyn Write) -> Result<()> {
+    writeln!(out, "digraph {} {{", quote(env!("CARGO_PKG_NAME")))?;
+    writeln!(out, "  rankdir=LR;")?;
+    writeln!(out, "  node [fontname=\"sans-serif\"];")?;
+    writeln!(out, "  edge [fontname=\"sans-serif\", fontsize=10];")?;
+
+    for (index, report) in reports.iter().enumerate() {
+        let id = |kind: &str, name: &str| quote(&format!("{index}:{kind}:{name}"));
+        let mut statements = BTreeSet::new();
+        for usage in dependency_usages(&report.manifest_path)? {
+            let dependency = id("dependency", &usage.dependency);
+            let Some(member) = &usage.member else {
+                let _ = statements.insert(format!("{dependency} [label={}, style=dashed];", quote(&usage.dependency)));
+                continue;
+            };
+
+            let _ = statements.insert(format!(
+                "{} [label={}, shape=box, style=filled, fillcolor=\"#ddf4ff\"];",
+                id("member", member),
+                quote(member)
+            ));
+            let _ = statements.insert(format!("{dependency} [label={}];", quote(&usage.dependency)));
+
+            let mut edge = format!("{} -> {dependency}", id("member", member));
+            if usage.default_features {
+                let _ = write!(
+                    edge,
+                    " [label={}, color=red, fontcolor=red, penwidth=2]",
+                    quote(&format!("{} default features", usage.section))
+                );
+            } else {
+                let _ = write!(edge, " [label={}]", quote(&usage.section));
+            }
+            let _ = statements.insert(format!("{edge};"));
+
+            for feature in &usage.features {
+                let node = id("feature", &format!("{}/{feature}", usage.dependency));
+                let _ = statements.insert(format!("{node} [label={}, shape=note];", quote(feature)));
+                let _ = statements.insert(format!("{dependency} -> {node};"));
+            }
+        }
+
+        writeln!(out, "  subgraph {} {{", quote(&format!("cluster_{index}")))?;
+        writeln!(out, "    label={};", quote(&relative_uri(&report.manifest_path)))?;
+        for statement in &statements {
+            writeln!(out, "    {statement}")?;
+        }
+        writeln!(out, "  }}")?;
+    }
+
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Quotes a DOT identifier
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::report;
+
+    #[test]
+    fn test_write_dot() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &manifest_path,
+            "[package]\nname = \"app\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] }\nlog = { version = \"0.4\", default-features = false }\n",
+        )
+        .unwrap();
+
+        let report = report(&manifest_path, Vec::new());
+
+        let mut out = Vec::new();
+        write_dot(&[report], &mut out).unwrap();
+        let dot = String::from_utf8(out).unwrap();
+
+        assert!(dot.starts_with("digraph \"cargo-ensure-no-default-features\" {\n"), "{dot}");
+        assert!(dot.contains("  subgraph \"cluster_0\" {\n"), "{dot}");
+        assert!(
+            dot.contains(
+                "\"0:member:app\" -> \"0:dependency:serde\" [label=\"[dependencies] default features\", color=red, fontcolor=red, penwidth=2];"
+            ),
+            "{dot}"
+        );
+        assert!(
+            dot.contains("\"0:member:app\" -> \"0:dependency:log\" [label=\"[dependencies]\"];"),
+            "{dot}"
+        );
+        assert!(dot.contains("\"0:dependency:serde\" -> \"0:feature:serde/derive\";"), "{dot}");
+        assert!(dot.trim_end().ends_with('}'), "{dot}");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("plain"), "\"plain\"");
+        assert_eq!(quote("a \"b\" \\ c"), "\"a \\\"b\\\" \\\\ c\"");
+    }
+}
@@ -0,0 +1,134 @@
+use core::fmt::Write as _;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use super::{automatic_fix, relative_path};
+use crate::rules::Finding;
+
+/// Renders code frames showing the manifest lines findings are about, reading each manifest once
+#[derive(Debug, Default)]
+pub struct CodeFrames {
+    /// Content of the manifests read so far, `None` for those that couldn't be read
+    contents: BTreeMap<PathBuf, Option<Rc<str>>>,
+}
+
+impl CodeFrames {
+    /// Renders the frame of a finding, with each line indented by `indent`
+    ///
+    /// The frame points at the line of the finding's location, underlines the entry, and closes with a
+    /// help line: the entry as --fix would rewrite it when the finding can be fixed automatically, or the
+    /// rule's summary otherwise. Returns an empty string when the finding has no location.
+    pub fn render(&mut self, finding: &Finding, indent: &str) -> String {
+        let content = self
+            .contents
+            .entry(finding.manifest_path.clone())
+            .or_insert_with(|| std::fs::read_to_string(&finding.manifest_path).ok().map(Rc::from))
+            .clone();
+        let Some(content) = content.as_deref() else {
+            return String::new();
+        };
+        let Some(location) = finding.location else {
+            return String::new();
+        };
+
+        let text = content.lines().nth(location.line - 1).unwrap_or_default().trim_end();
+        let gutter = " ".repeat(location.line.to_string().len());
+        let underline = text.chars().count().saturating_sub(location.column - 1).max(1);
+
+        let mut frame = String::new();
+        let _ = writeln!(
+            frame,
+            "{indent}{gutter}--> {}:{}:{}",
+            relative_path(&finding.manifest_path),
+            location.line,
+            location.column
+        );
+        let _ = writeln!(frame, "{indent}{gutter} |");
+        let _ = writeln!(frame, "{indent}{} | {text}", location.line);
+        let _ = writeln!(
+            frame,
+            "{indent}{gutter} | {}{}",
+            " ".repeat(location.column - 1),
+            "^".repeat(underline)
+        );
+        let _ = writeln!(frame, "{indent}{gutter} = help: {}", help(finding, content, location.line, text));
+        frame
+    }
+}
+
+/// Describes how to address a finding about the given line of its manifest
+fn help(finding: &Finding, content: &str, line: usize, text: &str) -> String {
+    let Some((description, fixed)) = automatic_fix(finding, content) else {
+        return format!(
+            "{}, see --explain {}",
+            finding.rule.summary.to_lowercase().trim_end_matches('.'),
+            finding.rule.id
+        );
+    };
+
+    let description = description.to_lowercase();
+    match fixed.lines().nth(line - 1) {
+        Some(replacement) if fixed.lines().count() == content.lines().count() && replacement.trim_end() != text => {
+            format!("{description}: `{}`", replacement.trim())
+        }
+        _ => format!("{description}, as --fix does"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, general};
+    use crate::rules::{DEFAULT_FEATURES, SORTED_DEPENDENCIES, Severity};
+    use crate::spans::Location;
+
+    #[test]
+    fn test_render() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace.dependencies]\ntokio = \"1.0\"\nserde = \"1.0\"\n").unwrap();
+
+        let finding = |rule, message, location| Finding::new(rule, Severity::Error, &manifest_path, message, location);
+
+        let mut frames = CodeFrames::default();
+        assert_eq!(
+            frames.render(
+                &finding(
+                    &DEFAULT_FEATURES,
+                    entry("tokio", "'tokio': uses simple version string"),
+                    Some(Location { line: 2, column: 1 })
+                ),
+                "  "
+            ),
+            format!(
+                "   --> {}:2:1\n    |\n  2 | tokio = \"1.0\"\n    | ^^^^^^^^^^^^^\n    \
+                 = help: set default-features = false: `tokio = {{ version = \"1.0\", default-features = false }}`\n",
+                manifest_path.display()
+            )
+        );
+
+        let frame = frames.render(
+            &finding(
+                &SORTED_DEPENDENCIES,
+                entry("serde", "'serde' should come before 'tokio'"),
+                Some(Location { line: 3, column: 1 }),
+            ),
+            "",
+        );
+        assert!(frame.contains("3 | serde = \"1.0\"\n"), "{frame}");
+        assert!(frame.ends_with(", see --explain ENDF017\n"), "{frame}");
+
+        assert_eq!(
+            frames.render(
+                &finding(
+                    &DEFAULT_FEATURES,
+                    general("package 'log' is imported under inconsistent names"),
+                    None
+                ),
+                ""
+            ),
+            ""
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use super::relative_path;
+use crate::check::WorkspaceReport;
+use crate::rules::{Finding, RULES, Severity};
+
+/// What the human-readable report groups findings by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// The rule reporting them, with warnings listed on their own
+    #[default]
+    Rule,
+
+    /// The crate they're about
+    Crate,
+
+    /// Their severity
+    Severity,
+
+    /// The manifest they were found in
+    File,
+}
+
+/// Order of the groups of the human-readable report, and of the findings within each group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Alphabetically, with rules in ID order and findings by the crate they're about
+    Name,
+
+    /// Errors before warnings
+    Severity,
+
+    /// The largest groups first
+    Count,
+}
+
+/// Findings sharing what the report groups them by
+#[derive(Debug)]
+pub struct FindingGroup<'a> {
+    /// What the findings share: a rule ID, a crate name, a severity, or a manifest path
+    ///
+    /// The label is empty for the group of findings that aren't about a single crate.
+    pub label: String,
+
+    /// The findings, each paired with the report of the workspace it was found in
+    pub findings: Vec<(&'a WorkspaceReport, &'a Finding)>,
+}
+
+impl FindingGroup<'_> {
+    /// Returns whether any finding of the group has error severity
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|(_, finding)| finding.severity == Severity::Error)
+    }
+}
+
+/// Groups findings by the given key, in the given order
+///
+/// Without an order, groups come in the order their first finding does, except for rules and
+/// severities which keep their natural order, and findings keep theirs.
+pub fn group_findings<'a>(
+    findings: impl IntoIterator<Item = (&'a WorkspaceReport, &'a Finding)>,
+    group_by: GroupBy,
+    sort: Option<SortOrder>,
+) -> Vec<FindingGroup<'a>> {
+    let mut groups: Vec<FindingGroup<'a>> = Vec::new();
+    for (report, finding) in findings {
+        let label = match group_by {
+            GroupBy::Rule => finding.rule.id.to_string(),
+            GroupBy::Crate => finding.dependency.as_deref().unwrap_or_default().to_string(),
+            GroupBy::Severity => finding.severity.to_string(),
+            GroupBy::File => relative_path(&finding.manifest_path),
+        };
+
+        match groups.iter_mut().find(|group| group.label == label) {
+            Some(group) => group.findings.push((report, finding)),
+            None => groups.push(FindingGroup {
+                label,
+                findings: vec![(report, finding)],
+            }),
+        }
+    }
+
+    match group_by {
+        GroupBy::Rule => groups.sort_by_key(|group| RULES.iter().position(|rule| rule.id == group.label)),
+        GroupBy::Severity => groups.sort_by_key(|group| !group.has_errors()),
+        GroupBy::Crate | GroupBy::File => {}
+    }
+
+    match sort {
+        None => {}
+        Some(SortOrder::Name) => {
+            if matches!(group_by, GroupBy::Crate | GroupBy::File) {
+                // The findings that aren't about a single crate come last
+                groups.sort_by(|a, b| (a.label.is_empty(), &a.label).cmp(&(b.label.is_empty(), &b.label)));
+            }
+
+            for group in &mut groups {
+                group.findings.sort_by(|(_, a), (_, b)| {
+                    a.dependency
+                        .as_deref()
+                        .unwrap_or(&a.message)
+                        .cmp(b.dependency.as_deref().unwrap_or(&b.message))
+                });
+            }
+        }
+        Some(SortOrder::Severity) => {
+            groups.sort_by_key(|group| !group.has_errors());
+            for group in &mut groups {
+                group.findings.sort_by_key(|(_, finding)| finding.severity != Severity::Error);
+            }
+        }
+        Some(SortOrder::Count) => groups.sort_by_key(|group| core::cmp::Reverse(group.findings.len())),
+    }
+
+    groups
+}
+
+/// A finding standing for the identical findings of every manifest it was seen in
+#[derive(Debug)]
+pub struct CollapsedFinding<'a> {
+    /// The first of the identical findings, paired with the report of the workspace it was found in
+    pub finding: (&'a WorkspaceReport, &'a Finding),
+
+    /// The manifests the finding was seen in, in the order they were first reported
+    pub manifests: Vec<&'a Path>,
+}
+
+/// Collapses the findings of the same rule, severity and message into the first of them
+///
+/// Members repeating the same inline declaration, or inheriting the same broken entry, get identical
+/// findings, which are reported once along with every manifest they were seen in.
+pub fn collapse_duplicates<'a>(findings: &[(&'a WorkspaceReport, &'a Finding)]) -> Vec<CollapsedFinding<'a>> {
+    let mut collapsed: Vec<CollapsedFinding<'a>> = Vec::new();
+    for &(report, finding) in findings {
+        let duplicate = collapsed.iter_mut().find(|collapsed| {
+            let first = collapsed.finding.1;
+            first.rule.id == finding.rule.id && first.severity == finding.severity && first.message == finding.message
+        });
+
+        match duplicate {
+            Some(collapsed) if !collapsed.manifests.contains(&finding.manifest_path.as_path()) => {
+                collapsed.manifests.push(&finding.manifest_path);
+            }
+            Some(_) => {}
+            None => collapsed.push(CollapsedFinding {
+                finding: (report, finding),
+                manifests: vec![&finding.manifest_path],
+            }),
+        }
+    }
+
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, finding, general, report};
+    use crate::rules::{DEFAULT_FEATURES, SORTED_DEPENDENCIES};
+
+    #[test]
+    fn test_group_findings() {
+        let report = report(
+            Path::new("/ws/Cargo.toml"),
+            vec![
+                finding(
+                    &SORTED_DEPENDENCIES,
+                    Severity::Warning,
+                    "/ws/Cargo.toml",
+                    entry("serde", "'serde' should come before 'tokio'"),
+                ),
+                finding(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    "/ws/Cargo.toml",
+                    entry("tokio", "'tokio': uses simple version string"),
+                ),
+                finding(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    "/ws/a/Cargo.toml",
+                    entry("serde", "'serde': missing default-features = false"),
+                ),
+                finding(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    "/ws/a/Cargo.toml",
+                    general("package 'log' is imported twice"),
+                ),
+            ],
+        );
+        let findings = || report.findings.iter().map(|finding| (&report, finding));
+        let labels = |groups: &[FindingGroup<'_>]| groups.iter().map(|group| group.label.clone()).collect::<Vec<_>>();
+
+        let groups = group_findings(findings(), GroupBy::Rule, None);
+        assert_eq!(labels(&groups), ["ENDF001", "ENDF017"]);
+        assert_eq!(groups[0].findings.len(), 3);
+
+        let groups = group_findings(findings(), GroupBy::Severity, None);
+        assert_eq!(labels(&groups), ["error", "warning"]);
+
+        let groups = group_findings(findings(), GroupBy::Crate, None);
+        assert_eq!(labels(&groups), ["serde", "tokio", ""]);
+
+        let groups = group_findings(findings(), GroupBy::Crate, Some(SortOrder::Count));
+        assert_eq!(labels(&groups), ["serde", "tokio", ""]);
+        assert_eq!(groups[0].findings.len(), 2);
+
+        let groups = group_findings(findings(), GroupBy::File, Some(SortOrder::Name));
+        assert_eq!(groups.len(), 2);
+        assert!(groups[0].label < groups[1].label);
+        let group = groups.iter().find(|group| group.label.ends_with("a/Cargo.toml")).unwrap();
+        assert_eq!(group.findings[0].1.message, "package 'log' is imported twice");
+
+        let groups = group_findings(findings(), GroupBy::File, Some(SortOrder::Severity));
+        let group = groups.iter().find(|group| !group.label.ends_with("a/Cargo.toml")).unwrap();
+        assert_eq!(group.findings[0].1.severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_collapse_duplicates() {
+        let finding = |manifest_path: &str, name: &str, message: &str| {
+            finding(&DEFAULT_FEATURES, Severity::Error, manifest_path, entry(name, message))
+        };
+        let report = report(
+            Path::new("/ws/Cargo.toml"),
+            vec![
+                finding("/ws/a/Cargo.toml", "serde", "'serde' in [dependencies]: declared directly"),
+                finding("/ws/a/Cargo.toml", "log", "'log' in [dependencies]: declared directly"),
+                finding("/ws/b/Cargo.toml", "serde", "'serde' in [dependencies]: declared directly"),
+                finding("/ws/c/Cargo.toml", "serde", "'serde' in [dependencies]: declared directly"),
+            ],
+        );
+        let findings: Vec<_> = report.findings.iter().map(|finding| (&report, finding)).collect();
+
+        let collapsed = collapse_duplicates(&findings);
+        assert_eq!(collapsed.len(), 2);
+        assert_eq!(collapsed[0].finding.1.message, "'serde' in [dependencies]: declared directly");
+        assert_eq!(
+            collapsed[0].manifests,
+            [
+                Path::new("/ws/a/Cargo.toml"),
+                Path::new("/ws/b/Cargo.toml"),
+                Path::new("/ws/c/Cargo.toml")
+            ]
+        );
+        assert_eq!(collapsed[1].manifests, [Path::new("/ws/a/Cargo.toml")]);
+    }
+}
@@ -0,0 +1,225 @@
+use core::fmt::Write as _;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{ReportedFinding, escape_markup, relative_uri, reported_findings};
+use crate::check::WorkspaceReport;
+use crate::rules::{RULES, Severity};
+
+/// Style sheet embedded in the report
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2em; color: #1f2328; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #d0d7de; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+#findings th { cursor: pointer; user-select: none; }
+#findings th::after { content: ' \\2195'; color: #8c959f; }
+.error { color: #cf222e; }
+.warning { color: #9a6700; }
+input { padding: 0.3em; margin-bottom: 0.6em; width: 24em; }
+pre { white-space: pre-wrap; background: #f6f8fa; padding: 1em; }
+";
+
+/// Script sorting the findings by the clicked column and filtering them by the typed text
+const SCRIPT: &str = "
+const table = document.getElementById('findings');
+const body = table.tBodies[0];
+table.querySelectorAll('th').forEach((header, column) => {
+  let ascending = true;
+  header.addEventListener('click', () => {
+    const rows = Array.from(body.rows);
+    rows.sort((a, b) => {
+      const x = a.cells[column].textContent, y = b.cells[column].textContent;
+      const order = x.localeCompare(y, undefined, { numeric: true });
+      return ascending ? order : -order;
+    });
+    ascending = !ascending;
+    rows.forEach(row => body.appendChild(row));
+  });
+});
+document.getElementById('filter').addEventListener('input', event => {
+  const text = event.target.value.toLowerCase();
+  Array.from(body.rows).forEach(row => {
+    row.hidden = !row.textContent.toLowerCase().includes(text);
+  });
+});
+";
+
+/// Writes the findings of the checked workspaces as a self-contained HTML page
+///
+/// The page summarizes the errors and warnings of every manifest with findings, lists the findings in a
+/// table that can be sorted by clicking a column and filtered by typing, and embeds the explanation of
+/// every rule with findings. It needs no other file, so it can be shared with people who won't read CI
+/// logs.
+pub fn write_html(reports: &[WorkspaceReport], truncated: bool, out: &mut dyn Write) -> Result<()> {
+    let findings = reported_findings(reports);
+    let title = format!("{} report", env!("CARGO_PKG_NAME"));
+
+    let mut page = String::new();
+    let _ = writeln!(
+        page,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>"
+    );
+    let _ = writeln!(page, "<h1>{title}</h1>");
+
+    let errors = findings
+        .iter()
+        .filter(|reported| reported.finding.severity == Severity::Error)
+        .count();
+    let _ = writeln!(
+        page,
+        "<p>{errors} error(s) and {} warning(s) in {} workspace(s).</p>",
+        findings.len() - errors,
+        reports.len()
+    );
+    if truncated {
+        let _ = writeln!(
+            page,
+            "<p class=\"warning\">The deadline passed before every workspace was fully checked, so this report is truncated.</p>"
+        );
+    }
+
+    if !findings.is_empty() {
+        write_members(&mut page, &findings);
+        write_findings(&mut page, &findings);
+        write_rules(&mut page, &findings);
+    }
+
+    let _ = writeln!(page, "<script>{SCRIPT}</script>\n</body>\n</html>");
+    out.write_all(page.as_bytes())?;
+    Ok(())
+}
+
+/// Writes the table counting the errors and warnings of each manifest with findings
+fn write_members(page: &mut String, findings: &[ReportedFinding<'_>]) {
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for reported in findings {
+        let (errors, warnings) = counts.entry(relative_uri(&reported.finding.manifest_path)).or_default();
+        match reported.finding.severity {
+            Severity::Error => *errors += 1,
+            Severity::Warning => *warnings += 1,
+        }
+    }
+
+    let _ = writeln!(page, "<h2>Manifests</h2>\n<table>");
+    let _ = writeln!(
+        page,
+        "<thead><tr><th>Manifest</th><th>Errors</th><th>Warnings</th></tr></thead>\n<tbody>"
+    );
+    for (manifest, (errors, warnings)) in &counts {
+        let _ = writeln!(
+            page,
+            "<tr><td>{}</td><td>{errors}</td><td>{warnings}</td></tr>",
+            escape_markup(manifest)
+        );
+    }
+    let _ = writeln!(page, "</tbody>\n</table>");
+}
+
+/// Writes the sortable and filterable table of findings
+fn write_findings(page: &mut String, findings: &[ReportedFinding<'_>]) {
+    let _ = writeln!(page, "<h2>Findings</h2>");
+    let _ = writeln!(page, "<input id=\"filter\" type=\"search\" placeholder=\"Filter findings\">");
+    let _ = writeln!(page, "<table id=\"findings\">");
+    let _ = writeln!(
+        page,
+        "<thead><tr><th>Rule</th><th>Severity</th><th>Manifest</th><th>Dependency</th><th>Line</th><th>Message</th></tr></thead>\n<tbody>"
+    );
+    for reported in findings {
+        let finding = reported.finding;
+        let severity = match finding.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let _ = writeln!(
+            page,
+            "<tr><td><a href=\"#{id}\">{id}</a> {}</td><td class=\"{severity}\">{severity}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            finding.rule.name,
+            escape_markup(&relative_uri(&finding.manifest_path)),
+            escape_markup(finding.dependency.as_deref().unwrap_or_default()),
+            finding.location.map(|location| location.line.to_string()).unwrap_or_default(),
+            escape_markup(&finding.message),
+            id = finding.rule.id,
+        );
+    }
+    let _ = writeln!(page, "</tbody>\n</table>");
+}
+
+/// Writes the explanation of every rule with findings
+fn write_rules(page: &mut String, findings: &[ReportedFinding<'_>]) {
+    let _ = writeln!(page, "<h2>Rules</h2>");
+    for rule in RULES
+        .iter()
+        .filter(|rule| findings.iter().any(|reported| reported.finding.rule.id == rule.id))
+    {
+        let _ = writeln!(
+            page,
+            "<section id=\"{}\">\n<h3>{} {}</h3>\n<p>{}</p>\n<pre>{}</pre>\n</section>",
+            rule.id,
+            rule.id,
+            rule.name,
+            escape_markup(rule.summary),
+            escape_markup(rule.explanation.trim_end())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::output::fixtures::{entry, report};
+    use crate::rules::{DEFAULT_FEATURES, Finding};
+    use crate::spans::Location;
+
+    #[test]
+    fn test_write_html() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace.dependencies]\ntokio = \"1.0\"\n").unwrap();
+
+        let report = report(
+            &manifest_path,
+            vec![Finding::new(
+                &DEFAULT_FEATURES,
+                Severity::Error,
+                &manifest_path,
+                entry("tokio", "'tokio': uses simple version string"),
+                Some(Location { line: 2, column: 1 }),
+            )],
+        );
+
+        let mut out = Vec::new();
+        write_html(&[report], false, &mut out).unwrap();
+        let page = String::from_utf8(out).unwrap();
+
+        assert!(page.starts_with("<!DOCTYPE html>"), "{page}");
+        assert!(page.contains("<p>1 error(s) and 0 warning(s) in 1 workspace(s).</p>"), "{page}");
+        assert!(page.contains("<td>1</td><td>0</td></tr>"), "{page}");
+        assert!(
+            page.contains("<tr><td><a href=\"#ENDF001\">ENDF001</a> default-features</td><td class=\"error\">error</td>"),
+            "{page}"
+        );
+        assert!(
+            page.contains("<td>tokio</td><td>2</td><td>&apos;tokio&apos;: uses simple version string</td></tr>"),
+            "{page}"
+        );
+        assert!(page.contains("<section id=\"ENDF001\">"), "{page}");
+        assert!(page.trim_end().ends_with("</html>"), "{page}");
+    }
+
+    #[test]
+    fn test_write_html_without_findings() {
+        let report = report(Path::new("Cargo.toml"), Vec::new());
+
+        let mut out = Vec::new();
+        write_html(&[report], false, &mut out).unwrap();
+        let page = String::from_utf8(out).unwrap();
+
+        assert!(page.contains("<p>0 error(s) and 0 warning(s) in 1 workspace(s).</p>"), "{page}");
+        assert!(!page.contains("<table"), "{page}");
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::{ReportedFinding, automatic_fix, reported_findings};
+use crate::check::WorkspaceReport;
+use crate::config::Exception;
+use crate::rules::{Severity, hash};
+use crate::spans::Location;
+
+/// The JSON document describing a run
+#[derive(Debug, Serialize)]
+struct JsonReport<'a> {
+    /// Version of the tool that produced the document
+    version: &'static str,
+
+    /// Paths to the root manifests of the checked workspaces
+    manifest_paths: Vec<&'a Path>,
+
+    /// Whether the deadline passed before every workspace was fully checked
+    truncated: bool,
+
+    /// Problems reported by the enabled rules
+    findings: Vec<JsonFinding<'a>>,
+
+    /// Exceptions of the configuration giving a reason or an owner, or past their expiry date
+    exceptions: Vec<JsonException<'a>>,
+}
+
+/// A single finding of the JSON document
+#[derive(Debug, Serialize)]
+struct JsonFinding<'a> {
+    /// Fingerprint identifying the violation across runs
+    fingerprint: String,
+
+    /// ID of the rule that reported the problem
+    rule: &'static str,
+
+    /// Name of the rule that reported the problem
+    rule_name: &'static str,
+
+    /// Severity the rule had when the problem was reported
+    severity: Severity,
+
+    /// Path to the manifest the problem was found in
+    manifest_path: &'a Path,
+
+    /// Dependency the problem is about, when it's about a single one
+    dependency: Option<&'a str>,
+
+    /// Header of the table holding the entry the problem is about, such as `[dev-dependencies]`
+    section: Option<&'a str>,
+
+    /// Description of the problem
+    message: &'a str,
+
+    /// Where the dependency is declared in the manifest, when it could be found
+    location: Option<Location>,
+
+    /// The automatic fix of the problem, when the tool knows one
+    fix: Option<JsonFix>,
+}
+
+/// The automatic fix of a finding, as applied by `apply --from`
+#[derive(Debug, Serialize)]
+struct JsonFix {
+    /// What the fix does
+    description: &'static str,
+
+    /// Hash of the manifest's content the fix was suggested for, telling whether it changed since
+    manifest_hash: String,
+}
+
+/// An exception of the configuration worth reporting
+#[derive(Debug, Serialize)]
+struct JsonException<'a> {
+    /// Rule ID or name the exception is keyed by, `*` for every rule
+    rule: &'a str,
+
+    /// Name, glob pattern, or regular expression of the excluded dependencies
+    name: &'a str,
+
+    /// Why the dependency is excluded
+    reason: Option<&'a str>,
+
+    /// Who to ask about the exception
+    owner: Option<&'a str>,
+
+    /// Last day the exception applies
+    expires: Option<String>,
+
+    /// Whether the exception no longer applies
+    expired: bool,
+}
+
+/// Writes the findings of the checked workspaces as a JSON document
+pub fn write_json(reports: &[WorkspaceReport], truncated: bool, out: &mut dyn Write) -> Result<()> {
+    let exceptions = reports.iter().flat_map(|report| {
+        let reasons = report
+            .exception_reasons
+            .iter()
+            .map(|(key, exception)| json_exception(key, exception, false));
+        let expired = report
+            .expired_exceptions
+            .iter()
+            .map(|(key, exception)| json_exception(key, exception, true));
+        reasons.chain(expired)
+    });
+
+    let document = JsonReport {
+        version: env!("CARGO_PKG_VERSION"),
+        manifest_paths: reports.iter().map(|report| report.manifest_path.as_path()).collect(),
+        truncated,
+        findings: reported_findings(reports).into_iter().map(json_finding).collect(),
+        exceptions: exceptions.collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &document).context("Failed to write the JSON report")?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Describes a finding in the JSON document
+fn json_finding(reported: ReportedFinding<'_>) -> JsonFinding<'_> {
+    let finding = reported.finding;
+    let fix = reported.content.as_deref().and_then(|content| {
+        automatic_fix(finding, content).map(|(description, _)| JsonFix {
+            description,
+            manifest_hash: hash(content),
+        })
+    });
+
+    JsonFinding {
+        fingerprint: reported.fingerprint,
+        rule: finding.rule.id,
+        rule_name: finding.rule.name,
+        severity: finding.severity,
+        manifest_path: &finding.manifest_path,
+        dependency: finding.dependency.as_deref(),
+        section: finding.section.as_deref(),
+        message: &finding.message,
+        location: finding.location,
+        fix,
+    }
+}
+
+/// Describes an exception of the configuration in the JSON document
+fn json_exception<'a>(key: &'a str, exception: &'a Exception, expired: bool) -> JsonException<'a> {
+    JsonException {
+        rule: key,
+        name: &exception.name,
+        reason: exception.reason.as_deref(),
+        owner: exception.owner.as_deref(),
+        expires: exception.expires.map(|expires| expires.to_string()),
+        expired,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, finding, report};
+    use crate::rules::DEFAULT_FEATURES;
+
+    #[test]
+    fn test_write_json() {
+        let manifest_path = Path::new("missing/Cargo.toml");
+        let report = WorkspaceReport {
+            exception_reasons: vec![(
+                "*".to_string(),
+                Exception {
+                    name: "openssl".to_string(),
+                    owner: Some("@platform-team".to_string()),
+                    ..Exception::default()
+                },
+            )],
+            ..report(
+                manifest_path,
+                vec![finding(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    manifest_path,
+                    entry("serde", "'serde': uses simple version string"),
+                )],
+            )
+        };
+
+        let mut out = Vec::new();
+        write_json(&[report], false, &mut out).unwrap();
+        let document: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(document["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(document["manifest_paths"], serde_json::json!(["missing/Cargo.toml"]));
+        assert_eq!(document["truncated"], false);
+
+        let finding = &document["findings"][0];
+        assert_eq!(finding["rule"], "ENDF001");
+        assert_eq!(finding["rule_name"], "default-features");
+        assert_eq!(finding["severity"], "error");
+        assert_eq!(finding["dependency"], "serde");
+        assert_eq!(finding["location"], serde_json::Value::Null);
+        assert_eq!(finding["fingerprint"].as_str().unwrap().len(), 16);
+        assert_eq!(finding["fix"], serde_json::Value::Null);
+
+        assert_eq!(
+            document["exceptions"],
+            serde_json::json!([{
+                "rule": "*", "name": "openssl", "reason": null, "owner": "@platform-team", "expires": null, "expired": false,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_write_json_fix() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        let content = "[workspace.dependencies]\nserde = \"1.0\"\n";
+        std::fs::write(&manifest_path, content).unwrap();
+
+        let report = report(
+            &manifest_path,
+            vec![finding(
+                &DEFAULT_FEATURES,
+                Severity::Error,
+                &manifest_path,
+                entry("serde", "'serde': uses simple version string"),
+            )],
+        );
+
+        let mut out = Vec::new();
+        write_json(&[report], false, &mut out).unwrap();
+        let document: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            document["findings"][0]["fix"],
+            serde_json::json!({ "description": "Set default-features = false", "manifest_hash": hash(content) })
+        );
+    }
+}
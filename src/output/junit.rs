@@ -0,0 +1,186 @@
+use core::fmt::Write as _;
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{ReportedFinding, escape_markup, relative_uri, reported_findings};
+use crate::check::WorkspaceReport;
+use crate::rules::{DEFAULT_FEATURES, Rule, Severity};
+
+/// Writes the findings of the checked workspaces as a `JUnit` XML report
+///
+/// Each workspace is a test suite holding a test case per finding, named after the dependency it's
+/// about and classified by rule. Findings with error severity are failures, while warnings are passing
+/// test cases whose output holds the finding. When rule ENDF001 ran, every other dependency of
+/// `[workspace.dependencies]` is a passing test case of it, so CI systems show what was checked along
+/// with what failed.
+pub fn write_junit(reports: &[WorkspaceReport], out: &mut dyn Write) -> Result<()> {
+    let mut suites = String::new();
+    let (mut total_tests, mut total_failures) = (0, 0);
+
+    for report in reports {
+        let findings = reported_findings(core::slice::from_ref(report));
+        let mut cases: Vec<_> = findings.iter().map(test_case).collect();
+        if report.rules.is_enabled(&DEFAULT_FEATURES) {
+            let uri = relative_uri(&report.manifest_path);
+            cases.extend(
+                workspace_dependencies(report)
+                    .into_iter()
+                    .filter(|name| {
+                        !report
+                            .findings
+                            .iter()
+                            .any(|finding| finding.rule == &DEFAULT_FEATURES && finding.dependency.as_deref() == Some(name))
+                    })
+                    .map(|name| {
+                        format!(
+                            "    <testcase classname=\"{}\" name=\"{}\" file=\"{}\"/>\n",
+                            classname(&DEFAULT_FEATURES),
+                            escape_markup(&name),
+                            escape_markup(&uri)
+                        )
+                    }),
+            );
+        }
+
+        let failures = findings
+            .iter()
+            .filter(|reported| reported.finding.severity == Severity::Error)
+            .count();
+        total_tests += cases.len();
+        total_failures += failures;
+
+        let _ = writeln!(
+            suites,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" skipped=\"0\">",
+            escape_markup(&relative_uri(&report.manifest_path)),
+            cases.len()
+        );
+        suites.extend(cases);
+        suites.push_str("  </testsuite>\n");
+    }
+
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        out,
+        "<testsuites name=\"{}\" tests=\"{total_tests}\" failures=\"{total_failures}\" errors=\"0\">",
+        env!("CARGO_PKG_NAME")
+    )?;
+    write!(out, "{suites}")?;
+    writeln!(out, "</testsuites>")?;
+    Ok(())
+}
+
+/// Describes a finding as a test case
+fn test_case(reported: &ReportedFinding<'_>) -> String {
+    let finding = reported.finding;
+    let message = escape_markup(&finding.message);
+    let mut case = format!(
+        "    <testcase classname=\"{}\" name=\"{}\" file=\"{}\"",
+        classname(finding.rule),
+        escape_markup(finding.dependency.as_deref().unwrap_or(&finding.message)),
+        escape_markup(&relative_uri(&finding.manifest_path))
+    );
+
+    if let Some(location) = finding.location {
+        let _ = write!(case, " line=\"{}\"", location.line);
+    }
+
+    match finding.severity {
+        Severity::Error => {
+            let _ = write!(
+                case,
+                ">\n      <failure type=\"{}\" message=\"{message}\">{message}</failure>\n    </testcase>\n",
+                finding.rule.id
+            );
+        }
+        Severity::Warning => {
+            let _ = write!(case, ">\n      <system-out>warning: {message}</system-out>\n    </testcase>\n");
+        }
+    }
+
+    case
+}
+
+/// Returns the class name of a rule's test cases, which CI systems group by the part before the dot
+fn classname(rule: &Rule) -> String {
+    format!("{}.{}", rule.id, rule.name)
+}
+
+/// Returns the names of the dependencies of the workspace's `[workspace.dependencies]` table
+fn workspace_dependencies(report: &WorkspaceReport) -> Vec<String> {
+    std::fs::read_to_string(&report.manifest_path)
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+        .and_then(|parsed| {
+            parsed
+                .get("workspace")
+                .and_then(|w| w.get("dependencies"))
+                .and_then(toml::Value::as_table)
+                .map(|table| table.keys().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, report};
+    use crate::rules::{Finding, SORTED_DEPENDENCIES};
+    use crate::spans::Location;
+
+    #[test]
+    fn test_write_junit() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &manifest_path,
+            "[workspace.dependencies]\ntokio = \"1.0\"\nserde = { version = \"1.0\", default-features = false }\n",
+        )
+        .unwrap();
+
+        let report = report(
+            &manifest_path,
+            vec![
+                Finding::new(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    &manifest_path,
+                    entry("tokio", "'tokio': uses simple version string"),
+                    Some(Location { line: 2, column: 1 }),
+                ),
+                Finding::new(
+                    &SORTED_DEPENDENCIES,
+                    Severity::Warning,
+                    &manifest_path,
+                    entry("serde", "'serde' should come before 'tokio'"),
+                    Some(Location { line: 3, column: 1 }),
+                ),
+            ],
+        );
+
+        let mut out = Vec::new();
+        write_junit(&[report], &mut out).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"), "{xml}");
+        assert!(xml.contains("tests=\"3\" failures=\"1\""), "{xml}");
+        assert!(
+            xml.contains("<testcase classname=\"ENDF001.default-features\" name=\"tokio\""),
+            "{xml}"
+        );
+        assert!(
+            xml.contains(" line=\"2\">\n      <failure type=\"ENDF001\" message=\"&apos;tokio&apos;: uses simple version string\">"),
+            "{xml}"
+        );
+        assert!(
+            xml.contains("<system-out>warning: &apos;serde&apos; should come before &apos;tokio&apos;</system-out>"),
+            "{xml}"
+        );
+        assert!(
+            xml.contains("<testcase classname=\"ENDF001.default-features\" name=\"serde\""),
+            "{xml}"
+        );
+        assert!(xml.trim_end().ends_with("</testsuites>"), "{xml}");
+    }
+}
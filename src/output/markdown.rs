@@ -0,0 +1,202 @@
+use core::fmt::Write as _;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{ReportedFinding, relative_uri, reported_findings};
+use crate::check::WorkspaceReport;
+use crate::config::Exception;
+use crate::rules::Severity;
+
+/// Writes the findings of the checked workspaces as a Markdown report
+///
+/// The report opens with the number of errors and warnings found, followed by a table of findings for
+/// each manifest they were found in, and a table of the exceptions worth reporting along with whether
+/// they still apply. It's meant to be pasted into pull request descriptions or posted by bots.
+pub fn write_markdown(reports: &[WorkspaceReport], truncated: bool, out: &mut dyn Write) -> Result<()> {
+    let findings = reported_findings(reports);
+    let errors = findings
+        .iter()
+        .filter(|reported| reported.finding.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+
+    let mut by_file: BTreeMap<String, Vec<&ReportedFinding<'_>>> = BTreeMap::new();
+    for reported in &findings {
+        by_file
+            .entry(relative_uri(&reported.finding.manifest_path))
+            .or_default()
+            .push(reported);
+    }
+
+    writeln!(out, "## {}\n", env!("CARGO_PKG_NAME"))?;
+    if findings.is_empty() {
+        writeln!(out, "✅ No problems found in {} workspace(s).", reports.len())?;
+    } else {
+        writeln!(
+            out,
+            "{} **{errors} error(s)** and **{warnings} warning(s)** in {} file(s) of {} workspace(s).",
+            if errors > 0 { "❌" } else { "⚠️" },
+            by_file.len(),
+            reports.len()
+        )?;
+    }
+
+    if truncated {
+        writeln!(
+            out,
+            "\n⏱️ The deadline passed before every workspace was fully checked, so this report is truncated."
+        )?;
+    }
+
+    for (file, findings) in &by_file {
+        writeln!(out, "\n### `{file}`\n")?;
+        writeln!(out, "| Rule | Severity | Dependency | Line | Message |")?;
+        writeln!(out, "|------|----------|------------|------|---------|")?;
+        for reported in findings {
+            writeln!(out, "{}", finding_row(reported))?;
+        }
+    }
+
+    let exceptions: Vec<_> = reports
+        .iter()
+        .flat_map(|report| {
+            let reasons = report.exception_reasons.iter().map(|(key, exception)| (key, exception, false));
+            let expired = report.expired_exceptions.iter().map(|(key, exception)| (key, exception, true));
+            reasons.chain(expired)
+        })
+        .collect();
+    if !exceptions.is_empty() {
+        writeln!(out, "\n### Exceptions\n")?;
+        writeln!(out, "| Rule | Dependency | Status | Reason | Owner |")?;
+        writeln!(out, "|------|------------|--------|--------|-------|")?;
+        for (key, exception, expired) in exceptions {
+            writeln!(out, "{}", exception_row(key, exception, expired))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Describes a finding as a row of its manifest's table
+fn finding_row(reported: &ReportedFinding<'_>) -> String {
+    let finding = reported.finding;
+    let severity = match finding.severity {
+        Severity::Warning => "⚠️ warning",
+        Severity::Error => "❌ error",
+    };
+
+    format!(
+        "| {} {} | {severity} | {} | {} | {} |",
+        finding.rule.id,
+        finding.rule.name,
+        finding.dependency.as_deref().map(|name| format!("`{name}`")).unwrap_or_default(),
+        finding.location.map(|location| location.line.to_string()).unwrap_or_default(),
+        escape(&finding.message)
+    )
+}
+
+/// Describes an exception as a row of the exceptions table
+fn exception_row(key: &str, exception: &Exception, expired: bool) -> String {
+    let mut status = String::from(if expired { "⌛ expired" } else { "✅ active" });
+    if let Some(expires) = exception.expires {
+        let _ = write!(status, " ({} {expires})", if expired { "on" } else { "until" });
+    }
+
+    format!(
+        "| {} | `{}` | {status} | {} | {} |",
+        escape(key),
+        exception.name,
+        exception.reason.as_deref().map(escape).unwrap_or_default(),
+        exception.owner.as_deref().map(escape).unwrap_or_default()
+    )
+}
+
+/// Escapes the characters of a text that would break a table cell
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::output::fixtures::{entry, report};
+    use crate::rules::{DEFAULT_FEATURES, Finding, SORTED_DEPENDENCIES};
+    use crate::spans::Location;
+
+    #[test]
+    fn test_write_markdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace.dependencies]\ntokio = \"1.0\"\nserde = \"1.0\"\n").unwrap();
+
+        let report = WorkspaceReport {
+            expired_exceptions: vec![(
+                "*".to_string(),
+                Exception {
+                    name: "openssl".to_string(),
+                    reason: Some("vendored | patched".to_string()),
+                    expires: Some(toml::value::Date {
+                        year: 2024,
+                        month: 1,
+                        day: 31,
+                    }),
+                    ..Exception::default()
+                },
+            )],
+            ..report(
+                &manifest_path,
+                vec![
+                    Finding::new(
+                        &DEFAULT_FEATURES,
+                        Severity::Error,
+                        &manifest_path,
+                        entry("tokio", "'tokio': uses simple version string"),
+                        Some(Location { line: 2, column: 1 }),
+                    ),
+                    Finding::new(
+                        &SORTED_DEPENDENCIES,
+                        Severity::Warning,
+                        &manifest_path,
+                        entry("serde", "'serde' should come before 'tokio'"),
+                        Some(Location { line: 3, column: 1 }),
+                    ),
+                ],
+            )
+        };
+
+        let mut out = Vec::new();
+        write_markdown(&[report], false, &mut out).unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+
+        assert!(
+            markdown.contains("❌ **1 error(s)** and **1 warning(s)** in 1 file(s) of 1 workspace(s)."),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("| ENDF001 default-features | ❌ error | `tokio` | 2 | 'tokio': uses simple version string |"),
+            "{markdown}"
+        );
+        assert!(markdown.contains("| ⚠️ warning | `serde` | 3 |"), "{markdown}");
+        assert!(
+            markdown.contains("| * | `openssl` | ⌛ expired (on 2024-01-31) | vendored \\| patched |  |"),
+            "{markdown}"
+        );
+    }
+
+    #[test]
+    fn test_write_markdown_without_findings() {
+        let report = report(Path::new("Cargo.toml"), Vec::new());
+
+        let mut out = Vec::new();
+        write_markdown(&[report], true, &mut out).unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+
+        assert!(markdown.contains("✅ No problems found in 1 workspace(s)."), "{markdown}");
+        assert!(markdown.contains("this report is truncated"), "{markdown}");
+        assert!(!markdown.contains("###"), "{markdown}");
+    }
+}
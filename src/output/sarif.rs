@@ -0,0 +1,208 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+
+use super::{ReportedFinding, automatic_fix, relative_uri, reported_findings};
+use crate::check::WorkspaceReport;
+use crate::rules::{RULES, Severity};
+use crate::spans::Location;
+
+/// Location of the SARIF 2.1.0 schema
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Key of the fingerprint in the `partialFingerprints` of each result
+const FINGERPRINT_KEY: &str = "endf/v1";
+
+/// Writes the findings of the checked workspaces as a SARIF 2.1.0 log
+///
+/// Every rule is described in the log's tool metadata, with its explanation as help. Each result
+/// points at the line declaring the dependency it's about, carries the finding's fingerprint, and
+/// suggests a fix when the finding can be fixed automatically.
+pub fn write_sarif(reports: &[WorkspaceReport], out: &mut dyn Write) -> Result<()> {
+    let rules: Vec<_> = RULES
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "name": rule.name,
+                "shortDescription": { "text": rule.summary },
+                "help": { "text": rule.explanation, "markdown": rule.explanation },
+                "defaultConfiguration": { "level": level(rule.severity), "enabled": rule.enabled_by_default },
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = reported_findings(reports).iter().map(sarif_result).collect();
+
+    let log = json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    serde_json::to_writer_pretty(&mut *out, &log).context("Failed to write the SARIF report")?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Describes a finding as a SARIF result
+fn sarif_result(reported: &ReportedFinding<'_>) -> Value {
+    let finding = reported.finding;
+    let location = finding.location.unwrap_or(Location { line: 1, column: 1 });
+    let uri = relative_uri(&finding.manifest_path);
+
+    let mut result = json!({
+        "ruleId": finding.rule.id,
+        "ruleIndex": RULES.iter().position(|rule| rule.id == finding.rule.id),
+        "level": level(finding.severity),
+        "message": { "text": finding.message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri },
+                "region": { "startLine": location.line, "startColumn": location.column },
+            },
+        }],
+        "partialFingerprints": { FINGERPRINT_KEY: reported.fingerprint },
+    });
+
+    if let Some(fix) = suggested_fix(reported) {
+        result["fixes"] = json!([fix]);
+    }
+
+    result
+}
+
+/// Returns the SARIF level matching a severity
+const fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+/// Suggests a fix for a finding the tool knows how to fix automatically
+///
+/// The fix replaces the smallest region of the manifest covering what the automatic fix changes.
+fn suggested_fix(reported: &ReportedFinding<'_>) -> Option<Value> {
+    let finding = reported.finding;
+    let content = reported.content.as_deref()?;
+    let (description, fixed) = automatic_fix(finding, content)?;
+
+    let (start, end, inserted) = changed_region(content, &fixed)?;
+    let (start, end) = (Location::at(content, start), Location::at(content, end));
+    Some(json!({
+        "description": { "text": description },
+        "artifactChanges": [{
+            "artifactLocation": { "uri": relative_uri(&finding.manifest_path) },
+            "replacements": [{
+                "deletedRegion": {
+                    "startLine": start.line,
+                    "startColumn": start.column,
+                    "endLine": end.line,
+                    "endColumn": end.column,
+                },
+                "insertedContent": { "text": inserted },
+            }],
+        }],
+    }))
+}
+
+/// Returns the byte range of the original content that differs from the fixed one, along with its replacement
+///
+/// Returns `None` when both are the same.
+fn changed_region(original: &str, fixed: &str) -> Option<(usize, usize, String)> {
+    if original == fixed {
+        return None;
+    }
+
+    let prefix = original
+        .char_indices()
+        .zip(fixed.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or_else(|| original.len().min(fixed.len()), |((index, _), _)| index);
+    let suffix = original
+        .get(prefix..)?
+        .chars()
+        .rev()
+        .zip(fixed.get(prefix..)?.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+
+    Some((
+        prefix,
+        original.len() - suffix,
+        fixed.get(prefix..fixed.len() - suffix)?.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, report};
+    use crate::rules::{DEFAULT_FEATURES, Finding};
+
+    #[test]
+    fn test_write_sarif() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace.dependencies]\ntokio = \"1.0\"\n").unwrap();
+
+        let report = report(
+            &manifest_path,
+            vec![Finding::new(
+                &DEFAULT_FEATURES,
+                Severity::Error,
+                &manifest_path,
+                entry("tokio", "'tokio': uses simple version string"),
+                Some(Location { line: 2, column: 1 }),
+            )],
+        );
+
+        let mut out = Vec::new();
+        write_sarif(&[report], &mut out).unwrap();
+        let log: Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), RULES.len());
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "ENDF001");
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "ENDF001");
+        assert_eq!(result["ruleIndex"], 0);
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+        assert_eq!(result["partialFingerprints"][FINGERPRINT_KEY].as_str().unwrap().len(), 16);
+
+        let replacement = &result["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["deletedRegion"]["startLine"], 2);
+        assert_eq!(replacement["deletedRegion"]["startColumn"], 9);
+        assert_eq!(replacement["deletedRegion"]["endColumn"], 14);
+        assert_eq!(
+            replacement["insertedContent"]["text"],
+            "{ version = \"1.0\", default-features = false }"
+        );
+    }
+
+    #[test]
+    fn test_changed_region() {
+        assert_eq!(
+            changed_region("a = \"1\"\n", "a = { version = \"1\" }\n"),
+            Some((4, 7, "{ version = \"1\" }".to_string()))
+        );
+        assert_eq!(changed_region("a\nb\nc\n", "a\nc\n"), Some((2, 4, String::new())));
+        assert_eq!(changed_region("same", "same"), None);
+    }
+}
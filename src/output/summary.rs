@@ -0,0 +1,282 @@
+use core::time::Duration;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::check::WorkspaceReport;
+use crate::rules::{DEFAULT_FEATURES, Finding, RULES, Severity};
+use crate::validation::DefaultFeaturesProblem;
+
+/// The JSON document summarizing a run, for pipelines gating or charting on aggregates
+#[derive(Debug, Serialize)]
+struct RunSummary<'a> {
+    /// Version of the tool that produced the document
+    version: &'static str,
+
+    /// Exit status of the run
+    status: i32,
+
+    /// Whether the deadline passed before every workspace was fully checked
+    truncated: bool,
+
+    /// Number of findings, in total and by severity
+    findings: FindingCounts,
+
+    /// Number of findings of each rule that reported any, by rule ID
+    rules: BTreeMap<&'static str, usize>,
+
+    /// Time spent on the whole run, in milliseconds
+    duration_ms: u128,
+
+    /// The checked workspaces, with the time spent checking each
+    workspaces: Vec<WorkspaceSummary<'a>>,
+}
+
+/// Number of findings of a run, in total and by severity
+#[derive(Debug, Default, Serialize)]
+struct FindingCounts {
+    /// Number of findings
+    total: usize,
+
+    /// Number of findings with error severity, which fail the check
+    errors: usize,
+
+    /// Number of findings with warning severity
+    warnings: usize,
+}
+
+/// A checked workspace of the run summary
+#[derive(Debug, Serialize)]
+struct WorkspaceSummary<'a> {
+    /// Path to the workspace's Cargo.toml
+    manifest_path: &'a Path,
+
+    /// Number of findings of the workspace, in total and by severity
+    findings: FindingCounts,
+
+    /// Time spent checking the workspace, in milliseconds
+    duration_ms: u128,
+}
+
+/// Kinds of `[workspace.dependencies]` entries lacking `default-features = false`, along with singular and
+/// plural noun phrases describing each
+const DEFAULT_FEATURES_KINDS: &[(DefaultFeaturesProblem, &str, &str)] = &[
+    (
+        DefaultFeaturesProblem::Missing,
+        "missing default-features",
+        "missing default-features",
+    ),
+    (
+        DefaultFeaturesProblem::SimpleVersion,
+        "simple version string",
+        "simple version strings",
+    ),
+    (
+        DefaultFeaturesProblem::Enabled,
+        "default-features = true",
+        "default-features = true",
+    ),
+    (
+        DefaultFeaturesProblem::UnexpectedValue,
+        "non-boolean default-features",
+        "non-boolean default-features",
+    ),
+    (
+        DefaultFeaturesProblem::NotATable,
+        "entry that isn't a table",
+        "entries that aren't tables",
+    ),
+];
+
+/// Returns a line counting the errors of the checked workspaces by kind, and the manifests they're spread across
+///
+/// Findings of rule ENDF001 are counted by what's wrong with the entry, and those of other rules by rule,
+/// as in `12 missing default-features, 4 simple version strings across 3 manifests`. Returns `None`
+/// when there are no errors.
+pub fn violation_summary(reports: &[WorkspaceReport]) -> Option<String> {
+    let errors: Vec<_> = reports.iter().flat_map(WorkspaceReport::errors).collect();
+    if errors.is_empty() {
+        return None;
+    }
+
+    let count = |matches: &dyn Fn(&Finding) -> bool| errors.iter().filter(|finding| matches(finding)).count();
+    let kind = |finding: &Finding| finding.problem.filter(|_| finding.rule == &DEFAULT_FEATURES);
+
+    let mut counts = Vec::new();
+    for (problem, singular, plural) in DEFAULT_FEATURES_KINDS {
+        match count(&|finding| kind(finding) == Some(*problem)) {
+            0 => {}
+            1 => counts.push(format!("1 {singular}")),
+            n => counts.push(format!("{n} {plural}")),
+        }
+    }
+
+    for rule in RULES {
+        let n = count(&|finding| finding.rule.id == rule.id && kind(finding).is_none());
+        if n > 0 {
+            counts.push(format!("{n} {}", rule.heading));
+        }
+    }
+
+    let manifests = errors.iter().map(|finding| &finding.manifest_path).collect::<BTreeSet<_>>().len();
+    let plural = if manifests == 1 { "" } else { "s" };
+    Some(format!("{} across {manifests} manifest{plural}", counts.join(", ")))
+}
+
+/// Writes a JSON document summarizing the run to the given path
+///
+/// The document holds the number of findings by severity and by rule, the exit status, the time spent
+/// on the run and on each workspace, and the version of the tool, so pipelines needn't parse the full report.
+pub fn write_summary_file(path: &Path, reports: &[WorkspaceReport], status: i32, truncated: bool, duration: Duration) -> Result<()> {
+    let mut rules = BTreeMap::new();
+    for finding in reports.iter().flat_map(|report| &report.findings) {
+        *rules.entry(finding.rule.id).or_default() += 1;
+    }
+
+    let summary = RunSummary {
+        version: env!("CARGO_PKG_VERSION"),
+        status,
+        truncated,
+        findings: finding_counts(reports),
+        rules,
+        duration_ms: duration.as_millis(),
+        workspaces: reports
+            .iter()
+            .map(|report| WorkspaceSummary {
+                manifest_path: &report.manifest_path,
+                findings: finding_counts(core::slice::from_ref(report)),
+                duration_ms: report.duration.as_millis(),
+            })
+            .collect(),
+    };
+
+    let mut document = serde_json::to_string_pretty(&summary).context("Failed to serialize the run summary")?;
+    document.push('\n');
+    std::fs::write(path, document).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Counts the findings of the checked workspaces in total and by severity
+fn finding_counts(reports: &[WorkspaceReport]) -> FindingCounts {
+    let mut counts = FindingCounts::default();
+    for finding in reports.iter().flat_map(|report| &report.findings) {
+        counts.total += 1;
+        match finding.severity {
+            Severity::Error => counts.errors += 1,
+            Severity::Warning => counts.warnings += 1,
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, finding, general, lacking, report};
+    use crate::rules::{INCONSISTENT_ALIASES, WORKSPACE_INHERITANCE};
+
+    #[test]
+    fn test_violation_summary() {
+        let report = |findings| report(Path::new("/repo/Cargo.toml"), findings);
+
+        assert_eq!(violation_summary(&[]), None);
+        assert_eq!(
+            violation_summary(&[report(vec![finding(
+                &INCONSISTENT_ALIASES,
+                Severity::Warning,
+                "/repo/Cargo.toml",
+                general("package 'log' is imported under inconsistent names")
+            )])]),
+            None
+        );
+
+        let findings = vec![
+            finding(
+                &DEFAULT_FEATURES,
+                Severity::Error,
+                "/repo/Cargo.toml",
+                lacking("serde", DefaultFeaturesProblem::Missing),
+            ),
+            finding(
+                &DEFAULT_FEATURES,
+                Severity::Error,
+                "/repo/Cargo.toml",
+                lacking("tokio", DefaultFeaturesProblem::Missing),
+            ),
+            finding(
+                &DEFAULT_FEATURES,
+                Severity::Error,
+                "/repo/Cargo.toml",
+                lacking("clap", DefaultFeaturesProblem::SimpleVersion),
+            ),
+            finding(
+                &WORKSPACE_INHERITANCE,
+                Severity::Error,
+                "/repo/app/Cargo.toml",
+                entry(
+                    "log",
+                    "'log' in [dependencies]: declared directly although [workspace.dependencies] has an entry for it",
+                ),
+            ),
+            finding(
+                &INCONSISTENT_ALIASES,
+                Severity::Warning,
+                "/repo/lib/Cargo.toml",
+                general("package 'log' is imported under inconsistent names"),
+            ),
+        ];
+
+        assert_eq!(
+            violation_summary(&[report(findings)]).as_deref(),
+            Some(&*format!(
+                "2 missing default-features, 1 simple version string, 1 {} across 2 manifests",
+                WORKSPACE_INHERITANCE.heading
+            ))
+        );
+    }
+
+    #[test]
+    fn test_write_summary_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        let report = WorkspaceReport {
+            findings: vec![
+                finding(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    &manifest_path,
+                    entry("tokio", "'tokio': missing default-features = false"),
+                ),
+                finding(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    &manifest_path,
+                    entry("serde", "'serde': missing default-features = false"),
+                ),
+                finding(
+                    &INCONSISTENT_ALIASES,
+                    Severity::Warning,
+                    &manifest_path,
+                    general("package 'log' is imported under inconsistent names"),
+                ),
+            ],
+            duration: Duration::from_millis(12),
+            ..report(&manifest_path, Vec::new())
+        };
+
+        let path = dir.path().join("summary.json");
+        write_summary_file(&path, &[report], 1, false, Duration::from_millis(34)).unwrap();
+        let summary: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(summary["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(summary["status"], 1);
+        assert_eq!(summary["truncated"], false);
+        assert_eq!(summary["findings"], serde_json::json!({ "total": 3, "errors": 2, "warnings": 1 }));
+        assert_eq!(summary["rules"], serde_json::json!({ "ENDF001": 2, "ENDF006": 1 }));
+        assert_eq!(summary["duration_ms"], 34);
+        assert_eq!(summary["workspaces"][0]["manifest_path"], manifest_path.to_str().unwrap());
+        assert_eq!(summary["workspaces"][0]["duration_ms"], 12);
+    }
+}
@@ -0,0 +1,124 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{ReportedFinding, relative_uri, reported_findings};
+use crate::check::WorkspaceReport;
+use crate::rules::{RULES, Severity};
+
+/// Writes the findings of the checked workspaces as `TeamCity` service messages
+///
+/// Every rule with findings is declared as an inspection type, and each finding is reported as an
+/// inspection of its type pointing at the line declaring its dependency, so `TeamCity` lists them in
+/// the build's inspections tab.
+pub fn write_teamcity(reports: &[WorkspaceReport], out: &mut dyn Write) -> Result<()> {
+    let findings = reported_findings(reports);
+
+    for rule in RULES
+        .iter()
+        .filter(|rule| findings.iter().any(|reported| reported.finding.rule.id == rule.id))
+    {
+        writeln!(
+            out,
+            "##teamcity[inspectionType id='{}' name='{}' description='{}' category='{}']",
+            rule.id,
+            rule.name,
+            escape(rule.summary),
+            env!("CARGO_PKG_NAME")
+        )?;
+    }
+
+    for reported in &findings {
+        writeln!(out, "{}", inspection(reported))?;
+    }
+
+    Ok(())
+}
+
+/// Describes a finding as an inspection service message
+fn inspection(reported: &ReportedFinding<'_>) -> String {
+    let finding = reported.finding;
+    let line = finding
+        .location
+        .map(|location| format!(" line='{}'", location.line))
+        .unwrap_or_default();
+    let severity = match finding.severity {
+        Severity::Warning => "WARNING",
+        Severity::Error => "ERROR",
+    };
+
+    format!(
+        "##teamcity[inspection typeId='{}' message='{}' file='{}'{line} SEVERITY='{severity}']",
+        finding.rule.id,
+        escape(&finding.message),
+        escape(&relative_uri(&finding.manifest_path))
+    )
+}
+
+/// Escapes the characters of a text that are special in the attribute values of service messages
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '|' => escaped.push_str("||"),
+            '\'' => escaped.push_str("|'"),
+            '[' => escaped.push_str("|["),
+            ']' => escaped.push_str("|]"),
+            '\n' => escaped.push_str("|n"),
+            '\r' => escaped.push_str("|r"),
+            '\u{85}' => escaped.push_str("|x"),
+            '\u{2028}' => escaped.push_str("|l"),
+            '\u{2029}' => escaped.push_str("|p"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, report};
+    use crate::rules::{DEFAULT_FEATURES, Finding};
+    use crate::spans::Location;
+
+    #[test]
+    fn test_write_teamcity() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "[workspace.dependencies]\ntokio = \"1.0\"\n").unwrap();
+
+        let report = report(
+            &manifest_path,
+            vec![Finding::new(
+                &DEFAULT_FEATURES,
+                Severity::Error,
+                &manifest_path,
+                entry("tokio", "'tokio': uses simple version string"),
+                Some(Location { line: 2, column: 1 }),
+            )],
+        );
+
+        let mut out = Vec::new();
+        write_teamcity(&[report], &mut out).unwrap();
+        let messages = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = messages.lines().collect();
+
+        assert_eq!(lines.len(), 2, "{messages}");
+        assert!(
+            lines[0].starts_with("##teamcity[inspectionType id='ENDF001' name='default-features' description='"),
+            "{messages}"
+        );
+        assert!(
+            lines[1].starts_with("##teamcity[inspection typeId='ENDF001' message='|'tokio|': uses simple version string' file='"),
+            "{messages}"
+        );
+        assert!(lines[1].ends_with(" line='2' SEVERITY='ERROR']"), "{messages}");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("'a' in [b|c]\nd"), "|'a|' in |[b||c|]|nd");
+    }
+}
@@ -0,0 +1,171 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{relative_path, single_line};
+use crate::check::WorkspaceReport;
+use crate::rules::Finding;
+use crate::spans::Location;
+
+/// Placeholders a message template can use
+const PLACEHOLDERS: [&str; 8] = ["dep", "rule", "rule_name", "file", "line", "column", "severity", "message"];
+
+/// Checks that a message template only uses known placeholders, for use as a clap value parser
+///
+/// Placeholders are names in braces, such as `{dep}`, and `{{` and `}}` stand for literal braces.
+pub fn parse_message_template(template: &str) -> Result<String, String> {
+    render(template, |name| PLACEHOLDERS.contains(&name).then(String::new)).map(|_| template.to_string())
+}
+
+/// Writes each finding of the checked workspaces on a line shaped by the given message template
+///
+/// The placeholders are replaced by the dependency the finding is about, or nothing when it isn't
+/// about a single one, its rule ID and name, its manifest's path relative to the current directory,
+/// the line and column it's at, its severity, and its message, with line breaks replaced by spaces.
+/// Findings that aren't about a single dependency are at the start of their manifest.
+pub fn write_template(template: &str, reports: &[WorkspaceReport], out: &mut dyn Write) -> Result<()> {
+    for finding in reports.iter().flat_map(|report| &report.findings) {
+        let line = render(template, |name| placeholder(finding, name)).map_err(anyhow::Error::msg)?;
+        writeln!(out, "{line}")?;
+    }
+
+    Ok(())
+}
+
+/// Returns the value of a placeholder for a finding, or `None` for an unknown placeholder
+fn placeholder(finding: &Finding, name: &str) -> Option<String> {
+    let location = finding.location.unwrap_or(Location { line: 1, column: 1 });
+    let value = match name {
+        "dep" => finding.dependency.as_deref().unwrap_or_default().to_string(),
+        "rule" => finding.rule.id.to_string(),
+        "rule_name" => finding.rule.name.to_string(),
+        "file" => relative_path(&finding.manifest_path),
+        "line" => location.line.to_string(),
+        "column" => location.column.to_string(),
+        "severity" => finding.severity.to_string(),
+        "message" => single_line(&finding.message),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Replaces the placeholders of a template with the values `value` returns for them
+fn render(template: &str, value: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        let (before, from_brace) = rest.split_at(start);
+        rendered.push_str(before);
+        let (brace, after) = from_brace.split_at(1);
+        if let Some(after) = after.strip_prefix(brace) {
+            rendered.push_str(brace);
+            rest = after;
+            continue;
+        }
+
+        if brace == "}" {
+            return Err(format!(
+                "unmatched '}}' in message template '{template}', use '}}}}' for a literal brace"
+            ));
+        }
+
+        let Some((name, after)) = after.split_once('}') else {
+            return Err(format!(
+                "unclosed '{{' in message template '{template}', use '{{{{' for a literal brace"
+            ));
+        };
+
+        let Some(replacement) = value(name) else {
+            return Err(format!(
+                "unknown placeholder '{{{name}}}' in message template, expected one of {}",
+                PLACEHOLDERS.map(|name| format!("{{{name}}}")).join(", ")
+            ));
+        };
+
+        rendered.push_str(&replacement);
+        rest = after;
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::output::fixtures::{entry, finding, general, report};
+    use crate::rules::{DEFAULT_FEATURES, INCONSISTENT_ALIASES, Severity};
+
+    #[test]
+    fn test_write_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        let report = report(
+            &manifest_path,
+            vec![
+                Finding::new(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    &manifest_path,
+                    entry("tokio", "'tokio': uses simple version string"),
+                    Some(Location { line: 2, column: 3 }),
+                ),
+                finding(
+                    &INCONSISTENT_ALIASES,
+                    Severity::Warning,
+                    &manifest_path,
+                    general("package 'log' is imported under inconsistent names"),
+                ),
+            ],
+        );
+
+        let mut out = Vec::new();
+        write_template("{{{severity}}} {file}:{line} {rule}/{rule_name} dep={dep}", &[report], &mut out).unwrap();
+        let path = manifest_path.display();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "{{error}} {path}:2 ENDF001/default-features dep=tokio\n\
+                 {{warning}} {path}:1 ENDF006/inconsistent-aliases dep=\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_template_joins_message_lines() {
+        let manifest_path = Path::new("/ws/Cargo.toml");
+        let report = report(
+            manifest_path,
+            vec![finding(
+                &INCONSISTENT_ALIASES,
+                Severity::Warning,
+                manifest_path,
+                general("first line\r\nsecond line"),
+            )],
+        );
+
+        let mut out = Vec::new();
+        write_template("{message}", &[report], &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "first line  second line\n");
+    }
+
+    #[test]
+    fn test_parse_message_template() {
+        assert_eq!(
+            parse_message_template("{file}:{line}: {message}").unwrap(),
+            "{file}:{line}: {message}"
+        );
+        assert_eq!(parse_message_template("{{literal}}").unwrap(), "{{literal}}");
+        assert!(
+            parse_message_template("{path}")
+                .unwrap_err()
+                .contains("unknown placeholder '{path}'")
+        );
+        assert!(parse_message_template("{dep").unwrap_err().contains("unclosed '{'"));
+        assert!(parse_message_template("dep}").unwrap_err().contains("unmatched '}'"));
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::relative_path;
+use crate::check::WorkspaceReport;
+
+/// The dependencies and manifests accounting for the most errors, each with its error count
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TopOffenders {
+    /// Dependencies, by the name the findings give them
+    pub dependencies: Vec<(String, usize)>,
+
+    /// Manifests, labeled with their package name when they define one
+    pub manifests: Vec<(String, usize)>,
+}
+
+/// Returns the `limit` dependencies and manifests with the most errors in the checked workspaces
+///
+/// Both lists are ordered by decreasing error count, and by name among equal counts. Errors that
+/// aren't about a single dependency still count toward their manifest.
+pub fn top_offenders(reports: &[WorkspaceReport], limit: usize) -> TopOffenders {
+    let mut dependencies: BTreeMap<String, usize> = BTreeMap::new();
+    let mut manifests: BTreeMap<&Path, usize> = BTreeMap::new();
+    for finding in reports.iter().flat_map(WorkspaceReport::errors) {
+        if let Some(name) = finding.dependency.as_deref() {
+            *dependencies.entry(name.to_string()).or_default() += 1;
+        }
+
+        *manifests.entry(&finding.manifest_path).or_default() += 1;
+    }
+
+    let top = |mut counts: Vec<(String, usize)>| {
+        counts.sort_by(|(a_name, a_count), (b_name, b_count)| b_count.cmp(a_count).then_with(|| a_name.cmp(b_name)));
+        counts.truncate(limit);
+        counts
+    };
+
+    TopOffenders {
+        dependencies: top(dependencies.into_iter().collect()),
+        manifests: top(manifests.into_iter().map(|(path, count)| (manifest_label(path), count)).collect()),
+    }
+}
+
+/// Labels a manifest with its package name and relative path, or just its path when it has no package
+fn manifest_label(manifest_path: &Path) -> String {
+    let path = relative_path(manifest_path);
+    std::fs::read_to_string(manifest_path)
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+        .and_then(|parsed| {
+            parsed
+                .get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(toml::Value::as_str)
+                .map(|name| format!("{name} ({path})"))
+        })
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, finding, general, report};
+    use crate::rules::{DEFAULT_FEATURES, INCONSISTENT_ALIASES, Severity, WORKSPACE_INHERITANCE};
+
+    #[test]
+    fn test_top_offenders() {
+        let dir = tempfile::tempdir().unwrap();
+        let root_manifest = dir.path().join("Cargo.toml");
+        let app_manifest = dir.path().join("app/Cargo.toml");
+        std::fs::create_dir(dir.path().join("app")).unwrap();
+        std::fs::write(&root_manifest, "[workspace]\nmembers = [\"app\"]\n").unwrap();
+        std::fs::write(&app_manifest, "[package]\nname = \"app\"\n").unwrap();
+
+        let report = report(
+            &root_manifest,
+            vec![
+                finding(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    &root_manifest,
+                    entry("tokio", "'tokio': missing default-features = false"),
+                ),
+                finding(
+                    &DEFAULT_FEATURES,
+                    Severity::Error,
+                    &root_manifest,
+                    entry("serde", "'serde': missing default-features = false"),
+                ),
+                finding(
+                    &WORKSPACE_INHERITANCE,
+                    Severity::Error,
+                    &app_manifest,
+                    entry("tokio", "'tokio' in [dependencies]: declared directly"),
+                ),
+                finding(
+                    &WORKSPACE_INHERITANCE,
+                    Severity::Error,
+                    &app_manifest,
+                    entry("log", "'log' in [dependencies]: declared directly"),
+                ),
+                finding(
+                    &WORKSPACE_INHERITANCE,
+                    Severity::Error,
+                    &app_manifest,
+                    entry("serde", "'serde' in [dependencies]: declared directly"),
+                ),
+                finding(
+                    &INCONSISTENT_ALIASES,
+                    Severity::Warning,
+                    &root_manifest,
+                    general("package 'log' is imported under inconsistent names"),
+                ),
+            ],
+        );
+
+        let top = top_offenders(&[report], 2);
+        assert_eq!(top.dependencies, vec![("serde".to_string(), 2), ("tokio".to_string(), 2)]);
+        assert_eq!(top.manifests.len(), 2);
+        assert_eq!(top.manifests[0].1, 3);
+        assert!(top.manifests[0].0.starts_with("app ("), "{:?}", top.manifests);
+        assert!(top.manifests[0].0.ends_with("app/Cargo.toml)"), "{:?}", top.manifests);
+        assert_eq!(top.manifests[1], (relative_path(&root_manifest), 2));
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::features::features;
+use crate::members::load_members;
+use crate::validation::{member_dependency_tables, member_enables_default_features};
+
+/// How a workspace member uses one of its dependencies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyUsage {
+    /// Name of the dependency, as declared by the member
+    pub dependency: String,
+
+    /// Package name of the member, `None` for an entry of `[workspace.dependencies]` no member uses
+    pub member: Option<String>,
+
+    /// Dependency table declaring the dependency, such as `[dev-dependencies]`
+    pub section: String,
+
+    /// Whether the entry inherits its definition from `[workspace.dependencies]`
+    pub inherited: bool,
+
+    /// Whether the dependency ends up with its default features enabled
+    pub default_features: bool,
+
+    /// Features the entry enables, including those inherited from the workspace
+    pub features: Vec<String>,
+}
+
+/// Lists how the members of a workspace use their dependencies
+///
+/// There's an entry for each dependency of each member's dependency tables, with inherited entries
+/// resolved against `[workspace.dependencies]`, followed by an entry for each dependency of
+/// `[workspace.dependencies]` no member uses.
+pub fn dependency_usages(manifest_path: &Path) -> Result<Vec<DependencyUsage>> {
+    let content = std::fs::read_to_string(manifest_path).with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    let parsed: toml::Value = toml::from_str(&content).context("Failed to parse Cargo.toml")?;
+    let workspace_deps = parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    let mut usages = Vec::new();
+    for member in load_members(manifest_path, &content, None)? {
+        let member_parsed: toml::Value =
+            toml::from_str(&member.content).with_context(|| format!("Failed to parse {}", member.manifest_path.display()))?;
+        for (section, table) in member_dependency_tables(&member_parsed) {
+            for (name, value) in table {
+                let inherited = matches!(value.get("workspace"), Some(toml::Value::Boolean(true)));
+                let mut enabled: Vec<String> = workspace_deps
+                    .and_then(|deps| deps.get(name))
+                    .filter(|_| inherited)
+                    .map(features)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(features(value))
+                    .map(str::to_string)
+                    .collect();
+                enabled.sort();
+                enabled.dedup();
+
+                usages.push(DependencyUsage {
+                    dependency: name.clone(),
+                    member: Some(member.name.clone()),
+                    section: section.clone(),
+                    inherited,
+                    default_features: member_enables_default_features(name, value, workspace_deps),
+                    features: enabled,
+                });
+            }
+        }
+    }
+
+    for (name, value) in workspace_deps.into_iter().flatten() {
+        if !usages.iter().any(|usage| usage.inherited && usage.dependency == *name) {
+            usages.push(DependencyUsage {
+                dependency: name.clone(),
+                member: None,
+                section: "[workspace.dependencies]".to_string(),
+                inherited: false,
+                default_features: member_enables_default_features(name, value, None),
+                features: features(value).into_iter().map(str::to_string).collect(),
+            });
+        }
+    }
+
+    Ok(usages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_usages() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false, features = ["std"] }
+regex = "1.0"
+"#,
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("app")).unwrap();
+        std::fs::write(
+            dir.path().join("app/Cargo.toml"),
+            r#"
+[package]
+name = "app"
+
+[dependencies]
+serde = { workspace = true, features = ["derive"] }
+
+[dev-dependencies]
+tokio = "1.0"
+"#,
+        )
+        .unwrap();
+
+        let usages = dependency_usages(&dir.path().join("Cargo.toml")).unwrap();
+        assert_eq!(
+            usages,
+            vec![
+                DependencyUsage {
+                    dependency: "serde".to_string(),
+                    member: Some("app".to_string()),
+                    section: "[dependencies]".to_string(),
+                    inherited: true,
+                    default_features: false,
+                    features: vec!["derive".to_string(), "std".to_string()],
+                },
+                DependencyUsage {
+                    dependency: "tokio".to_string(),
+                    member: Some("app".to_string()),
+                    section: "[dev-dependencies]".to_string(),
+                    inherited: false,
+                    default_features: true,
+                    features: Vec::new(),
+                },
+                DependencyUsage {
+                    dependency: "regex".to_string(),
+                    member: None,
+                    section: "[workspace.dependencies]".to_string(),
+                    inherited: false,
+                    default_features: true,
+                    features: Vec::new(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use flate2::read::GzDecoder;
+
+use crate::members::Member;
+use crate::metadata::cargo_metadata;
+use crate::rules::Violation;
+use crate::validation::{ExceptionPattern, is_excepted, validate_dependency};
+
+/// Sections of a package manifest whose dependencies reach the consumers of a published crate
+const SHIPPED_SECTIONS: [&str; 2] = ["dependencies", "build-dependencies"];
+
+/// Packages the publishable members and validates the normalized manifests Cargo generates for them
+///
+/// Publishing rewrites Cargo.toml, inlining everything inherited from the workspace. Each member
+/// inheriting dependencies with `workspace = true` is packaged with `cargo package --no-verify`, and
+/// those dependencies are checked in the manifest found inside the resulting `.crate` file, which
+/// confirms that `default-features = false` survives publishing. Members that can't be published are
+/// skipped.
+///
+/// # Returns
+///
+/// The errors found, grouped by the path of the `.crate` file they were found in.
+pub fn check_packaged_members(
+    manifest_path: &Path,
+    content: &str,
+    members: &[Member],
+    exceptions: &[ExceptionPattern],
+) -> Result<Vec<(PathBuf, Vec<Violation>)>> {
+    let workspace: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let workspace_version = workspace
+        .get("workspace")
+        .and_then(|w| w.get("package"))
+        .and_then(|p| p.get("version"))
+        .and_then(toml::Value::as_str);
+
+    let mut target_dir = None;
+    let mut results = Vec::new();
+    for member in members {
+        let parsed: toml::Value =
+            toml::from_str(&member.content).with_context(|| format!("Failed to parse {}", member.manifest_path.display()))?;
+        let Some(package) = parsed.get("package") else {
+            continue;
+        };
+
+        if !is_publishable(package) {
+            continue;
+        }
+
+        let inherited: Vec<_> = inherited_dependencies(&parsed)
+            .into_iter()
+            .filter(|(_, name)| !is_excepted(exceptions, name))
+            .collect();
+        if inherited.is_empty() {
+            continue;
+        }
+
+        // Packages without a version can't be published
+        let version = match package.get("version") {
+            Some(toml::Value::String(version)) => version.as_str(),
+            Some(_) => workspace_version.with_context(|| format!("No workspace version found for member '{}'", member.name))?,
+            None => continue,
+        };
+
+        let target_dir = match &target_dir {
+            Some(target_dir) => target_dir,
+            None => target_dir.insert(find_target_dir(manifest_path)?),
+        };
+
+        let crate_path = package_member(manifest_path, &member.name, version, target_dir)?;
+        let normalized = read_packaged_manifest(&crate_path, &format!("{}-{version}", member.name))?;
+        let normalized: toml::Value =
+            toml::from_str(&normalized).with_context(|| format!("Failed to parse the manifest in {}", crate_path.display()))?;
+
+        let errors: Vec<_> = inherited
+            .iter()
+            .filter_map(|(table_path, name)| {
+                let value = table_path.iter().try_fold(&normalized, |value, key| value.get(key))?.get(name)?;
+                let problem = validate_dependency(value).err()?;
+                Some(Violation::default_features(table_path, name, problem))
+            })
+            .collect();
+
+        if !errors.is_empty() {
+            results.push((crate_path, errors));
+        }
+    }
+
+    Ok(results)
+}
+
+/// Finds path dependencies of `[workspace.dependencies]` without a `version` that publishable members
+/// inherit
+///
+/// Publishing a crate requires each of its shipped dependencies to carry a version, since the path
+/// means nothing once the crate is on a registry, so `cargo publish` fails for these members.
+pub fn find_unversioned_path_dependencies(content: &str, members: &[Member]) -> Result<Vec<Violation>> {
+    let workspace: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let Some(deps_table) = workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut inherited_by = Vec::new();
+    for member in members {
+        let parsed: toml::Value =
+            toml::from_str(&member.content).with_context(|| format!("Failed to parse {}", member.manifest_path.display()))?;
+        if parsed.get("package").is_some_and(is_publishable) {
+            inherited_by.push((member.name.as_str(), inherited_dependencies(&parsed)));
+        }
+    }
+
+    let mut unversioned = Vec::new();
+    for (name, value) in deps_table {
+        if value.get("path").is_none() || value.get("version").is_some() {
+            continue;
+        }
+
+        let users: Vec<_> = inherited_by
+            .iter()
+            .filter(|(_, inherited)| inherited.iter().any(|(_, inherited_name)| inherited_name == name))
+            .map(|(member, _)| *member)
+            .collect();
+
+        if !users.is_empty() {
+            unversioned.push(Violation::workspace_dependency(
+                name,
+                format!(
+                    "'{name}': path dependency without a version, inherited by publishable members {}",
+                    users.join(", ")
+                ),
+            ));
+        }
+    }
+
+    Ok(unversioned)
+}
+
+/// Returns whether a package can be published, following the semantics of the `publish` key
+fn is_publishable(package: &toml::Value) -> bool {
+    match package.get("publish") {
+        Some(toml::Value::Boolean(publish)) => *publish,
+        Some(toml::Value::Array(registries)) => !registries.is_empty(),
+        _ => true,
+    }
+}
+
+/// Returns the table path and name of every shipped dependency inherited with `workspace = true`
+fn inherited_dependencies(parsed: &toml::Value) -> Vec<(Vec<String>, String)> {
+    let mut scopes = vec![(Vec::new(), parsed)];
+    if let Some(targets) = parsed.get("target").and_then(toml::Value::as_table) {
+        scopes.extend(
+            targets
+                .iter()
+                .map(|(target, value)| (vec!["target".to_string(), target.clone()], value)),
+        );
+    }
+
+    let mut inherited = Vec::new();
+    for (prefix, scope) in scopes {
+        for section in SHIPPED_SECTIONS {
+            let Some(deps_table) = scope.get(section).and_then(toml::Value::as_table) else {
+                continue;
+            };
+
+            for (name, value) in deps_table {
+                if value.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+                    let mut table_path = prefix.clone();
+                    table_path.push(section.to_string());
+                    inherited.push((table_path, name.clone()));
+                }
+            }
+        }
+    }
+
+    inherited
+}
+
+/// Asks Cargo for the target directory of the workspace
+fn find_target_dir(manifest_path: &Path) -> Result<PathBuf> {
+    cargo_metadata(manifest_path, true, None)?
+        .get("target_directory")
+        .and_then(serde_json::Value::as_str)
+        .map(PathBuf::from)
+        .context("cargo metadata didn't report a target directory")
+}
+
+/// Runs `cargo package` for one member and returns the path of the resulting `.crate` file
+fn package_member(manifest_path: &Path, name: &str, version: &str, target_dir: &Path) -> Result<PathBuf> {
+    let output = Command::new("cargo")
+        .args([
+            "package",
+            "--no-verify",
+            "--allow-dirty",
+            "--quiet",
+            "--package",
+            name,
+            "--manifest-path",
+        ])
+        .arg(manifest_path)
+        .output()
+        .context("Failed to run cargo package")?;
+    if !output.status.success() {
+        bail!(
+            "cargo package failed for '{name}': {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(target_dir.join("package").join(format!("{name}-{version}.crate")))
+}
+
+/// Extracts the normalized Cargo.toml from a `.crate` file
+fn read_packaged_manifest(crate_path: &Path, root_dir: &str) -> Result<String> {
+    let file = std::fs::File::open(crate_path).with_context(|| format!("Failed to open {}", crate_path.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let manifest = Path::new(root_dir).join("Cargo.toml");
+
+    for entry in archive
+        .entries()
+        .with_context(|| format!("Failed to read {}", crate_path.display()))?
+    {
+        let mut entry = entry.with_context(|| format!("Failed to read {}", crate_path.display()))?;
+        if entry.path().is_ok_and(|path| path == manifest) {
+            let mut content = String::new();
+            let _ = entry
+                .read_to_string(&mut content)
+                .with_context(|| format!("Failed to read the manifest in {}", crate_path.display()))?;
+            return Ok(content);
+        }
+    }
+
+    bail!("No Cargo.toml found in {}", crate_path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::messages;
+
+    #[test]
+    fn test_is_publishable() {
+        let package = |content: &str| toml::from_str::<toml::Value>(content).unwrap();
+        assert!(is_publishable(&package("name = \"a\"")));
+        assert!(is_publishable(&package("publish = true")));
+        assert!(is_publishable(&package("publish = [\"internal\"]")));
+        assert!(!is_publishable(&package("publish = false")));
+        assert!(!is_publishable(&package("publish = []")));
+    }
+
+    #[test]
+    fn test_find_unversioned_path_dependencies() {
+        let content = r#"
+[workspace.dependencies]
+util = { path = "util", default-features = false }
+core = { path = "core", version = "0.1.0", default-features = false }
+testing = { path = "testing", default-features = false }
+"#;
+        let member = |name: &str, content: &str| Member {
+            name: name.to_string(),
+            manifest_path: PathBuf::from(name).join("Cargo.toml"),
+            content: format!("[package]\nname = \"{name}\"\n{content}"),
+        };
+        let members = [
+            member("app", "[dependencies]\nutil = { workspace = true }\ncore = { workspace = true }\n"),
+            member("tool", "publish = false\n[dependencies]\nutil = { workspace = true }\n"),
+            member(
+                "lib",
+                "[dependencies]\nutil = { workspace = true }\n[dev-dependencies]\ntesting = { workspace = true }\n",
+            ),
+        ];
+
+        let unversioned = find_unversioned_path_dependencies(content, &members).unwrap();
+        assert_eq!(
+            messages(&unversioned),
+            ["'util': path dependency without a version, inherited by publishable members app, lib"]
+        );
+    }
+
+    #[test]
+    fn test_inherited_dependencies() {
+        let parsed: toml::Value = toml::from_str(
+            r#"
+[dependencies]
+serde = { workspace = true }
+regex = "1.0"
+
+[dev-dependencies]
+tempfile = { workspace = true }
+
+[target.'cfg(unix)'.build-dependencies]
+cc = { workspace = true, features = ["parallel"] }
+"#,
+        )
+        .unwrap();
+
+        let inherited = inherited_dependencies(&parsed);
+        assert_eq!(
+            inherited,
+            [
+                (vec!["dependencies".to_string()], "serde".to_string()),
+                (
+                    vec!["target".to_string(), "cfg(unix)".to_string(), "build-dependencies".to_string()],
+                    "cc".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_packaged_manifest() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let crate_path = dir.path().join("app-0.1.0.crate");
+
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            std::fs::File::create(&crate_path).unwrap(),
+            flate2::Compression::default(),
+        ));
+        for (path, content) in [
+            ("app-0.1.0/src/lib.rs", ""),
+            ("app-0.1.0/Cargo.toml", "[package]\nname = \"app\"\n"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_cksum();
+            builder.append_data(&mut header, path, content.as_bytes()).unwrap();
+        }
+        let _ = builder.into_inner().unwrap().finish().unwrap();
+
+        assert_eq!(
+            read_packaged_manifest(&crate_path, "app-0.1.0").unwrap(),
+            "[package]\nname = \"app\"\n"
+        );
+        let _ = read_packaged_manifest(&crate_path, "other-0.1.0").unwrap_err();
+    }
+}
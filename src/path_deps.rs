@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::members::Member;
+use crate::rules::Violation;
+use crate::validation::{
+    ExceptionPattern, dependency_package_name, enables_default_features, member_dependency_tables, member_enables_default_features,
+    path_dependencies, validate_package_dependencies,
+};
+
+/// Follows path dependencies that point outside the workspace and validates the manifests they lead to
+///
+/// Path dependencies of the followed crates are followed in turn, so the whole chain of out-of-tree
+/// crates reachable from the workspace gets checked. Crates living under the workspace root are
+/// skipped since they are members governed by the workspace itself.
+///
+/// # Returns
+///
+/// A vector pairing the path of each followed manifest with the violations found in it. Manifests
+/// without errors are omitted.
+pub fn follow_path_dependencies(
+    manifest_path: &Path,
+    content: &str,
+    exceptions: &[ExceptionPattern],
+) -> Result<Vec<(PathBuf, Vec<Violation>)>> {
+    let workspace_root = canonical_parent(manifest_path)?;
+
+    let mut visited = HashSet::new();
+    let mut pending: Vec<PathBuf> = path_dependencies(content)?
+        .into_iter()
+        .map(|(_, path)| workspace_root.join(path))
+        .collect();
+
+    let mut results = Vec::new();
+    while let Some(crate_dir) = pending.pop() {
+        let crate_dir = crate_dir
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve path dependency {}", crate_dir.display()))?;
+
+        if crate_dir.starts_with(&workspace_root) || !visited.insert(crate_dir.clone()) {
+            continue;
+        }
+
+        let crate_manifest = crate_dir.join("Cargo.toml");
+        let crate_content =
+            std::fs::read_to_string(&crate_manifest).with_context(|| format!("Failed to read {}", crate_manifest.display()))?;
+
+        let errors = validate_package_dependencies(&crate_content, exceptions)
+            .with_context(|| format!("Failed to validate {}", crate_manifest.display()))?;
+        if !errors.is_empty() {
+            results.push((crate_manifest, errors));
+        }
+
+        pending.extend(path_dependencies(&crate_content)?.into_iter().map(|(_, path)| crate_dir.join(path)));
+    }
+
+    Ok(results)
+}
+
+/// Finds published path dependencies whose own default features are non-empty while consumers in the
+/// workspace still use them with default features enabled
+///
+/// Only `[workspace.dependencies]` entries carrying both `path` and `version` are considered, since
+/// these are the crates the workspace both produces and consumes. For each of them, the `default`
+/// feature of the crate's manifest is compared against how the workspace table and the members
+/// declare the dependency.
+///
+/// # Returns
+///
+/// A vector of violations, one per offending crate, naming its default features and its consumers.
+pub fn find_path_default_consumers(manifest_path: &Path, content: &str, members: &[Member]) -> Result<Vec<Violation>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let Some(workspace_deps) = parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let root = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut warnings = Vec::new();
+    for (name, value) in workspace_deps {
+        let (Some(path), Some(_)) = (value.get("path").and_then(toml::Value::as_str), value.get("version")) else {
+            continue;
+        };
+
+        let crate_manifest = root.join(path).join("Cargo.toml");
+        let crate_content =
+            std::fs::read_to_string(&crate_manifest).with_context(|| format!("Failed to read {}", crate_manifest.display()))?;
+        let crate_parsed: toml::Value =
+            toml::from_str(&crate_content).with_context(|| format!("Failed to parse {}", crate_manifest.display()))?;
+
+        let default_features: Vec<&str> = crate_parsed
+            .get("features")
+            .and_then(|f| f.get("default"))
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(toml::Value::as_str)
+            .collect();
+        if default_features.is_empty() {
+            continue;
+        }
+
+        let package = dependency_package_name(name, value);
+        let mut consumers = Vec::new();
+        if enables_default_features(value) {
+            consumers.push("[workspace.dependencies]".to_string());
+        }
+
+        for member in members {
+            let member_parsed: toml::Value =
+                toml::from_str(&member.content).with_context(|| format!("Failed to parse {}", member.manifest_path.display()))?;
+            let uses_defaults = member_dependency_tables(&member_parsed).into_iter().any(|(_, deps)| {
+                deps.iter().any(|(dep_name, dep_value)| {
+                    // Entries inherited from the workspace use its key, which may itself be a rename
+                    let dep_package = if dep_name == name {
+                        package
+                    } else {
+                        dependency_package_name(dep_name, dep_value)
+                    };
+                    dep_package == package && member_enables_default_features(dep_name, dep_value, Some(workspace_deps))
+                })
+            });
+
+            if uses_defaults {
+                consumers.push(format!("member '{}'", member.name));
+            }
+        }
+
+        if !consumers.is_empty() {
+            warnings.push(Violation::workspace_dependency(
+                name,
+                format!(
+                    "'{package}' at {path} has default features [{}] which are enabled by {}",
+                    default_features.join(", "),
+                    consumers.join(", "),
+                ),
+            ));
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Returns the canonicalized directory containing the given manifest
+fn canonical_parent(manifest_path: &Path) -> Result<PathBuf> {
+    let parent = manifest_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    parent
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", parent.display()))
+}
@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::members::Member;
+
+/// The manifests of a workspace, as handed to custom rules
+#[derive(Debug, Serialize)]
+pub struct ManifestModel<'a> {
+    /// The workspace's root manifest
+    pub workspace: ManifestEntry<'a>,
+
+    /// The manifests of the workspace members
+    pub members: Vec<ManifestEntry<'a>>,
+}
+
+/// A single manifest handed to custom rules
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry<'a> {
+    /// The package name of a member, absent for the workspace's root manifest
+    pub name: Option<&'a str>,
+
+    /// Path to the manifest
+    pub manifest_path: &'a Path,
+
+    /// The parsed content of the manifest
+    pub manifest: toml::Value,
+}
+
+impl<'a> ManifestModel<'a> {
+    /// Parses the manifests of a workspace and its members
+    pub fn new(manifest_path: &'a Path, content: &str, members: &'a [Member]) -> Result<Self> {
+        let workspace = ManifestEntry {
+            name: None,
+            manifest_path,
+            manifest: toml::from_str(content).context("Failed to parse Cargo.toml")?,
+        };
+
+        let members = members
+            .iter()
+            .map(|member| {
+                Ok(ManifestEntry {
+                    name: Some(&member.name),
+                    manifest_path: &member.manifest_path,
+                    manifest: toml::from_str(&member.content)
+                        .with_context(|| format!("Failed to parse {}", member.manifest_path.display()))?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { workspace, members })
+    }
+}
+
+/// A finding emitted by a custom rule
+#[derive(Debug, Deserialize)]
+struct CustomFinding {
+    /// Path to the manifest the finding is about, the workspace's root manifest when absent
+    #[serde(default)]
+    manifest_path: Option<PathBuf>,
+
+    /// Description of the problem
+    message: String,
+}
+
+/// Runs the custom rules compiled to WebAssembly against a workspace
+///
+/// Plugin paths are relative to the workspace root. Each plugin is handed the manifest model as JSON
+/// and returns its findings as a JSON array of `{ "manifest_path": ..., "message": ... }` objects, see
+/// `run_plugin` for the calling convention. Messages are prefixed with the plugin's file name.
+///
+/// # Returns
+///
+/// A vector of findings, each paired with the path to the manifest it's about.
+pub fn run_plugins(plugins: &[PathBuf], model: &ManifestModel<'_>) -> Result<Vec<(PathBuf, String)>> {
+    let root = model.workspace.manifest_path.parent().unwrap_or_else(|| Path::new(""));
+    let input = serde_json::to_string(model).context("Failed to serialize the manifests")?;
+
+    let mut findings = Vec::new();
+    for plugin in plugins {
+        let path = root.join(plugin);
+        let output = run_plugin(&path, &input).with_context(|| format!("Failed to run plugin {}", path.display()))?;
+        let name = plugin.file_stem().unwrap_or(plugin.as_os_str()).to_string_lossy();
+        findings.extend(
+            parse_findings(&output, &name, model.workspace.manifest_path)
+                .with_context(|| format!("Plugin {} returned invalid findings", path.display()))?,
+        );
+    }
+
+    Ok(findings)
+}
+
+/// Turns the JSON output of a custom rule into findings
+fn parse_findings(output: &str, rule_name: &str, manifest_path: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let findings: Vec<CustomFinding> = serde_json::from_str(output)?;
+    Ok(findings
+        .into_iter()
+        .map(|finding| {
+            (
+                finding.manifest_path.unwrap_or_else(|| manifest_path.to_path_buf()),
+                format!("{rule_name}: {}", finding.message),
+            )
+        })
+        .collect())
+}
+
+/// Runs a single plugin, returning its output
+///
+/// A plugin is a WebAssembly module without imports that exports its `memory` along with two functions:
+/// `alloc(len: i32) -> i32`, which returns the address of a buffer of the given size, and
+/// `check(ptr: i32, len: i32) -> i64`, which reads the input from that buffer and returns the address
+/// of its UTF-8 output in the upper 32 bits and the output's length in the lower 32 bits.
+#[cfg(feature = "wasm-plugins")]
+fn run_plugin(path: &Path, input: &str) -> Result<String> {
+    use wasmtime::{Engine, Instance, Module, Store};
+
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, path)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .context("The plugin doesn't export its memory")?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+    let check = instance.get_typed_func::<(i32, i32), i64>(&mut store, "check")?;
+
+    let len = i32::try_from(input.len()).context("The manifests are too large to be handed to the plugin")?;
+    let ptr = alloc.call(&mut store, len)?;
+    memory.write(&mut store, usize::try_from(ptr)?, input.as_bytes())?;
+
+    let packed = check.call(&mut store, (ptr, len))?.cast_unsigned();
+    let mut output = vec![0; usize::try_from(packed & 0xFFFF_FFFF)?];
+    memory.read(&store, usize::try_from(packed >> 32)?, &mut output)?;
+    String::from_utf8(output).context("The plugin's output isn't valid UTF-8")
+}
+
+/// Fails, since running plugins requires the `wasm-plugins` feature
+#[cfg(not(feature = "wasm-plugins"))]
+fn run_plugin(_path: &Path, _input: &str) -> Result<String> {
+    anyhow::bail!("This build doesn't support plugins, reinstall with `--features wasm-plugins`")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_model() {
+        let members = [Member {
+            name: "member".to_string(),
+            manifest_path: PathBuf::from("member/Cargo.toml"),
+            content: "[package]\nname = \"member\"\n".to_string(),
+        }];
+        let model = ManifestModel::new(Path::new("Cargo.toml"), "[workspace]\nmembers = [\"member\"]\n", &members).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&model).unwrap(),
+            serde_json::json!({
+                "workspace": { "name": null, "manifest_path": "Cargo.toml", "manifest": { "workspace": { "members": ["member"] } } },
+                "members": [
+                    { "name": "member", "manifest_path": "member/Cargo.toml", "manifest": { "package": { "name": "member" } } },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_findings() {
+        let output = r#"[{ "message": "no openssl" }, { "manifest_path": "member/Cargo.toml", "message": "no tokio" }]"#;
+        assert_eq!(
+            parse_findings(output, "policy", Path::new("Cargo.toml")).unwrap(),
+            [
+                (PathBuf::from("Cargo.toml"), "policy: no openssl".to_string()),
+                (PathBuf::from("member/Cargo.toml"), "policy: no tokio".to_string()),
+            ]
+        );
+
+        let _ = parse_findings("{}", "policy", Path::new("Cargo.toml")).unwrap_err();
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn test_run_plugin() {
+        let expected = r#"[{"message":"checked"}]"#;
+        let module = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 1024) "{escaped}")
+                (func (export "alloc") (param i32) (result i32) i32.const 0)
+                (func (export "check") (param i32 i32) (result i64) i64.const {packed}))"#,
+            escaped = expected.replace('"', "\\\""),
+            packed = (1024_i64 << 32) | i64::try_from(expected.len()).unwrap(),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.wat");
+        std::fs::write(&path, module).unwrap();
+
+        assert_eq!(run_plugin(&path, "{}").unwrap(), expected);
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    #[test]
+    fn test_run_plugin_without_feature() {
+        let err = run_plugin(Path::new("policy.wasm"), "{}").unwrap_err();
+        assert!(err.to_string().contains("--features wasm-plugins"));
+    }
+}
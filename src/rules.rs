@@ -0,0 +1,1028 @@
+use core::fmt;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::config::closest_match;
+use crate::spans::Location;
+use crate::validation::{DefaultFeaturesProblem, section_label};
+
+/// How the findings of a rule affect the outcome of a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Reported without failing the check
+    #[serde(alias = "warn")]
+    Warning,
+
+    /// Reported and fails the check
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// A check performed by the tool, identified by a stable ID
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// Stable identifier, such as `ENDF001`
+    pub id: &'static str,
+
+    /// Human-friendly name, accepted wherever an ID is
+    pub name: &'static str,
+
+    /// Severity of the rule's findings unless overridden
+    pub severity: Severity,
+
+    /// Whether the rule runs without being explicitly enabled
+    pub enabled_by_default: bool,
+
+    /// One-line description of what the rule checks
+    pub summary: &'static str,
+
+    /// Plural noun phrase describing the rule's findings in report headings
+    pub heading: &'static str,
+
+    /// Extended explanation printed by `--explain`
+    pub explanation: &'static str,
+}
+
+/// Workspace dependencies must declare `default-features = false`
+pub const DEFAULT_FEATURES: Rule = Rule {
+    id: "ENDF001",
+    name: "default-features",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Entries in [workspace.dependencies] must declare default-features = false",
+    heading: "dependencies without default-features = false",
+    explanation: include_str!("explanations/ENDF001.md"),
+};
+
+/// Members must inherit crates governed by `[workspace.dependencies]` instead of renaming them
+pub const WORKSPACE_BYPASS: Rule = Rule {
+    id: "ENDF002",
+    name: "workspace-bypass",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Members must not use `package` renames to bypass [workspace.dependencies]",
+    heading: "member dependencies bypassing [workspace.dependencies]",
+    explanation: include_str!("explanations/ENDF002.md"),
+};
+
+/// Path dependencies outside the workspace must declare `default-features = false`
+pub const PATH_DEPENDENCIES: Rule = Rule {
+    id: "ENDF003",
+    name: "path-dependencies",
+    severity: Severity::Error,
+    enabled_by_default: false,
+    summary: "Crates reached through path dependencies outside the workspace must disable default features",
+    heading: "path dependency entries without default-features = false",
+    explanation: include_str!("explanations/ENDF003.md"),
+};
+
+/// Published manifests must keep `default-features = false`
+pub const PACKAGED_MANIFESTS: Rule = Rule {
+    id: "ENDF004",
+    name: "packaged-manifests",
+    severity: Severity::Error,
+    enabled_by_default: false,
+    summary: "Manifests normalized by `cargo package` must keep default-features = false",
+    heading: "packaged dependencies without default-features = false",
+    explanation: include_str!("explanations/ENDF004.md"),
+};
+
+/// Members shouldn't declare a crate in both dependency sections with different defaults
+pub const BUILD_DEPENDENCY_MISMATCH: Rule = Rule {
+    id: "ENDF005",
+    name: "build-dependency-mismatch",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "A crate in both [dependencies] and [build-dependencies] must use the same default-features setting",
+    heading: "mismatched build dependencies",
+    explanation: include_str!("explanations/ENDF005.md"),
+};
+
+/// Members should import a package under the same name
+pub const INCONSISTENT_ALIASES: Rule = Rule {
+    id: "ENDF006",
+    name: "inconsistent-aliases",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "Members should import each package under the same name",
+    heading: "inconsistently aliased packages",
+    explanation: include_str!("explanations/ENDF006.md"),
+};
+
+/// Published path crates shouldn't have default features their consumers leave enabled
+pub const PATH_DEFAULTS: Rule = Rule {
+    id: "ENDF007",
+    name: "path-defaults",
+    severity: Severity::Warning,
+    enabled_by_default: false,
+    summary: "Published path crates with default features must be consumed with default-features = false",
+    heading: "path crates with default features left enabled",
+    explanation: include_str!("explanations/ENDF007.md"),
+};
+
+/// The resolved dependency graph shouldn't enable defaults the workspace disables
+pub const LEAKED_DEFAULTS: Rule = Rule {
+    id: "ENDF008",
+    name: "leaked-defaults",
+    severity: Severity::Warning,
+    enabled_by_default: false,
+    summary: "Default features disabled in [workspace.dependencies] must stay disabled in the resolved graph",
+    heading: "dependencies whose default features leak into the resolved graph",
+    explanation: include_str!("explanations/ENDF008.md"),
+};
+
+/// Dependencies must not request the `default` feature explicitly
+pub const DEFAULT_IN_FEATURES: Rule = Rule {
+    id: "ENDF009",
+    name: "default-in-features",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Dependencies must not list \"default\" in their features",
+    heading: "dependencies re-enabling default features through their features",
+    explanation: include_str!("explanations/ENDF009.md"),
+};
+
+/// Member features must not enable the default features of a dependency
+pub const DEFAULT_FEATURE_REFERENCES: Rule = Rule {
+    id: "ENDF010",
+    name: "default-feature-references",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Member [features] must not enable a dependency's default feature with \"<dep>/default\"",
+    heading: "member features re-enabling the default features of a dependency",
+    explanation: include_str!("explanations/ENDF010.md"),
+};
+
+/// Members must not re-enable the default features of inherited dependencies
+pub const MEMBER_DEFAULT_OVERRIDES: Rule = Rule {
+    id: "ENDF011",
+    name: "member-default-overrides",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Members must not set default-features = true on dependencies inherited with workspace = true",
+    heading: "member dependencies overriding the workspace's default-features setting",
+    explanation: include_str!("explanations/ENDF011.md"),
+};
+
+/// Member dependencies should be centralized in `[workspace.dependencies]`
+pub const DECENTRALIZED_DEPENDENCIES: Rule = Rule {
+    id: "ENDF012",
+    name: "decentralized-dependencies",
+    severity: Severity::Error,
+    enabled_by_default: false,
+    summary: "Member dependencies must be inherited from [workspace.dependencies] instead of declaring their own source",
+    heading: "member dependencies not centralized in [workspace.dependencies]",
+    explanation: include_str!("explanations/ENDF012.md"),
+};
+
+/// Members must inherit dependencies that exist in `[workspace.dependencies]`
+pub const WORKSPACE_INHERITANCE: Rule = Rule {
+    id: "ENDF013",
+    name: "workspace-inheritance",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Members must use workspace = true for dependencies declared in [workspace.dependencies]",
+    heading: "member dependencies declared directly instead of inheriting from [workspace.dependencies]",
+    explanation: include_str!("explanations/ENDF013.md"),
+};
+
+/// Workspace dependencies must meet the configured version precision
+pub const VERSION_PRECISION: Rule = Rule {
+    id: "ENDF014",
+    name: "version-precision",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Version requirements in [workspace.dependencies] must meet the configured version-precision",
+    heading: "dependencies with imprecise version requirements",
+    explanation: include_str!("explanations/ENDF014.md"),
+};
+
+/// Workspace dependencies must not come from git repositories
+pub const GIT_DEPENDENCIES: Rule = Rule {
+    id: "ENDF015",
+    name: "git-dependencies",
+    severity: Severity::Error,
+    enabled_by_default: false,
+    summary: "[workspace.dependencies] must not contain git dependencies, unless allowed by the configuration",
+    heading: "git dependencies",
+    explanation: include_str!("explanations/ENDF015.md"),
+};
+
+/// Path dependencies used by publishable members must carry a version
+pub const UNVERSIONED_PATH_DEPENDENCIES: Rule = Rule {
+    id: "ENDF016",
+    name: "unversioned-path-dependencies",
+    severity: Severity::Error,
+    enabled_by_default: false,
+    summary: "Path dependencies in [workspace.dependencies] inherited by publishable members must have a version",
+    heading: "path dependencies without a version used by publishable members",
+    explanation: include_str!("explanations/ENDF016.md"),
+};
+
+/// Workspace dependencies should be sorted alphabetically
+pub const SORTED_DEPENDENCIES: Rule = Rule {
+    id: "ENDF017",
+    name: "sorted-dependencies",
+    severity: Severity::Warning,
+    enabled_by_default: false,
+    summary: "Entries in [workspace.dependencies] should be sorted alphabetically",
+    heading: "workspace dependencies out of alphabetical order",
+    explanation: include_str!("explanations/ENDF017.md"),
+};
+
+/// Dependencies shouldn't list the same feature twice
+pub const DUPLICATE_FEATURES: Rule = Rule {
+    id: "ENDF018",
+    name: "duplicate-features",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "A dependency's features should not list the same feature twice, including features inherited from the workspace",
+    heading: "dependencies listing the same feature more than once",
+    explanation: include_str!("explanations/ENDF018.md"),
+};
+
+/// Dependencies must not enable features denied by the configuration
+pub const DENIED_FEATURES: Rule = Rule {
+    id: "ENDF019",
+    name: "denied-features",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Dependencies must not enable the features listed in the denied-features setting",
+    heading: "dependencies enabling denied features",
+    explanation: include_str!("explanations/ENDF019.md"),
+};
+
+/// Dependencies without default features must enable the features required by the configuration
+pub const REQUIRED_FEATURES: Rule = Rule {
+    id: "ENDF020",
+    name: "required-features",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Dependencies declared without default features must enable the features listed in the required-features setting",
+    heading: "dependencies missing required features",
+    explanation: include_str!("explanations/ENDF020.md"),
+};
+
+/// Dependencies must not enable more features than their configured budget
+pub const FEATURE_BUDGET: Rule = Rule {
+    id: "ENDF021",
+    name: "feature-budget",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "Dependencies must not enable more features than the max-features settings allow",
+    heading: "dependencies exceeding their feature budget",
+    explanation: include_str!("explanations/ENDF021.md"),
+};
+
+/// Dependencies must not enable umbrella features that bring back most of a crate
+pub const UMBRELLA_FEATURES: Rule = Rule {
+    id: "ENDF022",
+    name: "umbrella-features",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "Dependencies must not enable umbrella features such as full, all, or complete",
+    heading: "dependencies enabling umbrella features",
+    explanation: include_str!("explanations/ENDF022.md"),
+};
+
+/// Members' own default feature must be empty or limited to the allowed features
+pub const MEMBER_DEFAULT_FEATURES: Rule = Rule {
+    id: "ENDF023",
+    name: "member-default-features",
+    severity: Severity::Warning,
+    enabled_by_default: false,
+    summary: "Members' own default feature must only enable the features listed in the allowed-default-features setting",
+    heading: "members with excessive default features",
+    explanation: include_str!("explanations/ENDF023.md"),
+};
+
+/// Optional dependencies must be enabled through `dep:` features
+pub const OPTIONAL_DEPENDENCY_GATING: Rule = Rule {
+    id: "ENDF024",
+    name: "optional-dependency-gating",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "Optional dependencies must be enabled by a feature through dep:name rather than an implicit feature",
+    heading: "optional dependencies without dep: features",
+    explanation: include_str!("explanations/ENDF024.md"),
+};
+
+/// Dependencies must spell the default-features key with a hyphen
+pub const LEGACY_DEFAULT_FEATURES: Rule = Rule {
+    id: "ENDF025",
+    name: "legacy-default-features",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "Dependencies must use default-features rather than the legacy default_features spelling",
+    heading: "dependencies using the legacy default_features key",
+    explanation: include_str!("explanations/ENDF025.md"),
+};
+
+/// Dependencies without default features must not list exactly the default features
+pub const REDUNDANT_DEFAULT_FEATURES: Rule = Rule {
+    id: "ENDF026",
+    name: "redundant-default-features",
+    severity: Severity::Warning,
+    enabled_by_default: false,
+    summary: "Workspace dependencies without default features must not list exactly the crate's default features",
+    heading: "dependencies re-enabling exactly their default features",
+    explanation: include_str!("explanations/ENDF026.md"),
+};
+
+/// Members designated as `no_std` must not enable `std` features on their dependencies
+pub const NO_STD: Rule = Rule {
+    id: "ENDF027",
+    name: "no-std",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Members designated as no_std must not enable features such as std on their dependencies",
+    heading: "dependencies enabling std features in no_std members",
+    explanation: include_str!("explanations/ENDF027.md"),
+};
+
+/// Member dependencies must not require a different version than the workspace entry for the same crate
+pub const VERSION_MISMATCH: Rule = Rule {
+    id: "ENDF028",
+    name: "version-mismatch",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "Member dependencies declared inline must require the same version as [workspace.dependencies]",
+    heading: "member dependencies requiring a different version than the workspace",
+    explanation: include_str!("explanations/ENDF028.md"),
+};
+
+/// Every workspace dependency must be inherited by at least one member
+pub const UNUSED_WORKSPACE_DEPENDENCIES: Rule = Rule {
+    id: "ENDF029",
+    name: "unused-workspace-dependencies",
+    severity: Severity::Warning,
+    enabled_by_default: true,
+    summary: "Every entry of [workspace.dependencies] must be inherited by at least one member",
+    heading: "workspace dependencies no member inherits",
+    explanation: include_str!("explanations/ENDF029.md"),
+};
+
+/// The resolved graph shouldn't contain several incompatible versions of a crate
+pub const DUPLICATE_VERSIONS: Rule = Rule {
+    id: "ENDF030",
+    name: "duplicate-versions",
+    severity: Severity::Warning,
+    enabled_by_default: false,
+    summary: "Crates must not appear at several semver-incompatible versions in Cargo.lock",
+    heading: "crates appearing at several incompatible versions",
+    explanation: include_str!("explanations/ENDF030.md"),
+};
+
+/// Custom rules supplied by the workspace must pass
+pub const CUSTOM_RULES: Rule = Rule {
+    id: "ENDF031",
+    name: "custom-rules",
+    severity: Severity::Error,
+    enabled_by_default: true,
+    summary: "Manifests must pass the custom rules configured through the plugins and scripts settings",
+    heading: "violations of custom rules",
+    explanation: include_str!("explanations/ENDF031.md"),
+};
+
+/// Every rule known to the tool, in ID order
+pub const RULES: &[&Rule] = &[
+    &DEFAULT_FEATURES,
+    &WORKSPACE_BYPASS,
+    &PATH_DEPENDENCIES,
+    &PACKAGED_MANIFESTS,
+    &BUILD_DEPENDENCY_MISMATCH,
+    &INCONSISTENT_ALIASES,
+    &PATH_DEFAULTS,
+    &LEAKED_DEFAULTS,
+    &DEFAULT_IN_FEATURES,
+    &DEFAULT_FEATURE_REFERENCES,
+    &MEMBER_DEFAULT_OVERRIDES,
+    &DECENTRALIZED_DEPENDENCIES,
+    &WORKSPACE_INHERITANCE,
+    &VERSION_PRECISION,
+    &GIT_DEPENDENCIES,
+    &UNVERSIONED_PATH_DEPENDENCIES,
+    &SORTED_DEPENDENCIES,
+    &DUPLICATE_FEATURES,
+    &DENIED_FEATURES,
+    &REQUIRED_FEATURES,
+    &FEATURE_BUDGET,
+    &UMBRELLA_FEATURES,
+    &MEMBER_DEFAULT_FEATURES,
+    &OPTIONAL_DEPENDENCY_GATING,
+    &LEGACY_DEFAULT_FEATURES,
+    &REDUNDANT_DEFAULT_FEATURES,
+    &NO_STD,
+    &VERSION_MISMATCH,
+    &UNUSED_WORKSPACE_DEPENDENCIES,
+    &DUPLICATE_VERSIONS,
+    &CUSTOM_RULES,
+];
+
+/// Rules honoring exceptions and suppression comments, in ID order
+pub const EXCEPTION_RULES: &[&Rule] = &[
+    &DEFAULT_FEATURES,
+    &PATH_DEPENDENCIES,
+    &PACKAGED_MANIFESTS,
+    &DEFAULT_IN_FEATURES,
+    &DEFAULT_FEATURE_REFERENCES,
+    &MEMBER_DEFAULT_OVERRIDES,
+    &DECENTRALIZED_DEPENDENCIES,
+    &OPTIONAL_DEPENDENCY_GATING,
+    &UNUSED_WORKSPACE_DEPENDENCIES,
+    &DUPLICATE_VERSIONS,
+];
+
+/// Looks up a rule by ID (case-insensitively) or by name
+pub fn find_rule(key: &str) -> Result<&'static Rule> {
+    if let Some(rule) = RULES.iter().find(|rule| rule.id.eq_ignore_ascii_case(key) || rule.name == key) {
+        return Ok(rule);
+    }
+
+    if let Some(suggestion) = closest_match(key, RULES.iter().map(|rule| rule.name)) {
+        bail!("Unknown rule '{key}', did you mean '{suggestion}'?");
+    }
+
+    let known: Vec<_> = RULES.iter().map(|rule| format!("{} ({})", rule.id, rule.name)).collect();
+    bail!("Unknown rule '{key}', expected one of: {}", known.join(", "))
+}
+
+/// Parses a rule ID or name given on the command line, returning the rule's ID
+pub fn parse_rule(key: &str) -> Result<String, String> {
+    find_rule(key).map(|rule| rule.id.to_string()).map_err(|err| err.to_string())
+}
+
+/// Rules that only report what the configuration asks for, left alone by the minimal preset
+const CONFIGURED_RULES: [&Rule; 6] = [
+    &VERSION_PRECISION,
+    &DENIED_FEATURES,
+    &REQUIRED_FEATURES,
+    &FEATURE_BUDGET,
+    &NO_STD,
+    &CUSTOM_RULES,
+];
+
+/// Rules that run cargo or read files besides the workspace's manifests, left alone by the strict preset
+const EXTERNAL_RULES: [&Rule; 6] = [
+    &PATH_DEPENDENCIES,
+    &PACKAGED_MANIFESTS,
+    &PATH_DEFAULTS,
+    &LEAKED_DEFAULTS,
+    &REDUNDANT_DEFAULT_FEATURES,
+    &DUPLICATE_VERSIONS,
+];
+
+/// Curated sets of rules and severities, a starting point the configuration and command line build on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// Only check the default features of workspace dependencies, along with the rules the configuration asks for
+    Minimal,
+
+    /// The rules enabled by default, with their default severities
+    Standard,
+
+    /// Every rule checking the workspace's own manifests, reporting all findings as errors
+    Strict,
+}
+
+impl Preset {
+    /// Returns the rule overrides making up the preset
+    pub fn rules(self) -> RuleSet {
+        let mut rules = RuleSet::default();
+        match self {
+            Self::Minimal => {
+                for rule in RULES {
+                    if rule.enabled_by_default
+                        && *rule != &DEFAULT_FEATURES
+                        && *rule != &WORKSPACE_BYPASS
+                        && !CONFIGURED_RULES.contains(rule)
+                    {
+                        rules.disable(rule);
+                    }
+                }
+            }
+            Self::Standard => {}
+            Self::Strict => {
+                for rule in RULES.iter().filter(|rule| !EXTERNAL_RULES.contains(rule)) {
+                    rules.set_severity(rule, Severity::Error);
+                }
+            }
+        }
+
+        rules
+    }
+}
+
+/// Overrides of the rules' default settings
+///
+/// Each rule maps to its severity, or to `None` when it's disabled. Rules without an override keep
+/// their defaults.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    overrides: BTreeMap<&'static str, Option<Severity>>,
+}
+
+impl RuleSet {
+    /// Enables a rule, keeping any severity it was already given
+    pub fn enable(&mut self, rule: &'static Rule) {
+        let severity = self.overrides.get(rule.id).copied().flatten().unwrap_or(rule.severity);
+        let _ = self.overrides.insert(rule.id, Some(severity));
+    }
+
+    /// Disables a rule
+    pub fn disable(&mut self, rule: &'static Rule) {
+        let _ = self.overrides.insert(rule.id, None);
+    }
+
+    /// Enables a rule with the given severity
+    pub fn set_severity(&mut self, rule: &'static Rule, severity: Severity) {
+        let _ = self.overrides.insert(rule.id, Some(severity));
+    }
+
+    /// Applies the overrides of another rule set on top of this one
+    pub fn merge(&mut self, other: &Self) {
+        self.overrides.extend(other.overrides.iter().map(|(id, severity)| (*id, *severity)));
+    }
+
+    /// Returns the severity of a rule's findings, or `None` when the rule is disabled
+    pub fn severity(&self, rule: &Rule) -> Option<Severity> {
+        self.overrides
+            .get(rule.id)
+            .map_or_else(|| rule.enabled_by_default.then_some(rule.severity), |severity| *severity)
+    }
+
+    /// Returns whether a rule runs
+    pub fn is_enabled(&self, rule: &Rule) -> bool {
+        self.severity(rule).is_some()
+    }
+}
+
+/// What a problem found by a check is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subject {
+    /// The manifest as a whole, or several of its entries at once
+    Manifest,
+
+    /// A dependency without an entry of its own in the manifest, such as a package of the resolved graph
+    Package(String),
+
+    /// A dependency entry of the manifest
+    Dependency {
+        /// Keys leading to the dependency table holding the entry, for example `["dependencies"]`
+        table_path: Vec<String>,
+
+        /// Name of the dependency entry
+        name: String,
+    },
+
+    /// An entry of the manifest's `[features]` table
+    Feature {
+        /// Name of the feature
+        name: String,
+
+        /// The dependency whose features the entry enables, when the problem is about one of them
+        dependency: Option<String>,
+    },
+}
+
+/// A problem found by a check, along with what it's about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// What the problem is about
+    pub subject: Subject,
+
+    /// Description of the problem
+    pub message: String,
+
+    /// What's wrong with the entry, for entries that don't disable default features
+    pub problem: Option<DefaultFeaturesProblem>,
+}
+
+impl Violation {
+    /// Creates a violation about the manifest as a whole
+    pub const fn manifest(message: String) -> Self {
+        Self {
+            subject: Subject::Manifest,
+            message,
+            problem: None,
+        }
+    }
+
+    /// Creates a violation about a dependency without an entry of its own in the manifest
+    pub fn package(name: &str, message: String) -> Self {
+        Self {
+            subject: Subject::Package(name.to_string()),
+            message,
+            problem: None,
+        }
+    }
+
+    /// Creates a violation about the entry of a dependency in the table at the given path
+    pub fn dependency(table_path: &[String], name: &str, message: String) -> Self {
+        Self {
+            subject: Subject::Dependency {
+                table_path: table_path.to_vec(),
+                name: name.to_string(),
+            },
+            message,
+            problem: None,
+        }
+    }
+
+    /// Creates a violation about the entry of a dependency that doesn't disable default features
+    pub fn default_features(table_path: &[String], name: &str, problem: DefaultFeaturesProblem) -> Self {
+        Self {
+            problem: Some(problem),
+            ..Self::dependency(table_path, name, problem.message(name))
+        }
+    }
+
+    /// Creates a violation about an entry of `[workspace.dependencies]`
+    pub fn workspace_dependency(name: &str, message: String) -> Self {
+        Self::dependency(&["workspace".to_string(), "dependencies".to_string()], name, message)
+    }
+
+    /// Creates a violation about an entry of the `[features]` table
+    pub fn feature(name: &str, message: String) -> Self {
+        Self {
+            subject: Subject::Feature {
+                name: name.to_string(),
+                dependency: None,
+            },
+            message,
+            problem: None,
+        }
+    }
+
+    /// Creates a violation about what an entry of the `[features]` table enables in one of its dependencies
+    pub fn feature_reference(name: &str, dependency: &str, message: String) -> Self {
+        Self {
+            subject: Subject::Feature {
+                name: name.to_string(),
+                dependency: Some(dependency.to_string()),
+            },
+            message,
+            problem: None,
+        }
+    }
+}
+
+/// Returns the messages of the given violations, for tests comparing them
+#[cfg(test)]
+pub fn messages(violations: &[Violation]) -> Vec<&str> {
+    violations.iter().map(|violation| violation.message.as_str()).collect()
+}
+
+/// A problem reported by a rule
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// The rule that reported the problem
+    pub rule: &'static Rule,
+
+    /// The severity the rule had when the problem was reported
+    pub severity: Severity,
+
+    /// Path to the manifest the problem was found in
+    pub manifest_path: PathBuf,
+
+    /// Description of the problem
+    pub message: String,
+
+    /// The dependency the problem is about, when it's about a single one
+    pub dependency: Option<String>,
+
+    /// The entry of the `[features]` table the problem is about, when it's about one
+    pub feature: Option<String>,
+
+    /// Header of the table holding the entry the problem is about, such as `[dev-dependencies]`
+    pub section: Option<String>,
+
+    /// What's wrong with the entry, for entries that don't disable default features
+    pub problem: Option<DefaultFeaturesProblem>,
+
+    /// Where in the manifest the problem is, when it's about a single entry that could be found
+    pub location: Option<Location>,
+}
+
+impl Finding {
+    /// Creates the finding of a rule for a violation found in the given manifest
+    pub fn new(rule: &'static Rule, severity: Severity, manifest_path: &Path, violation: Violation, location: Option<Location>) -> Self {
+        let (dependency, feature, section) = match violation.subject {
+            Subject::Manifest => (None, None, None),
+            Subject::Package(name) => (Some(name), None, None),
+            Subject::Dependency { table_path, name } => (Some(name), None, Some(section_label(&table_path))),
+            Subject::Feature { name, dependency } => (dependency, Some(name), Some("[features]".to_string())),
+        };
+
+        Self {
+            rule,
+            severity,
+            manifest_path: manifest_path.to_path_buf(),
+            message: violation.message,
+            dependency,
+            feature,
+            section,
+            problem: violation.problem,
+            location,
+        }
+    }
+
+    /// Returns a fingerprint identifying the violation across runs
+    ///
+    /// The fingerprint hashes the rule ID, the manifest path relative to the given root, the table holding
+    /// the entry the finding is about, and the names of the feature and of the dependency it's about, so
+    /// a feature enabling something in two dependencies yields two fingerprints. Line numbers don't affect
+    /// it, and neither does the wording of messages, except for findings that aren't about a single
+    /// dependency or feature, whose whole message is hashed instead of a name. External systems
+    /// can thus track a violation while the manifest around it changes. It's 16 hexadecimal digits of a
+    /// 64-bit FNV-1a hash.
+    pub fn fingerprint(&self, root: &Path) -> String {
+        let manifest_path = std::path::absolute(&self.manifest_path).unwrap_or_else(|_| self.manifest_path.clone());
+        let root = std::path::absolute(root).unwrap_or_else(|_| root.to_path_buf());
+        let relative = manifest_path.strip_prefix(&root).unwrap_or(&manifest_path);
+        let manifest_path = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let section = self.section.as_deref().unwrap_or_default();
+        let names: Vec<_> = self.feature.iter().chain(&self.dependency).map(String::as_str).collect();
+        let subject = if names.is_empty() { self.message.clone() } else { names.join("\0") };
+        hash(&[self.rule.id, &manifest_path, section, &subject].join("\0"))
+    }
+}
+
+/// Returns 16 hexadecimal digits of the 64-bit FNV-1a hash of a text
+pub fn hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+    }
+
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::{entry, finding};
+
+    #[test]
+    fn test_rule_ids_are_unique_and_ordered() {
+        assert!(RULES.is_sorted_by_key(|rule| rule.id));
+        assert!(RULES.iter().all(|rule| rule.explanation.ends_with('\n')));
+
+        let mut names: Vec<_> = RULES.iter().map(|rule| rule.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), RULES.len());
+    }
+
+    #[test]
+    fn test_find_rule() {
+        assert_eq!(find_rule("ENDF001").unwrap(), &DEFAULT_FEATURES);
+        assert_eq!(find_rule("endf002").unwrap(), &WORKSPACE_BYPASS);
+        assert_eq!(find_rule("leaked-defaults").unwrap(), &LEAKED_DEFAULTS);
+
+        let err = find_rule("ENDF999").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Unknown rule 'ENDF999', expected one of: ENDF001 (default-features)")
+        );
+
+        let err = find_rule("leaked-default").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown rule 'leaked-default', did you mean 'leaked-defaults'?");
+    }
+
+    #[test]
+    fn test_presets() {
+        let minimal = Preset::Minimal.rules();
+        assert!(minimal.is_enabled(&DEFAULT_FEATURES));
+        assert!(minimal.is_enabled(&DENIED_FEATURES));
+        assert!(!minimal.is_enabled(&INCONSISTENT_ALIASES));
+        assert!(!minimal.is_enabled(&SORTED_DEPENDENCIES));
+
+        let standard = Preset::Standard.rules();
+        assert!(RULES.iter().all(|rule| standard.is_enabled(rule) == rule.enabled_by_default));
+
+        let strict = Preset::Strict.rules();
+        assert_eq!(strict.severity(&INCONSISTENT_ALIASES), Some(Severity::Error));
+        assert_eq!(strict.severity(&SORTED_DEPENDENCIES), Some(Severity::Error));
+        assert!(!strict.is_enabled(&PACKAGED_MANIFESTS));
+    }
+
+    #[test]
+    fn test_rule_set() {
+        let mut rules = RuleSet::default();
+        assert_eq!(rules.severity(&DEFAULT_FEATURES), Some(Severity::Error));
+        assert_eq!(rules.severity(&INCONSISTENT_ALIASES), Some(Severity::Warning));
+        assert!(!rules.is_enabled(&PATH_DEFAULTS));
+
+        rules.enable(&PATH_DEFAULTS);
+        rules.disable(&INCONSISTENT_ALIASES);
+        rules.set_severity(&DEFAULT_FEATURES, Severity::Warning);
+        assert_eq!(rules.severity(&PATH_DEFAULTS), Some(Severity::Warning));
+        assert!(!rules.is_enabled(&INCONSISTENT_ALIASES));
+        assert_eq!(rules.severity(&DEFAULT_FEATURES), Some(Severity::Warning));
+
+        rules.enable(&DEFAULT_FEATURES);
+        assert_eq!(rules.severity(&DEFAULT_FEATURES), Some(Severity::Warning));
+
+        let mut overrides = RuleSet::default();
+        overrides.enable(&INCONSISTENT_ALIASES);
+        overrides.set_severity(&PATH_DEFAULTS, Severity::Error);
+        rules.merge(&overrides);
+        assert_eq!(rules.severity(&INCONSISTENT_ALIASES), Some(Severity::Warning));
+        assert_eq!(rules.severity(&PATH_DEFAULTS), Some(Severity::Error));
+        assert_eq!(rules.severity(&DEFAULT_FEATURES), Some(Severity::Warning));
+    }
+
+    #[test]
+    fn test_finding_new() {
+        let finding = |violation| Finding::new(&DEFAULT_FEATURES, Severity::Error, Path::new("Cargo.toml"), violation, None);
+
+        let entry = finding(Violation::dependency(
+            &["target".to_string(), "cfg(unix)".to_string(), "dependencies".to_string()],
+            "tokio",
+            "'tokio' in [target.'cfg(unix)'.dependencies]: declares its own version".to_string(),
+        ));
+        assert_eq!(entry.dependency.as_deref(), Some("tokio"));
+        assert_eq!(entry.section.as_deref(), Some("[target.'cfg(unix)'.dependencies]"));
+
+        let package = finding(Violation::package(
+            "serde",
+            "'serde' appears at incompatible versions 0.9.0, 1.0.0".to_string(),
+        ));
+        assert_eq!(package.dependency.as_deref(), Some("serde"));
+        assert_eq!(package.section, None);
+
+        let feature = finding(Violation::feature("full", "feature 'full' enables \"tokio/default\"".to_string()));
+        assert_eq!(feature.dependency, None);
+        assert_eq!(feature.feature.as_deref(), Some("full"));
+        assert_eq!(feature.section.as_deref(), Some("[features]"));
+
+        let reference = finding(Violation::feature_reference(
+            "full",
+            "tokio",
+            "feature 'full' enables \"tokio/default\"".to_string(),
+        ));
+        assert_eq!(reference.dependency.as_deref(), Some("tokio"));
+        assert_eq!(reference.feature.as_deref(), Some("full"));
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(""), "cbf29ce484222325");
+        assert_eq!(hash("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_finding_fingerprint() {
+        let serde = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/member/Cargo.toml",
+            entry("serde", "'serde': missing default-features = false"),
+        );
+        assert_eq!(serde.fingerprint(Path::new("/repo")).len(), 16);
+
+        let reworded = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/member/Cargo.toml",
+            entry("serde", "'serde': has default-features = true (must be false)"),
+        );
+        let moved = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/checkout/member/Cargo.toml",
+            entry("serde", "'serde': missing default-features = false"),
+        );
+        assert_eq!(serde.fingerprint(Path::new("/repo")), reworded.fingerprint(Path::new("/repo")));
+        assert_eq!(serde.fingerprint(Path::new("/repo")), moved.fingerprint(Path::new("/checkout")));
+
+        let tokio = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/member/Cargo.toml",
+            entry("tokio", "'tokio': missing default-features = false"),
+        );
+        let other_member = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/other/Cargo.toml",
+            entry("serde", "'serde': missing default-features = false"),
+        );
+        assert_ne!(serde.fingerprint(Path::new("/repo")), tokio.fingerprint(Path::new("/repo")));
+        assert_ne!(serde.fingerprint(Path::new("/repo")), other_member.fingerprint(Path::new("/repo")));
+
+        let in_dependencies = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/member/Cargo.toml",
+            Violation::dependency(
+                &["dependencies".to_string()],
+                "serde",
+                "'serde' in [dependencies]: declared directly".to_string(),
+            ),
+        );
+        let in_dev_dependencies = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/member/Cargo.toml",
+            Violation::dependency(
+                &["dev-dependencies".to_string()],
+                "serde",
+                "'serde' in [dev-dependencies]: declared directly".to_string(),
+            ),
+        );
+        assert_ne!(
+            in_dependencies.fingerprint(Path::new("/repo")),
+            in_dev_dependencies.fingerprint(Path::new("/repo"))
+        );
+
+        let general = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/Cargo.toml",
+            Violation::manifest("package 'serde' is imported under inconsistent names".to_string()),
+        );
+        let other_general = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/Cargo.toml",
+            Violation::manifest("package 'log' is imported under inconsistent names".to_string()),
+        );
+        assert_ne!(general.fingerprint(Path::new("/repo")), serde.fingerprint(Path::new("/repo")));
+        assert_ne!(
+            general.fingerprint(Path::new("/repo")),
+            other_general.fingerprint(Path::new("/repo"))
+        );
+    }
+
+    #[test]
+    fn test_feature_finding_fingerprint() {
+        let feature = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/member/Cargo.toml",
+            Violation::feature("full", "feature 'full' enables \"tokio/default\"".to_string()),
+        );
+        let reworded_feature = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/member/Cargo.toml",
+            Violation::feature("full", "feature 'full' re-enables the default features of 'tokio'".to_string()),
+        );
+        let other_feature = finding(
+            &DEFAULT_FEATURES,
+            Severity::Error,
+            "/repo/member/Cargo.toml",
+            Violation::feature("cli", "feature 'cli' enables \"tokio/default\"".to_string()),
+        );
+        assert_eq!(
+            feature.fingerprint(Path::new("/repo")),
+            reworded_feature.fingerprint(Path::new("/repo"))
+        );
+        assert_ne!(
+            feature.fingerprint(Path::new("/repo")),
+            other_feature.fingerprint(Path::new("/repo"))
+        );
+
+        // Both references of `full = ["tokio/default", "serde/default"]` must be told apart
+        let reference = |dependency: &str| {
+            finding(
+                &DEFAULT_FEATURE_REFERENCES,
+                Severity::Error,
+                "/repo/member/Cargo.toml",
+                Violation::feature_reference("full", dependency, format!("feature 'full' enables \"{dependency}/default\"")),
+            )
+        };
+        assert_ne!(
+            reference("tokio").fingerprint(Path::new("/repo")),
+            reference("serde").fingerprint(Path::new("/repo"))
+        );
+        assert_ne!(
+            reference("tokio").fingerprint(Path::new("/repo")),
+            feature.fingerprint(Path::new("/repo"))
+        );
+    }
+}
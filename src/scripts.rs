@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::plugins::ManifestModel;
+
+/// Runs the custom rules written as Rhai scripts found in a directory
+///
+/// The directory is relative to the workspace root, and its `.rhai` files are run in name order. Each
+/// script sees the manifest model through its `workspace` and `members` constants, and reports
+/// findings with `report(message)` for the root manifest or `report(manifest_path, message)`.
+/// Messages are prefixed with the script's file name.
+///
+/// # Returns
+///
+/// A vector of findings, each paired with the path to the manifest it's about.
+pub fn run_scripts(dir: &Path, model: &ManifestModel<'_>) -> Result<Vec<(PathBuf, String)>> {
+    let dir = model.workspace.manifest_path.parent().unwrap_or_else(|| Path::new("")).join(dir);
+    let mut scripts = Vec::new();
+    for entry in std::fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry.with_context(|| format!("Failed to read {}", dir.display()))?.path();
+        if path.extension().is_some_and(|extension| extension == "rhai") {
+            scripts.push(path);
+        }
+    }
+
+    scripts.sort();
+
+    let mut findings = Vec::new();
+    for script in scripts {
+        let name = script.file_stem().unwrap_or(script.as_os_str()).to_string_lossy().into_owned();
+        let reported = run_script(&script, model).with_context(|| format!("Failed to run script {}", script.display()))?;
+        findings.extend(reported.into_iter().map(|(path, message)| (path, format!("{name}: {message}"))));
+    }
+
+    Ok(findings)
+}
+
+/// Runs a single script, returning the findings it reported
+#[cfg(feature = "scripting")]
+fn run_script(path: &Path, model: &ManifestModel<'_>) -> Result<Vec<(PathBuf, String)>> {
+    use core::cell::RefCell;
+    use std::rc::Rc;
+
+    use rhai::{Engine, Scope};
+
+    let reported: Rc<RefCell<Vec<(PathBuf, String)>>> = Rc::default();
+    let mut engine = Engine::new();
+
+    let sink = Rc::clone(&reported);
+    let root = model.workspace.manifest_path.to_path_buf();
+    let _ = engine.register_fn("report", move |message: &str| {
+        sink.borrow_mut().push((root.clone(), message.to_string()));
+    });
+
+    let sink = Rc::clone(&reported);
+    let _ = engine.register_fn("report", move |manifest_path: &str, message: &str| {
+        sink.borrow_mut().push((PathBuf::from(manifest_path), message.to_string()));
+    });
+
+    let mut scope = Scope::new();
+    let workspace = rhai::serde::to_dynamic(&model.workspace).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let members = rhai::serde::to_dynamic(&model.members).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let _ = scope.push_constant("workspace", workspace).push_constant("members", members);
+
+    engine
+        .run_file_with_scope(&mut scope, path.to_path_buf())
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    Ok(reported.take())
+}
+
+/// Fails, since running scripts requires the `scripting` feature
+#[cfg(not(feature = "scripting"))]
+fn run_script(_path: &Path, _model: &ManifestModel<'_>) -> Result<Vec<(PathBuf, String)>> {
+    anyhow::bail!("This build doesn't support scripts, reinstall with `--features scripting`")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::members::Member;
+
+    #[test]
+    fn test_run_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::create_dir(dir.path().join("rules")).unwrap();
+        std::fs::write(
+            dir.path().join("rules/no-openssl.rhai"),
+            r#"
+for member in members {
+    let deps = member.manifest.dependencies;
+    if deps != () && "openssl" in deps {
+        report(member.manifest_path, "'openssl' isn't allowed in " + member.name);
+    }
+}
+if "tokio" in workspace.manifest.workspace.dependencies {
+    report("'tokio' isn't allowed");
+}
+"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("rules/README.md"), "Not a script").unwrap();
+
+        let members = [Member {
+            name: "member".to_string(),
+            manifest_path: dir.path().join("member/Cargo.toml"),
+            content: "[dependencies]\nopenssl = { workspace = true }\n".to_string(),
+        }];
+        let model = ManifestModel::new(
+            &manifest_path,
+            "[workspace.dependencies]\ntokio = { version = \"1\", default-features = false }\n",
+            &members,
+        )
+        .unwrap();
+
+        let result = run_scripts(Path::new("rules"), &model);
+
+        #[cfg(feature = "scripting")]
+        assert_eq!(
+            result.unwrap(),
+            [
+                (
+                    members[0].manifest_path.clone(),
+                    "no-openssl: 'openssl' isn't allowed in member".to_string()
+                ),
+                (manifest_path, "no-openssl: 'tokio' isn't allowed".to_string()),
+            ]
+        );
+
+        #[cfg(not(feature = "scripting"))]
+        assert!(format!("{:#}", result.unwrap_err()).contains("--features scripting"));
+    }
+}
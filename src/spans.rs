@@ -0,0 +1,184 @@
+use core::ops::Range;
+
+use serde::Serialize;
+use toml::Spanned;
+use toml::de::{DeString, DeTable, DeValue};
+
+use crate::rules::Subject;
+
+/// A position within a manifest, with 1-based line and column numbers counting characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Location {
+    /// Line number, starting at 1
+    pub line: usize,
+
+    /// Column number, starting at 1
+    pub column: usize,
+}
+
+impl Location {
+    /// Returns the location of a byte offset within the content
+    pub fn at(content: &str, offset: usize) -> Self {
+        let before = content.get(..offset).unwrap_or(content);
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before.get(line_start..).unwrap_or_default().chars().count() + 1,
+        }
+    }
+}
+
+/// Returns the byte range of the key declaring a dependency within the table at the given path
+///
+/// The table path lists the keys leading to the dependency table, for example
+/// `["workspace", "dependencies"]`. Returns `None` when the content can't be parsed or the
+/// dependency isn't declared in that table.
+pub fn dependency_key_span(content: &str, table_path: &[&str], name: &str) -> Option<Range<usize>> {
+    let document = DeTable::parse(content).ok()?;
+    let mut table = document.get_ref();
+    for segment in table_path {
+        table = match find_entry(table, segment)?.1.get_ref() {
+            DeValue::Table(nested) => nested,
+            _ => return None,
+        };
+    }
+
+    find_entry(table, name).map(|(key, _)| key.span())
+}
+
+/// Locations of the keys of a manifest, indexed once so every finding in it can be located
+#[derive(Debug, Default)]
+pub struct KeyLocations {
+    /// Keys leading to each entry, along with the location of the entry's key, in document order
+    keys: Vec<(Vec<String>, Location)>,
+}
+
+impl KeyLocations {
+    /// Indexes the keys of a manifest, leaving the index empty when the content can't be parsed
+    pub fn new(content: &str) -> Self {
+        let mut keys = Vec::new();
+        if let Ok(document) = DeTable::parse(content) {
+            index_keys(content, document.get_ref(), &mut Vec::new(), &mut keys);
+        }
+
+        keys.sort_by_key(|(_, location)| (location.line, location.column));
+        Self { keys }
+    }
+
+    /// Returns the location of what a violation is about
+    ///
+    /// Dependency entries and features point at their key. A dependency without an entry of its own
+    /// points at its entry in `[workspace.dependencies]`, or else in the first dependency table
+    /// declaring it. Returns `None` for violations about the manifest as a whole.
+    pub fn locate(&self, subject: &Subject) -> Option<Location> {
+        match subject {
+            Subject::Manifest => None,
+            Subject::Dependency { table_path, name } => self.find(|path| {
+                path.split_last()
+                    .is_some_and(|(key, parent)| key == name && parent == table_path.as_slice())
+            }),
+            Subject::Feature { name, .. } => self.find(|path| path == ["features", name.as_str()]),
+            Subject::Package(name) => self
+                .find(|path| path == ["workspace", "dependencies", name.as_str()])
+                .or_else(|| self.find(|path| is_dependency_key(path, name))),
+        }
+    }
+
+    /// Returns the location of the first key whose path matches
+    fn find(&self, matches: impl Fn(&[String]) -> bool) -> Option<Location> {
+        self.keys.iter().find(|(path, _)| matches(path)).map(|(_, location)| *location)
+    }
+}
+
+/// Returns whether the keys lead to a dependency of the given name in one of a package's dependency tables
+fn is_dependency_key(path: &[String], name: &str) -> bool {
+    let (section, key) = match path {
+        [section, key] => (section, key),
+        [target, _, section, key] if target == "target" => (section, key),
+        _ => return false,
+    };
+
+    key == name && ["dependencies", "build-dependencies", "dev-dependencies"].contains(&section.as_str())
+}
+
+/// Records the location of every key of a table and of the tables nested in it
+fn index_keys(content: &str, table: &DeTable<'_>, path: &mut Vec<String>, keys: &mut Vec<(Vec<String>, Location)>) {
+    for (key, value) in table {
+        path.push(key.get_ref().to_string());
+        keys.push((path.clone(), Location::at(content, key.span().start)));
+        if let DeValue::Table(nested) = value.get_ref() {
+            index_keys(content, nested, path, keys);
+        }
+
+        let _ = path.pop();
+    }
+}
+
+/// Looks up an entry by key in a spanned table
+fn find_entry<'a, 'i>(table: &'a DeTable<'i>, name: &str) -> Option<(&'a Spanned<DeString<'i>>, &'a Spanned<DeValue<'i>>)> {
+    table.iter().find(|(key, _)| key.get_ref() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_key_span() {
+        let content = r#"[workspace.dependencies]
+serde = "1.0"
+tokio = { version = "1.0" }
+
+[workspace.dependencies.regex]
+version = "1.0"
+"#;
+
+        let span = dependency_key_span(content, &["workspace", "dependencies"], "tokio").unwrap();
+        assert_eq!(content.get(span), Some("tokio"));
+
+        let span = dependency_key_span(content, &["workspace", "dependencies"], "regex").unwrap();
+        assert_eq!(content.get(span), Some("regex"));
+
+        assert!(dependency_key_span(content, &["workspace", "dependencies"], "missing").is_none());
+        assert!(dependency_key_span(content, &["dependencies"], "serde").is_none());
+        assert!(dependency_key_span("[invalid", &["dependencies"], "serde").is_none());
+    }
+
+    #[test]
+    fn test_key_locations() {
+        let content = "[workspace.dependencies]\nserde = \"1.0\"\n\n[dependencies]\ntokio = \"1\"\n\n[dev-dependencies]\n  tokio = \"1\"\nlog = \"0.4\"\n\n[features]\nfull = [\"tokio/default\"]\n";
+        let locations = KeyLocations::new(content);
+        let dependency = |section: &str, name: &str| Subject::Dependency {
+            table_path: vec![section.to_string()],
+            name: name.to_string(),
+        };
+
+        assert_eq!(
+            locations.locate(&dependency("dependencies", "tokio")),
+            Some(Location { line: 5, column: 1 })
+        );
+        assert_eq!(
+            locations.locate(&dependency("dev-dependencies", "tokio")),
+            Some(Location { line: 8, column: 3 })
+        );
+        assert_eq!(locations.locate(&dependency("dependencies", "log")), None);
+        assert_eq!(
+            locations.locate(&Subject::Package("serde".to_string())),
+            Some(Location { line: 2, column: 1 })
+        );
+        assert_eq!(
+            locations.locate(&Subject::Package("log".to_string())),
+            Some(Location { line: 9, column: 1 })
+        );
+        assert_eq!(
+            locations.locate(&Subject::Feature {
+                name: "full".to_string(),
+                dependency: None,
+            }),
+            Some(Location { line: 12, column: 1 })
+        );
+        assert_eq!(locations.locate(&Subject::Manifest), None);
+        assert_eq!(KeyLocations::new("[invalid").locate(&Subject::Package("serde".to_string())), None);
+        assert_eq!(Location::at("é = 1\nab", 9), Location { line: 2, column: 3 });
+    }
+}
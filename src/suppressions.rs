@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, bail};
+use toml_edit::{DocumentMut, Item};
+
+use crate::rules::{EXCEPTION_RULES, Rule, find_rule};
+use crate::validation::member_dependency_table_paths;
+
+/// Marker starting a suppression comment, as in `# endf: allow(default-features) reason="needs std"`
+const MARKER: &str = "endf:";
+
+/// Returns the dependencies whose findings are suppressed by a comment, keyed by rule ID
+///
+/// A suppression comment sits on a line preceding a dependency entry in one of the manifest's
+/// dependency tables, `[workspace.dependencies]` included, and names the rules it allows by ID or
+/// name: `# endf: allow(ENDF001, decentralized-dependencies) reason="needs std"`. Whatever follows
+/// the closing parenthesis, such as the reason, is left for reviewers. Naming a rule that doesn't
+/// honor exceptions is an error, since the comment would have no effect. Manifests that can't be
+/// parsed have no suppressions, leaving it to the checks to report the problem.
+pub fn find_suppressions(content: &str) -> Result<BTreeMap<&'static str, Vec<String>>> {
+    let (Ok(document), Ok(parsed)) = (content.parse::<DocumentMut>(), toml::from_str::<toml::Value>(content)) else {
+        return Ok(BTreeMap::new());
+    };
+
+    let mut paths = vec![vec!["workspace".to_string(), "dependencies".to_string()]];
+    paths.extend(member_dependency_table_paths(&parsed).into_iter().map(|(path, _)| path));
+
+    let mut suppressions: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+    for path in paths {
+        let Some(table) = path
+            .iter()
+            .try_fold(document.as_item(), |item, key| item.get(key))
+            .and_then(Item::as_table_like)
+        else {
+            continue;
+        };
+
+        for (name, item) in table.iter() {
+            let key_prefix = table
+                .key(name)
+                .and_then(|key| key.leaf_decor().prefix())
+                .and_then(|prefix| prefix.as_str());
+            let table_prefix = item
+                .as_table()
+                .and_then(|table| table.decor().prefix())
+                .and_then(|prefix| prefix.as_str());
+            for comment in key_prefix.into_iter().chain(table_prefix).flat_map(str::lines) {
+                for key in allowed_rules(comment) {
+                    let rule = suppressible_rule(key).with_context(|| format!("Invalid suppression comment for '{name}'"))?;
+                    suppressions.entry(rule.id).or_default().push(name.to_string());
+                }
+            }
+        }
+    }
+
+    Ok(suppressions)
+}
+
+/// Looks up a rule named by a suppression comment, which must be one of the rules honoring exceptions
+fn suppressible_rule(key: &str) -> Result<&'static Rule> {
+    let rule = find_rule(key)?;
+    if !EXCEPTION_RULES.iter().any(|candidate| candidate.id == rule.id) {
+        let known: Vec<_> = EXCEPTION_RULES.iter().map(|rule| format!("{} ({})", rule.id, rule.name)).collect();
+        bail!("Rule '{key}' doesn't honor suppressions, expected one of: {}", known.join(", "));
+    }
+
+    Ok(rule)
+}
+
+/// Returns the rules a comment line allows, empty when it isn't a suppression comment
+fn allowed_rules(line: &str) -> Vec<&str> {
+    line.trim()
+        .strip_prefix('#')
+        .and_then(|comment| comment.trim_start().strip_prefix(MARKER))
+        .and_then(|directive| directive.trim_start().strip_prefix("allow("))
+        .and_then(|directive| directive.split_once(')'))
+        .map(|(rules, _)| rules.split(',').map(str::trim).filter(|rule| !rule.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_suppressions() {
+        let content = r#"
+[workspace.dependencies]
+# endf: allow(default-features) reason="needs std"
+serde = "1.0"
+# A regular comment
+tokio = "1.0"
+
+[dependencies]
+#endf:allow(ENDF012, default-in-features)
+clap = "4"
+
+# endf: allow(ENDF001)
+[dependencies.regex]
+version = "1"
+"#;
+
+        let suppressions = find_suppressions(content).unwrap();
+        assert_eq!(suppressions["ENDF001"], ["serde", "regex"]);
+        assert_eq!(suppressions["ENDF012"], ["clap"]);
+        assert_eq!(suppressions["ENDF009"], ["clap"]);
+        assert_eq!(suppressions.len(), 3);
+
+        let err = find_suppressions("[dependencies]\n# endf: allow(default-feature)\nserde = \"1.0\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains("Unknown rule 'default-feature', did you mean 'default-features'?"));
+
+        let err = find_suppressions("[dependencies]\n# endf: allow(ENDF013)\nserde = \"1.0\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains("Rule 'ENDF013' doesn't honor suppressions, expected one of: ENDF001 (default-features),"));
+
+        assert!(find_suppressions("[invalid").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_allowed_rules() {
+        assert_eq!(allowed_rules("# endf: allow(ENDF001) reason=\"x\""), ["ENDF001"]);
+        assert_eq!(allowed_rules("  #endf:allow( ENDF001 , ENDF002 )"), ["ENDF001", "ENDF002"]);
+        assert!(allowed_rules("# endf: deny(ENDF001)").is_empty());
+        assert!(allowed_rules("# allow(ENDF001)").is_empty());
+        assert!(allowed_rules("# endf: allow(ENDF001").is_empty());
+    }
+}
@@ -1,62 +1,1076 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use anyhow::{Context, Result};
+use glob::Pattern;
+use regex::Regex;
+use semver::VersionReq;
+use serde::Deserialize;
+
+use crate::members::Member;
+use crate::rules::Violation;
+use crate::versions::{lowest_version, version_requirement};
+
+/// Returns whether a dependency is covered by any of the exceptions not limited to a dependency kind
+pub fn is_excepted(exceptions: &[ExceptionPattern], name: &str) -> bool {
+    exceptions
+        .iter()
+        .any(|exception| exception.scope.is_none() && exception.matches(name))
+}
+
+/// Returns whether a dependency entry is covered by any of the exceptions
+///
+/// Renamed dependencies are covered by exceptions matching either their key or the real name of the
+/// package given by their `package` field.
+pub fn is_dependency_excepted(exceptions: &[ExceptionPattern], name: &str, value: &toml::Value) -> bool {
+    is_excepted(exceptions, name) || is_excepted(exceptions, dependency_package_name(name, value))
+}
+
+/// Returns whether an entry of a member's dependency table is covered by any of the exceptions
+///
+/// On top of the exceptions covering every table, exceptions limited to a dependency kind, such as
+/// `dev:criterion`, cover the entries of the tables of that kind.
+pub fn is_member_dependency_excepted(exceptions: &[ExceptionPattern], section: &str, name: &str, value: &toml::Value) -> bool {
+    let kind = DependencyKind::of_section(section);
+    exceptions.iter().any(|exception| {
+        (exception.scope.is_none() || exception.scope == kind)
+            && (exception.matches(name) || exception.matches(dependency_package_name(name, value)))
+    })
+}
 
-/// Validates a single dependency entry and returns an error message if invalid.
-fn validate_dependency(name: &str, value: &toml::Value) -> Result<(), String> {
+/// The kind of a dependency, given by the table declaring it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    /// Declared in `[dependencies]`
+    Normal,
+
+    /// Declared in `[dev-dependencies]`
+    Dev,
+
+    /// Declared in `[build-dependencies]`
+    Build,
+}
+
+impl DependencyKind {
+    const ALL: [Self; 3] = [Self::Normal, Self::Dev, Self::Build];
+
+    /// Returns the name of the kind, as written in configuration files
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Dev => "dev",
+            Self::Build => "build",
+        }
+    }
+
+    /// Limits an exception to dependencies of this kind
+    pub fn scope(self, exception: &str) -> String {
+        format!("{}{KIND_SEPARATOR}{exception}", self.as_str())
+    }
+
+    /// Returns the kind of the dependencies of a member's table, given its label such as `[dev-dependencies]`
+    fn of_section(section: &str) -> Option<Self> {
+        if section.starts_with("[workspace.") {
+            return None;
+        }
+
+        match section.strip_suffix(']')?.rsplit(['.', '[']).next()? {
+            "dependencies" => Some(Self::Normal),
+            "dev-dependencies" => Some(Self::Dev),
+            "build-dependencies" => Some(Self::Build),
+            _ => None,
+        }
+    }
+}
+
+/// Separates the dependency kind limiting an exception from its pattern, as in `dev:criterion`
+const KIND_SEPARATOR: char = ':';
+
+/// Splits an exception into the dependency kind it's limited to, if any, and its pattern
+fn split_kind_scope(exception: &str) -> (Option<DependencyKind>, &str) {
+    DependencyKind::ALL
+        .into_iter()
+        .find_map(|kind| {
+            exception
+                .strip_prefix(kind.as_str())
+                .and_then(|rest| rest.strip_prefix(KIND_SEPARATOR))
+                .map(|pattern| (Some(kind), pattern))
+        })
+        .unwrap_or((None, exception))
+}
+
+/// Prefix of the exceptions holding a regular expression
+const REGEX_PREFIX: &str = "regex:";
+
+/// An exception whose pattern is compiled once, so that matching dependencies against it is cheap
+///
+/// An exception is either a dependency name, a glob pattern such as `windows-*` covering a whole
+/// family of crates, or a regular expression prefixed with `regex:`, such as `regex:^winapi(-.*)?$`.
+/// It can be limited to a dependency kind, as in `dev:criterion`. Invalid patterns cover nothing, see
+/// `validate_exceptions`.
+#[derive(Debug, Clone)]
+pub struct ExceptionPattern {
+    /// The exception as given
+    text: String,
+
+    /// The dependency kind the exception is limited to
+    scope: Option<DependencyKind>,
+
+    /// The exception's pattern, without its kind
+    pattern: String,
+
+    matcher: Matcher,
+}
+
+/// How an exception's pattern is matched against dependency names
+#[derive(Debug, Clone)]
+enum Matcher {
+    Glob(Pattern),
+    Regex(Regex),
+    Invalid,
+}
+
+impl ExceptionPattern {
+    /// Compiles an exception
+    pub fn new(exception: &str) -> Self {
+        let (scope, pattern) = split_kind_scope(exception);
+        let matcher = pattern.strip_prefix(REGEX_PREFIX).map_or_else(
+            || Pattern::new(pattern).map_or(Matcher::Invalid, Matcher::Glob),
+            |regex| Regex::new(regex).map_or(Matcher::Invalid, Matcher::Regex),
+        );
+
+        Self {
+            text: exception.to_string(),
+            scope,
+            pattern: pattern.to_string(),
+            matcher,
+        }
+    }
+
+    /// Returns the exception as given
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns whether the exception's pattern covers a dependency, regardless of its kind
+    pub fn matches(&self, name: &str) -> bool {
+        match &self.matcher {
+            Matcher::Regex(regex) => regex.is_match(name),
+            Matcher::Glob(pattern) => self.pattern == name || pattern.matches(name),
+            Matcher::Invalid => self.pattern == name,
+        }
+    }
+}
+
+/// Compiles the given exceptions
+pub fn compile_exceptions(exceptions: &[String]) -> Vec<ExceptionPattern> {
+    exceptions.iter().map(|exception| ExceptionPattern::new(exception)).collect()
+}
+
+/// Trims the exceptions given on the command line, dropping empty and duplicate entries
+///
+/// This keeps lists built from shell variables or CI pipelines, such as `-e "serde, tokio,"`, working.
+pub fn normalize_exceptions(exceptions: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for exception in exceptions {
+        let exception = exception.trim();
+        if !exception.is_empty() && !normalized.iter().any(|existing| existing == exception) {
+            normalized.push(exception.to_string());
+        }
+    }
+
+    normalized
+}
+
+/// Fails when one of the exceptions is an invalid glob pattern, regular expression, or version requirement
+pub fn validate_exceptions(exceptions: &[String]) -> Result<()> {
+    for exception in exceptions {
+        let (pattern, constraint) = split_version_constraint(exception);
+        if let Some(regex) = pattern.strip_prefix(REGEX_PREFIX) {
+            let _ = Regex::new(regex).with_context(|| format!("Invalid regular expression in exception '{exception}'"))?;
+        } else {
+            let _ = Pattern::new(pattern).with_context(|| format!("Invalid glob pattern in exception '{exception}'"))?;
+        }
+
+        if let Some(constraint) = constraint {
+            let _ = VersionReq::parse(constraint).with_context(|| format!("Invalid version requirement in exception '{exception}'"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits an exception into its pattern and the version requirement constraining it, such as `serde@^1.0`
+///
+/// Regular expressions are never constrained, since `@` is a valid character in them.
+pub fn split_version_constraint(exception: &str) -> (&str, Option<&str>) {
+    if exception.starts_with(REGEX_PREFIX) {
+        return (exception, None);
+    }
+
+    exception
+        .split_once('@')
+        .map_or((exception, None), |(pattern, constraint)| (pattern, Some(constraint)))
+}
+
+/// Resolves the exceptions constrained to a version requirement against `[workspace.dependencies]`
+///
+/// A constrained exception such as `serde@^1.0` turns into the names of the workspace dependencies it
+/// covers whose version requirement satisfies the constraint, judged by the lowest version the
+/// requirement allows. Once a dependency is bumped past the constraint, the exception expires and the
+/// dependency is checked again. Unconstrained exceptions are kept as they are, while constrained ones
+/// never match until resolved.
+pub fn resolve_versioned_exceptions(exceptions: &[String], content: &str) -> Vec<String> {
+    let parsed = toml::from_str::<toml::Value>(content).ok();
+    let deps_table = parsed
+        .as_ref()
+        .and_then(|parsed| parsed.get("workspace"))
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    let mut resolved = Vec::new();
+    for exception in exceptions {
+        let (scope, unscoped) = split_kind_scope(exception);
+        let (pattern, Some(constraint)) = split_version_constraint(unscoped) else {
+            resolved.push(exception.clone());
+            continue;
+        };
+
+        let Ok(constraint) = VersionReq::parse(constraint) else {
+            continue;
+        };
+
+        let pattern = ExceptionPattern::new(pattern);
+
+        for (name, value) in deps_table.into_iter().flatten() {
+            let satisfied = version_requirement(value)
+                .and_then(lowest_version)
+                .is_some_and(|version| constraint.matches(&version));
+            let package = dependency_package_name(name, value);
+            if satisfied && (pattern.matches(name) || pattern.matches(package)) {
+                resolved.push(scope.map_or_else(|| name.clone(), |kind| kind.scope(name)));
+            }
+        }
+    }
+
+    resolved
+}
+
+/// What's wrong with a dependency entry that doesn't disable default features
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultFeaturesProblem {
+    /// The entry is a version string, such as `serde = "1.0"`
+    SimpleVersion,
+
+    /// The entry is neither a version string nor a table
+    NotATable,
+
+    /// The entry sets `default-features = true`
+    Enabled,
+
+    /// The entry doesn't set `default-features`
+    Missing,
+
+    /// The entry sets `default-features` to something other than a boolean
+    UnexpectedValue,
+}
+
+impl DefaultFeaturesProblem {
+    /// Describes the problem with the entry of the given dependency
+    pub fn message(self, name: &str) -> String {
+        match self {
+            Self::SimpleVersion => format!("'{name}': uses simple version string, should be a table with default-features = false"),
+            Self::NotATable => format!("'{name}': dependency is not a table"),
+            Self::Enabled => format!("'{name}': has default-features = true (must be false)"),
+            Self::Missing => format!("'{name}': missing default-features = false"),
+            Self::UnexpectedValue => format!("'{name}': default-features has unexpected value (must be boolean false)"),
+        }
+    }
+}
+
+/// Validates a single dependency entry and returns what's wrong with it if invalid.
+pub fn validate_dependency(value: &toml::Value) -> Result<(), DefaultFeaturesProblem> {
     if value.is_str() {
-        return Err(format!(
-            "  - '{name}': uses simple version string, should be a table with default-features = false",
+        return Err(DefaultFeaturesProblem::SimpleVersion);
+    }
+
+    if !value.is_table() {
+        return Err(DefaultFeaturesProblem::NotATable);
+    }
+
+    match default_features_setting(value) {
+        Some(toml::Value::Boolean(false)) => Ok(()),
+
+        Some(toml::Value::Boolean(true)) => Err(DefaultFeaturesProblem::Enabled),
+
+        None => Err(DefaultFeaturesProblem::Missing),
+
+        Some(_) => Err(DefaultFeaturesProblem::UnexpectedValue),
+    }
+}
+
+/// Validates all workspace dependencies in the given Cargo.toml content
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * A vector of violations for invalid dependencies
+/// * A vector of all dependency names found in [workspace.dependencies], along with the packages they rename
+pub fn validate_workspace_dependencies(content: &str, exceptions: &[ExceptionPattern]) -> Result<(Vec<Violation>, Vec<String>)> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let workspace = parsed.get("workspace").context("No [workspace] section found")?;
+    let dependencies = workspace.get("dependencies").context("No [workspace.dependencies] section found")?;
+    let deps_table = dependencies.as_table().context("[workspace.dependencies] is not a table")?;
+
+    let mut errors = Vec::new();
+    let mut found_deps = Vec::new();
+    for (name, value) in deps_table {
+        found_deps.push(name.clone());
+        let package = dependency_package_name(name, value);
+        if package != name {
+            found_deps.push(package.to_string());
+        }
+
+        if is_dependency_excepted(exceptions, name, value) {
+            continue;
+        }
+
+        if let Err(problem) = validate_dependency(value) {
+            errors.push(Violation::default_features(
+                &["workspace".to_string(), "dependencies".to_string()],
+                name,
+                problem,
+            ));
+        }
+    }
+
+    Ok((errors, found_deps))
+}
+
+/// Returns the names of the workspace dependencies that can be fixed by adding `default-features = false`
+///
+/// Entries that are neither version strings nor tables need a human to look at them, so they're left out.
+pub fn fixable_workspace_dependencies(content: &str, exceptions: &[ExceptionPattern]) -> Result<Vec<String>> {
+    failing_workspace_dependencies(content, exceptions, true)
+}
+
+/// Returns the exceptions no longer needed, since every dependency they cover already passes the default-features check
+///
+/// Exceptions not matching any dependency of `[workspace.dependencies]` are left out.
+pub fn find_obsolete_exceptions(content: &str, exceptions: &[ExceptionPattern]) -> Vec<String> {
+    let parsed = toml::from_str::<toml::Value>(content).ok();
+    let deps_table = parsed
+        .as_ref()
+        .and_then(|parsed| parsed.get("workspace"))
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    let mut obsolete = Vec::new();
+    for exception in exceptions {
+        let mut covered = deps_table
+            .into_iter()
+            .flatten()
+            .filter(|(name, value)| exception.matches(name) || exception.matches(dependency_package_name(name, value)))
+            .peekable();
+        if covered.peek().is_some()
+            && covered.all(|(_, value)| validate_dependency(value).is_ok())
+            && !obsolete.iter().any(|obsolete| obsolete == exception.as_str())
+        {
+            obsolete.push(exception.as_str().to_string());
+        }
+    }
+
+    obsolete
+}
+
+/// Returns the names of the workspace dependencies failing the default-features check
+///
+/// When `fixable_only` is set, entries that are neither version strings nor tables are left out.
+pub fn failing_workspace_dependencies(content: &str, exceptions: &[ExceptionPattern], fixable_only: bool) -> Result<Vec<String>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let deps_table = parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    Ok(deps_table
+        .into_iter()
+        .flatten()
+        .filter(|(name, value)| {
+            !is_dependency_excepted(exceptions, name, value)
+                && (!fixable_only || value.is_str() || value.is_table())
+                && validate_dependency(value).is_err()
+        })
+        .map(|(name, _)| name.clone())
+        .collect())
+}
+
+/// Finds git dependencies in `[workspace.dependencies]` that aren't allowed
+///
+/// A git dependency is allowed when the crate is listed in `allowed_crates`, or when the host of its
+/// repository URL is listed in `allowed_hosts`. Both `https://host/...` URLs and scp-like
+/// `git@host:...` addresses are understood.
+pub fn find_git_dependencies(content: &str, allowed_hosts: &[String], allowed_crates: &[String]) -> Result<Vec<Violation>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let deps_table = parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    let mut forbidden = Vec::new();
+    for (name, value) in deps_table.into_iter().flatten() {
+        let Some(url) = value.get("git").and_then(toml::Value::as_str) else {
+            continue;
+        };
+
+        if allowed_crates.contains(name) || allowed_hosts.iter().any(|host| host.eq_ignore_ascii_case(git_host(url))) {
+            continue;
+        }
+
+        forbidden.push(Violation::workspace_dependency(
+            name,
+            format!("'{name}': uses the git repository {url}, git dependencies are not allowed"),
         ));
     }
 
-    let Some(dep_table) = value.as_table() else {
-        return Err(format!("  - '{name}': dependency is not a table"));
+    Ok(forbidden)
+}
+
+/// Extracts the host from a git repository URL
+fn git_host(url: &str) -> &str {
+    let (rest, scp_like) = url.split_once("://").map_or((url, true), |(_, rest)| (rest, false));
+    let rest = rest.split_once('@').map_or(rest, |(_, host)| host);
+    let end = if scp_like {
+        [':', '/'].as_slice()
+    } else {
+        [':', '/', '?', '#'].as_slice()
+    };
+    rest.split(end).next().unwrap_or_default()
+}
+
+/// Finds entries of `[workspace.dependencies]` that break alphabetical order
+///
+/// Entries are compared in the order they appear in the document, and each out-of-order entry is
+/// reported along with the first entry it should precede. Dependencies declared in their own
+/// `[workspace.dependencies.<name>]` tables aren't considered.
+pub fn find_unsorted_dependencies(content: &str) -> Result<Vec<Violation>> {
+    let document: toml_edit::DocumentMut = content.parse().context("Failed to parse Cargo.toml")?;
+    let Some(deps_table) = document
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml_edit::Item::as_table_like)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut seen: Vec<&str> = Vec::new();
+    let mut unsorted = Vec::new();
+    for (name, _) in deps_table.iter().filter(|(_, item)| item.is_value()) {
+        if let Some(successor) = seen.iter().filter(|seen| **seen > name).min() {
+            unsorted.push(Violation::workspace_dependency(
+                name,
+                format!("'{name}' is out of alphabetical order, it should come before '{successor}'"),
+            ));
+        }
+
+        seen.push(name);
+    }
+
+    Ok(unsorted)
+}
+
+/// Validates the dependencies of a package manifest reached by following a path dependency
+///
+/// Only `[dependencies]` and `[build-dependencies]` are checked since dev-dependencies never reach
+/// consumers of the crate. Entries inherited with `workspace = true` are skipped, as they are
+/// governed by the `[workspace.dependencies]` table of the workspace that owns the crate.
+pub fn validate_package_dependencies(content: &str, exceptions: &[ExceptionPattern]) -> Result<Vec<Violation>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+
+    let mut errors = Vec::new();
+    for section in ["dependencies", "build-dependencies"] {
+        let Some(deps_table) = parsed.get(section).and_then(toml::Value::as_table) else {
+            continue;
+        };
+
+        for (name, value) in deps_table {
+            if is_dependency_excepted(exceptions, name, value) || is_workspace_inherited(value) {
+                continue;
+            }
+
+            if let Err(problem) = validate_dependency(value) {
+                errors.push(Violation::default_features(&[section.to_string()], name, problem));
+            }
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Returns the name and `path` value of every path dependency declared in the given manifest
+///
+/// Both `[workspace.dependencies]` and the package's `[dependencies]` and `[build-dependencies]`
+/// tables are searched.
+pub fn path_dependencies(content: &str) -> Result<Vec<(String, String)>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+
+    let tables = [
+        parsed.get("workspace").and_then(|w| w.get("dependencies")),
+        parsed.get("dependencies"),
+        parsed.get("build-dependencies"),
+    ];
+
+    let mut paths = Vec::new();
+    for deps_table in tables.into_iter().flatten().filter_map(toml::Value::as_table) {
+        for (name, value) in deps_table {
+            if let Some(path) = value.get("path").and_then(toml::Value::as_str) {
+                paths.push((name.clone(), path.to_string()));
+            }
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Finds dependency entries that list the `default` feature in their `features` array
+///
+/// Requesting the `default` feature re-enables everything `default-features = false` disabled, so such
+/// entries defeat the policy while looking compliant. Both `[workspace.dependencies]` and the package's
+/// dependency tables are searched.
+pub fn find_default_feature_requests(content: &str, exceptions: &[ExceptionPattern]) -> Result<Vec<Violation>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+
+    let mut tables = dependency_tables(&parsed, true);
+    tables.extend(member_dependency_table_paths(&parsed));
+
+    let mut requests = Vec::new();
+    for (table_path, deps_table) in tables {
+        let section = section_label(&table_path);
+        for (name, value) in deps_table {
+            if is_member_dependency_excepted(exceptions, &section, name, value) {
+                continue;
+            }
+
+            let features = value.get("features").and_then(toml::Value::as_array).into_iter().flatten();
+            if features.filter_map(toml::Value::as_str).any(|feature| feature == "default") {
+                requests.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!("'{name}' in {section}: lists \"default\" in features, which re-enables the default features"),
+                ));
+            }
+        }
+    }
+
+    Ok(requests)
+}
+
+/// Finds features of a member that enable the `default` feature of one of its dependencies
+///
+/// Entries such as `full = ["tokio/default"]` or `std = ["serde?/default"]` in the member's
+/// `[features]` table turn the dependency's default features back on whenever the feature is enabled.
+pub fn find_default_feature_references(member_content: &str, exceptions: &[ExceptionPattern]) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+    let Some(features) = member.get("features").and_then(toml::Value::as_table) else {
+        return Ok(Vec::new());
+    };
+
+    let mut references = Vec::new();
+    for (feature, enables) in features {
+        for entry in enables.as_array().into_iter().flatten().filter_map(toml::Value::as_str) {
+            let Some(dependency) = entry.strip_suffix("/default") else {
+                continue;
+            };
+
+            let dependency = dependency.strip_suffix('?').unwrap_or(dependency);
+            if !is_excepted(exceptions, dependency) {
+                references.push(Violation::feature_reference(
+                    feature,
+                    dependency,
+                    format!("feature '{feature}' enables \"{entry}\", which re-enables the default features of '{dependency}'"),
+                ));
+            }
+        }
+    }
+
+    Ok(references)
+}
+
+/// Finds member entries inheriting a workspace dependency that turn its default features back on
+///
+/// Cargo merges `default-features = true` from the member on top of the workspace entry, so
+/// `serde = { workspace = true, default-features = true }` quietly overrides the workspace's
+/// `default-features = false`.
+pub fn find_default_feature_overrides(member_content: &str, exceptions: &[ExceptionPattern]) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+
+    let mut overrides = Vec::new();
+    for (table_path, deps_table) in member_dependency_table_paths(&member) {
+        let section = section_label(&table_path);
+        for (name, value) in deps_table {
+            if is_workspace_inherited(value)
+                && matches!(default_features_setting(value), Some(toml::Value::Boolean(true)))
+                && !is_member_dependency_excepted(exceptions, &section, name, value)
+            {
+                overrides.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!(
+                        "'{name}' in {section}: sets default-features = true, overriding the default-features setting inherited from [workspace.dependencies]"
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(overrides)
+}
+
+/// Finds member dependencies declared with their own `version`, `git`, or `path` instead of being
+/// inherited from `[workspace.dependencies]`
+///
+/// Decentralized declarations are where the default-features discipline usually breaks down, since
+/// each one has to get the setting right on its own.
+pub fn find_decentralized_dependencies(member_content: &str, exceptions: &[ExceptionPattern]) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+
+    let mut decentralized = Vec::new();
+    for (table_path, deps_table) in member_dependency_table_paths(&member) {
+        let section = section_label(&table_path);
+        for (name, value) in deps_table {
+            if is_workspace_inherited(value) || is_member_dependency_excepted(exceptions, &section, name, value) {
+                continue;
+            }
+
+            let keys: Vec<_> = if value.is_str() {
+                vec!["version"]
+            } else {
+                ["version", "git", "path"]
+                    .into_iter()
+                    .filter(|key| value.get(key).is_some())
+                    .collect()
+            };
+
+            if !keys.is_empty() {
+                decentralized.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!(
+                        "'{name}' in {section}: declares its own {} instead of inheriting from [workspace.dependencies]",
+                        keys.join(" and ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(decentralized)
+}
+
+/// Finds dependency entries spelling the default-features key the legacy way, as `default_features`
+///
+/// The tables checked are chosen the same way as for `dependency_tables`.
+pub fn find_legacy_default_features(content: &str, workspace_table: bool) -> Result<Vec<DependencyProblem>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+
+    let mut problems = Vec::new();
+    for (table_path, deps_table) in dependency_tables(&parsed, workspace_table) {
+        for (name, value) in deps_table {
+            if value.get("default_features").is_some() {
+                problems.push(DependencyProblem {
+                    message: format!(
+                        "'{name}' in {}: uses the legacy default_features key instead of default-features",
+                        section_label(&table_path)
+                    ),
+                    table_path: table_path.clone(),
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Finds crates declared in both `[dependencies]` and `[build-dependencies]` of a member manifest with
+/// different default-features settings
+///
+/// Outside of cross-compilation, Cargo unifies the features of both declarations, so the declaration
+/// enabling default features wins and the reduction expected from the other one never materializes.
+/// Entries inherited with `workspace = true` are resolved against `[workspace.dependencies]`.
+///
+/// # Returns
+///
+/// A vector of violations describing each mismatched pair, about the declaration enabling default features.
+pub fn find_build_dependency_mismatches(member_content: &str, workspace_content: &str) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+    let workspace: toml::Value = toml::from_str(workspace_content).context("Failed to parse Cargo.toml")?;
+    let workspace_deps = workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    let (Some(deps), Some(build_deps)) = (
+        member.get("dependencies").and_then(toml::Value::as_table),
+        member.get("build-dependencies").and_then(toml::Value::as_table),
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    let mut mismatches = Vec::new();
+    for (name, value) in deps {
+        let package = dependency_package_name(name, value);
+        let Some((build_name, build_value)) = build_deps
+            .iter()
+            .find(|(build_name, build_value)| dependency_package_name(build_name, build_value) == package)
+        else {
+            continue;
+        };
+
+        let normal_defaults = member_enables_default_features(name, value, workspace_deps);
+        let build_defaults = member_enables_default_features(build_name, build_value, workspace_deps);
+        if normal_defaults != build_defaults {
+            let (enabled_in, enabled_name, disabled_in) = if build_defaults {
+                ("build-dependencies", build_name, "dependencies")
+            } else {
+                ("dependencies", name, "build-dependencies")
+            };
+
+            mismatches.push(Violation::dependency(
+                &[enabled_in.to_string()],
+                enabled_name,
+                format!(
+                    "'{package}' has default features disabled in [{disabled_in}] but enabled in [{enabled_in}]; \
+                     both declarations unify outside of cross-compilation, so default features end up enabled",
+                ),
+            ));
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Finds member dependency entries that use `package` to declare a crate already governed by
+/// `[workspace.dependencies]` instead of inheriting it
+///
+/// Such entries look like ordinary dependencies but bypass the workspace table entirely, so the
+/// default-features policy enforced there doesn't apply to them.
+///
+/// # Returns
+///
+/// A vector of violations, each naming the offending entry and the `workspace = true` entry that
+/// should replace it.
+pub fn find_workspace_bypasses(member_content: &str, workspace_content: &str) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+    let workspace: toml::Value = toml::from_str(workspace_content).context("Failed to parse Cargo.toml")?;
+    let Some(workspace_deps) = workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut bypasses = Vec::new();
+    for (table_path, deps_table) in member_dependency_table_paths(&member) {
+        let section = section_label(&table_path);
+        for (name, value) in deps_table {
+            let Some(package) = value.get("package").and_then(toml::Value::as_str) else {
+                continue;
+            };
+
+            if is_workspace_inherited(value) {
+                continue;
+            }
+
+            let Some(workspace_name) = workspace_deps
+                .iter()
+                .find(|(ws_name, ws_value)| dependency_package_name(ws_name, ws_value) == package)
+                .map(|(ws_name, _)| ws_name)
+            else {
+                continue;
+            };
+
+            bypasses.push(Violation::dependency(
+                &table_path,
+                name,
+                format!(
+                    "'{name}' in {section}: renames package '{package}' which is governed by [workspace.dependencies], use `{}` instead",
+                    workspace_replacement(workspace_name, value),
+                ),
+            ));
+        }
+    }
+
+    Ok(bypasses)
+}
+
+/// A problem with a specific dependency entry, located precisely enough to be fixed automatically
+#[derive(Debug, PartialEq, Eq)]
+pub struct DependencyProblem {
+    /// Keys leading to the dependency table holding the entry, for example `["dependencies"]`
+    pub table_path: Vec<String>,
+
+    /// Name of the dependency entry
+    pub name: String,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+impl From<DependencyProblem> for Violation {
+    fn from(problem: DependencyProblem) -> Self {
+        Self::dependency(&problem.table_path, &problem.name, problem.message)
+    }
+}
+
+/// Finds member dependencies declared directly, for example `serde = "1.0"`, while
+/// `[workspace.dependencies]` has an entry under the same name
+///
+/// The inline copy doesn't inherit the workspace's settings, so it bypasses the default-features
+/// policy enforced there. Entries using `package` are left to `find_workspace_bypasses`.
+pub fn find_uninherited_dependencies(member_content: &str, workspace_content: &str) -> Result<Vec<DependencyProblem>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+    let workspace: toml::Value = toml::from_str(workspace_content).context("Failed to parse Cargo.toml")?;
+    let Some(workspace_deps) = workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut uninherited = Vec::new();
+    for (table_path, deps_table) in member_dependency_table_paths(&member) {
+        let section = section_label(&table_path);
+        for (name, value) in deps_table {
+            if is_workspace_inherited(value) || value.get("package").is_some() || !workspace_deps.contains_key(name) {
+                continue;
+            }
+
+            uninherited.push(DependencyProblem {
+                table_path: table_path.clone(),
+                name: name.clone(),
+                message: format!(
+                    "'{name}' in {section}: declared directly although [workspace.dependencies] has an entry for it, use `{}` instead",
+                    workspace_replacement(name, value),
+                ),
+            });
+        }
+    }
+
+    Ok(uninherited)
+}
+
+/// Builds the `workspace = true` entry that should replace a member's own dependency declaration,
+/// carrying over the keys that are allowed alongside `workspace = true`
+fn workspace_replacement(workspace_name: &str, value: &toml::Value) -> String {
+    let mut keys = vec!["workspace = true".to_string()];
+    for key in ["features", "optional"] {
+        if let Some(kept) = value.get(key) {
+            keys.push(format!("{key} = {kept}"));
+        }
+    }
+
+    format!("{workspace_name} = {{ {} }}", keys.join(", "))
+}
+
+/// Finds packages that members import under different names
+///
+/// Entries inherited with `workspace = true` resolve to the package of the matching
+/// `[workspace.dependencies]` entry, under the workspace's key.
+///
+/// # Returns
+///
+/// A vector of violations, one per inconsistently aliased package, listing every alias and the members
+/// using it.
+pub fn find_inconsistent_aliases(members: &[Member], workspace_content: &str) -> Result<Vec<Violation>> {
+    let workspace: toml::Value = toml::from_str(workspace_content).context("Failed to parse Cargo.toml")?;
+    let workspace_deps = workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    let mut aliases: BTreeMap<String, BTreeMap<String, BTreeSet<&str>>> = BTreeMap::new();
+    for member in members {
+        let parsed: toml::Value =
+            toml::from_str(&member.content).with_context(|| format!("Failed to parse {}", member.manifest_path.display()))?;
+        for (_, deps_table) in member_dependency_tables(&parsed) {
+            for (name, value) in deps_table {
+                let package = match workspace_deps.and_then(|deps| deps.get(name)) {
+                    Some(ws_value) if is_workspace_inherited(value) => dependency_package_name(name, ws_value),
+                    _ => dependency_package_name(name, value),
+                };
+
+                let _ = aliases
+                    .entry(package.to_string())
+                    .or_default()
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(&member.name);
+            }
+        }
+    }
+
+    Ok(aliases
+        .into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(package, names)| {
+            let names: Vec<String> = names
+                .into_iter()
+                .map(|(alias, users)| format!("'{alias}' (in {})", users.into_iter().collect::<Vec<_>>().join(", ")))
+                .collect();
+            let message = format!("package '{package}' is imported under inconsistent names: {}", names.join(", "));
+            Violation::package(&package, message)
+        })
+        .collect())
+}
+
+/// Finds entries of `[workspace.dependencies]` that no member inherits with `workspace = true`
+///
+/// Nothing is reported for a workspace without members, since there's nothing to compare against, nor
+/// for the dependencies covered by the exceptions.
+pub fn find_unused_workspace_dependencies(
+    members: &[Member],
+    workspace_content: &str,
+    exceptions: &[ExceptionPattern],
+) -> Result<Vec<DependencyProblem>> {
+    let workspace: toml::Value = toml::from_str(workspace_content).context("Failed to parse Cargo.toml")?;
+    let Some(workspace_deps) = workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    else {
+        return Ok(Vec::new());
     };
 
-    match dep_table.get("default-features") {
-        Some(toml::Value::Boolean(false)) => Ok(()),
+    if members.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut inherited = BTreeSet::new();
+    for member in members {
+        let parsed: toml::Value =
+            toml::from_str(&member.content).with_context(|| format!("Failed to parse {}", member.manifest_path.display()))?;
+        for (_, deps_table) in member_dependency_tables(&parsed) {
+            inherited.extend(
+                deps_table
+                    .iter()
+                    .filter(|(_, value)| is_workspace_inherited(value))
+                    .map(|(name, _)| name.clone()),
+            );
+        }
+    }
 
-        Some(toml::Value::Boolean(true)) => Err(format!("  - '{name}': has default-features = true (must be false)")),
+    Ok(workspace_deps
+        .iter()
+        .filter(|(name, value)| !inherited.contains(*name) && !is_dependency_excepted(exceptions, name, value))
+        .map(|(name, _)| DependencyProblem {
+            table_path: vec!["workspace".to_string(), "dependencies".to_string()],
+            name: name.clone(),
+            message: format!("'{name}': isn't inherited by any member"),
+        })
+        .collect())
+}
 
-        None => Err(format!("  - '{name}': missing default-features = false")),
+/// Returns every dependency table of a member manifest, labeled with its section name
+///
+/// This includes `[dependencies]`, `[build-dependencies]`, `[dev-dependencies]`, and their
+/// target-specific variants.
+pub fn member_dependency_tables(member: &toml::Value) -> Vec<(String, &toml::Table)> {
+    member_dependency_table_paths(member)
+        .into_iter()
+        .map(|(path, table)| (section_label(&path), table))
+        .collect()
+}
 
-        Some(_) => Err(format!(
-            "  - '{name}': default-features has unexpected value (must be boolean false)",
-        )),
+/// Formats the keys leading to a dependency table the way the table's header is written
+pub fn section_label(path: &[String]) -> String {
+    match path {
+        [target, scope, section] if target == "target" => format!("[target.'{scope}'.{section}]"),
+        _ => format!("[{}]", path.join(".")),
     }
 }
 
-/// Validates all workspace dependencies in the given Cargo.toml content
-///
-/// # Returns
+/// Returns the dependency tables of a manifest, along with the keys leading to them
 ///
-/// A tuple containing:
-/// * A vector of error messages for invalid dependencies
-/// * A vector of all dependency names found in [workspace.dependencies]
-pub fn validate_workspace_dependencies(content: &str, exceptions: &[String]) -> Result<(Vec<String>, Vec<String>)> {
-    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
-    let workspace = parsed.get("workspace").context("No [workspace] section found")?;
-    let dependencies = workspace.get("dependencies").context("No [workspace.dependencies] section found")?;
-    let deps_table = dependencies.as_table().context("[workspace.dependencies] is not a table")?;
+/// Only `[workspace.dependencies]` is returned when `workspace_table` is set, and only the package's
+/// tables otherwise, so a root manifest that's also a member has each table checked once.
+pub fn dependency_tables(parsed: &toml::Value, workspace_table: bool) -> Vec<(Vec<String>, &toml::Table)> {
+    if !workspace_table {
+        return member_dependency_table_paths(parsed);
+    }
 
-    let mut errors = Vec::new();
-    let mut found_deps = Vec::new();
-    for (name, value) in deps_table {
-        found_deps.push(name.clone());
-        if exceptions.contains(name) {
-            continue;
+    parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+        .map(|table| (vec!["workspace".to_string(), "dependencies".to_string()], table))
+        .into_iter()
+        .collect()
+}
+
+/// Returns every dependency table of a member manifest, along with the keys leading to it
+pub fn member_dependency_table_paths(member: &toml::Value) -> Vec<(Vec<String>, &toml::Table)> {
+    const SECTIONS: [&str; 3] = ["dependencies", "build-dependencies", "dev-dependencies"];
+
+    let mut tables = Vec::new();
+    for section in SECTIONS {
+        if let Some(table) = member.get(section).and_then(toml::Value::as_table) {
+            tables.push((vec![section.to_string()], table));
         }
+    }
 
-        if let Err(err) = validate_dependency(name, value) {
-            errors.push(err);
+    for (target, scope) in member.get("target").and_then(toml::Value::as_table).into_iter().flatten() {
+        for section in SECTIONS {
+            if let Some(table) = scope.get(section).and_then(toml::Value::as_table) {
+                tables.push((vec!["target".to_string(), target.clone(), section.to_string()], table));
+            }
         }
     }
 
-    Ok((errors, found_deps))
+    tables
+}
+
+/// Returns whether a member's dependency entry ends up with default features enabled, resolving
+/// `workspace = true` entries against the workspace dependency table
+pub fn member_enables_default_features(name: &str, value: &toml::Value, workspace_deps: Option<&toml::Table>) -> bool {
+    if !is_workspace_inherited(value) {
+        return enables_default_features(value);
+    }
+
+    // A member can only add features on top of the workspace entry, never remove them
+    matches!(default_features_setting(value), Some(toml::Value::Boolean(true)))
+        || workspace_deps.and_then(|deps| deps.get(name)).is_none_or(enables_default_features)
+}
+
+/// Returns whether a dependency entry leaves the dependency's default features enabled
+pub fn enables_default_features(value: &toml::Value) -> bool {
+    !matches!(default_features_setting(value), Some(toml::Value::Boolean(false)))
+}
+
+/// Returns the default-features setting of a dependency entry
+///
+/// Cargo still accepts the legacy `default_features` spelling, which is used when the hyphenated key
+/// is absent.
+pub fn default_features_setting(value: &toml::Value) -> Option<&toml::Value> {
+    value.get("default-features").or_else(|| value.get("default_features"))
+}
+
+/// Returns the name of the crate a dependency entry refers to, honoring `package` renames
+pub fn dependency_package_name<'a>(name: &'a str, value: &'a toml::Value) -> &'a str {
+    value.get("package").and_then(toml::Value::as_str).unwrap_or(name)
+}
+
+/// Returns whether a dependency entry inherits its definition from the workspace
+fn is_workspace_inherited(value: &toml::Value) -> bool {
+    matches!(value.get("workspace"), Some(toml::Value::Boolean(true)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::messages;
 
     #[test]
     fn test_validate_dependency_with_default_features_false() {
@@ -66,7 +1080,7 @@ default-features = false
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_ok(), "Should be valid when default-features = false");
     }
 
@@ -79,7 +1093,7 @@ features = ["feature1", "feature2"]
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_ok(), "Should be valid with default-features = false and features");
     }
 
@@ -87,9 +1101,9 @@ features = ["feature1", "feature2"]
     fn test_validate_dependency_simple_version_string() {
         let value = toml::Value::String("1.0".to_string());
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_err());
-        let error = result.unwrap_err();
+        let error = result.unwrap_err().message("test-crate");
         assert!(error.contains("test-crate"));
         assert!(error.contains("uses simple version string"));
     }
@@ -99,9 +1113,9 @@ features = ["feature1", "feature2"]
         // Test with an array value (not a string or table)
         let value = toml::Value::Array(vec![toml::Value::String("1.0".to_string())]);
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_err());
-        let error = result.unwrap_err();
+        let error = result.unwrap_err().message("test-crate");
         assert!(error.contains("test-crate"));
         assert!(error.contains("dependency is not a table"));
     }
@@ -113,9 +1127,9 @@ version = "1.0"
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_err());
-        let error = result.unwrap_err();
+        let error = result.unwrap_err().message("test-crate");
         assert!(error.contains("test-crate"));
         assert!(error.contains("missing default-features = false"));
     }
@@ -128,9 +1142,9 @@ default-features = true
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_err());
-        let error = result.unwrap_err();
+        let error = result.unwrap_err().message("test-crate");
         assert!(error.contains("test-crate"));
         assert!(error.contains("has default-features = true"));
     }
@@ -143,7 +1157,7 @@ default-features = false
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_ok(), "Should be valid with git source and default-features = false");
     }
 
@@ -155,7 +1169,7 @@ default-features = false
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_ok(), "Should be valid with path source and default-features = false");
     }
 
@@ -168,7 +1182,7 @@ optional = true
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_ok(), "Should be valid with optional flag and default-features = false");
     }
 
@@ -180,9 +1194,9 @@ default-features = "false"
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_err());
-        let error = result.unwrap_err();
+        let error = result.unwrap_err().message("test-crate");
         assert!(error.contains("test-crate"));
         assert!(error.contains("unexpected value"));
     }
@@ -198,7 +1212,7 @@ package = "other-name"
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_ok(), "Should be valid with complex configuration");
     }
 
@@ -209,9 +1223,9 @@ git = "https://github.com/example/repo"
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_err());
-        let error = result.unwrap_err();
+        let error = result.unwrap_err().message("test-crate");
         assert!(error.contains("missing default-features = false"));
     }
 
@@ -222,9 +1236,9 @@ path = "../local-crate"
 "#;
         let value: toml::Value = toml::from_str(toml_str).unwrap();
 
-        let result = validate_dependency("test-crate", &value);
+        let result = validate_dependency(&value);
         assert!(result.is_err());
-        let error = result.unwrap_err();
+        let error = result.unwrap_err().message("test-crate");
         assert!(error.contains("missing default-features = false"));
     }
 
@@ -258,6 +1272,146 @@ tokio = { version = "1.0" }
         assert_eq!(errors.0.len(), 2, "Should have 2 errors");
     }
 
+    #[test]
+    fn test_validate_workspace_dependencies_with_glob_exceptions() {
+        let content = r#"
+[workspace.dependencies]
+windows-sys = "0.59"
+windows-core = "0.59"
+winapi = "0.3"
+"#;
+
+        let (errors, _) = validate_workspace_dependencies(content, &compile_exceptions(&["windows-*".to_string()])).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("'winapi'"));
+    }
+
+    fn matches(exception: &str, name: &str) -> bool {
+        ExceptionPattern::new(exception).matches(name)
+    }
+
+    #[test]
+    fn test_exception_matches() {
+        assert!(matches("serde", "serde"));
+        assert!(!matches("serde", "serde_json"));
+        assert!(matches("serde*", "serde_json"));
+        assert!(matches("my-org-*", "my-org-core"));
+        assert!(matches("tokio-[ru]*", "tokio-util"));
+        assert!(!matches("my-org-*", "other-core"));
+        assert!(matches("regex:^winapi(-.*)?$", "winapi"));
+        assert!(matches("regex:^winapi(-.*)?$", "winapi-util"));
+        assert!(!matches("regex:^winapi(-.*)?$", "winapix"));
+        assert!(!matches("regex:(", "("));
+    }
+
+    #[test]
+    fn test_normalize_exceptions() {
+        let exceptions = [" serde", "tokio ", "", "serde", "  ", "windows-*"].map(String::from);
+        assert_eq!(normalize_exceptions(exceptions), ["serde", "tokio", "windows-*"]);
+    }
+
+    #[test]
+    fn test_validate_exceptions() {
+        validate_exceptions(&["serde".to_string(), "windows-*".to_string(), "regex:^win".to_string()]).unwrap();
+
+        let err = validate_exceptions(&["regex:^winapi(".to_string()]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid regular expression in exception 'regex:^winapi('");
+
+        let err = validate_exceptions(&["windows-[".to_string()]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid glob pattern in exception 'windows-['");
+
+        validate_exceptions(&["serde@^1.0".to_string(), "regex:^a@b$".to_string()]).unwrap();
+        let err = validate_exceptions(&["serde@one".to_string()]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid version requirement in exception 'serde@one'");
+    }
+
+    #[test]
+    fn test_validate_workspace_dependencies_with_renamed_exceptions() {
+        let content = r#"
+[workspace.dependencies]
+json = { package = "serde_json", version = "1.0" }
+yaml = { package = "serde_yaml", version = "0.9" }
+"#;
+
+        let (errors, found_deps) =
+            validate_workspace_dependencies(content, &compile_exceptions(&["serde_json".to_string(), "yaml".to_string()])).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(found_deps, ["json", "serde_json", "yaml", "serde_yaml"]);
+    }
+
+    #[test]
+    fn test_find_obsolete_exceptions() {
+        let content = r#"
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+tokio = "1.0"
+windows-sys = { version = "0.52", default-features = false }
+windows-core = "0.59"
+"#;
+
+        let exceptions: Vec<_> = ["serde", "tokio", "windows-*", "openssl", "serde"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(find_obsolete_exceptions(content, &compile_exceptions(&exceptions)), ["serde"]);
+    }
+
+    #[test]
+    fn test_resolve_versioned_exceptions() {
+        let content = r#"
+[workspace.dependencies]
+serde = "1.0.200"
+tokio = "2"
+windows-sys = "0.52"
+windows-core = "0.59"
+local = { path = "local" }
+"#;
+
+        let exceptions: Vec<_> = ["serde@^1.0", "tokio@^1", "windows-*@<0.59", "local@*", "openssl"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            resolve_versioned_exceptions(&exceptions, content),
+            ["serde", "windows-sys", "openssl"]
+        );
+        assert!(!matches("serde@^1.0", "serde"));
+        assert_eq!(
+            resolve_versioned_exceptions(&["dev:serde@^1".to_string(), "dev:tokio@^1".to_string()], content),
+            ["dev:serde"]
+        );
+    }
+
+    #[test]
+    fn test_is_member_dependency_excepted() {
+        let exceptions = compile_exceptions(&["dev:criterion".to_string(), "build:cc*".to_string(), "serde".to_string()]);
+        let value = toml::Value::String("1".to_string());
+        assert!(is_member_dependency_excepted(
+            &exceptions,
+            "[dev-dependencies]",
+            "criterion",
+            &value
+        ));
+        assert!(is_member_dependency_excepted(
+            &exceptions,
+            "[target.'cfg(unix)'.dev-dependencies]",
+            "criterion",
+            &value
+        ));
+        assert!(!is_member_dependency_excepted(&exceptions, "[dependencies]", "criterion", &value));
+        assert!(!is_member_dependency_excepted(
+            &exceptions,
+            "[workspace.dependencies]",
+            "criterion",
+            &value
+        ));
+        assert!(is_member_dependency_excepted(&exceptions, "[build-dependencies]", "cc", &value));
+        assert!(is_member_dependency_excepted(&exceptions, "[dependencies]", "serde", &value));
+
+        let renamed: toml::Value = toml::from_str("package = \"criterion\"").unwrap();
+        assert!(is_member_dependency_excepted(&exceptions, "[dev-dependencies]", "bench", &renamed));
+    }
+
     #[test]
     fn test_validate_workspace_dependencies_no_workspace() {
         let content = r#"
@@ -309,7 +1463,7 @@ serde = { version = "1.0", default-features = false }
 tokio = { version = "1.0", default-features = false, features = ["rt"] }
 "#;
 
-        let exceptions = vec!["tokio".to_string()];
+        let exceptions = compile_exceptions(&["tokio".to_string()]);
         let errors = validate_workspace_dependencies(content, &exceptions).unwrap();
         assert!(errors.0.is_empty(), "Should have no errors with valid dependencies");
         assert_eq!(errors.1.len(), 2, "Should find 2 dependencies");
@@ -328,11 +1482,476 @@ serde = "1.0"
 tokio = { version = "1.0" }
 "#;
 
-        let exceptions = vec!["tokio".to_string()];
+        let exceptions = compile_exceptions(&["tokio".to_string()]);
         let errors = validate_workspace_dependencies(content, &exceptions).unwrap();
         assert_eq!(errors.0.len(), 1, "Should have 1 error");
         assert_eq!(errors.1.len(), 2, "Should find 2 dependencies");
         assert!(errors.1.contains(&"serde".to_string()));
         assert!(errors.1.contains(&"tokio".to_string()));
     }
+
+    #[test]
+    fn test_fixable_workspace_dependencies() {
+        let content = r#"
+[workspace.dependencies]
+serde = "1.0"
+tokio = { version = "1.0", default-features = false }
+regex = { version = "1.0", default-features = true }
+anyhow = "1.0"
+odd = ["1.0"]
+"#;
+
+        let fixable = fixable_workspace_dependencies(content, &compile_exceptions(&["anyhow".to_string()])).unwrap();
+        assert_eq!(fixable, ["regex", "serde"]);
+    }
+
+    #[test]
+    fn test_validate_dependency_legacy_default_features() {
+        let value: toml::Value = toml::from_str("version = \"1.0\"\ndefault_features = false").unwrap();
+        let result = validate_dependency(&value);
+        assert!(result.is_ok(), "Should be valid when default_features = false");
+
+        let value: toml::Value = toml::from_str("version = \"1.0\"\ndefault_features = true").unwrap();
+        let result = validate_dependency(&value);
+        assert_eq!(result.unwrap_err(), DefaultFeaturesProblem::Enabled);
+        assert_eq!(
+            DefaultFeaturesProblem::Enabled.message("serde"),
+            "'serde': has default-features = true (must be false)"
+        );
+    }
+
+    #[test]
+    fn test_find_legacy_default_features() {
+        let content = r#"
+[workspace.dependencies]
+serde = { version = "1.0", default_features = false }
+tokio = { version = "1.0", default-features = false }
+
+[target.'cfg(unix)'.dependencies]
+libc = { version = "0.2", default_features = false }
+"#;
+
+        let problems = find_legacy_default_features(content, true).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].name, "serde");
+        assert_eq!(
+            problems[0].message,
+            "'serde' in [workspace.dependencies]: uses the legacy default_features key instead of default-features"
+        );
+
+        let problems = find_legacy_default_features(content, false).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].table_path, ["target", "cfg(unix)", "dependencies"]);
+    }
+
+    #[test]
+    fn test_find_unused_workspace_dependencies() {
+        let workspace = r#"
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+tokio = { version = "1.0", default-features = false }
+regex = { version = "1.0", default-features = false }
+"#;
+        let member = |content: &str| Member {
+            name: "member".to_string(),
+            manifest_path: "member/Cargo.toml".into(),
+            content: content.to_string(),
+        };
+        let members = [
+            member("[dependencies]\nserde = { workspace = true }\n"),
+            member("[target.'cfg(unix)'.dev-dependencies]\ntokio = { workspace = true }\n[dependencies]\nregex = \"1.0\"\n"),
+        ];
+
+        let unused = find_unused_workspace_dependencies(&members, workspace, &[]).unwrap();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].name, "regex");
+        assert_eq!(unused[0].message, "'regex': isn't inherited by any member");
+
+        assert!(
+            find_unused_workspace_dependencies(&members, workspace, &compile_exceptions(&["reg*".to_string()]))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(find_unused_workspace_dependencies(&[], workspace, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_git_dependencies() {
+        let content = r#"
+[workspace.dependencies]
+internal = { git = "https://git.example.com/team/internal.git", default-features = false }
+fork = { git = "git@github.com:someone/fork.git", default-features = false }
+pinned = { git = "https://github.com/someone/pinned", rev = "abc123", default-features = false }
+serde = { version = "1.0", default-features = false }
+"#;
+
+        let forbidden = find_git_dependencies(content, &[], &[]).unwrap();
+        assert_eq!(forbidden.len(), 3);
+        assert_eq!(
+            forbidden[0].message,
+            "'fork': uses the git repository git@github.com:someone/fork.git, git dependencies are not allowed"
+        );
+
+        let forbidden = find_git_dependencies(content, &["GIT.example.com".to_string()], &["pinned".to_string()]).unwrap();
+        assert_eq!(forbidden.len(), 1);
+        assert!(forbidden[0].message.starts_with("'fork'"));
+    }
+
+    #[test]
+    fn test_git_host() {
+        assert_eq!(git_host("https://github.com/org/repo"), "github.com");
+        assert_eq!(git_host("ssh://git@host.example:2222/repo.git"), "host.example");
+        assert_eq!(git_host("git@github.com:org/repo.git"), "github.com");
+    }
+
+    #[test]
+    fn test_find_unsorted_dependencies() {
+        let content = r#"
+[workspace.dependencies]
+anyhow = { version = "1.0", default-features = false }
+tokio = { version = "1.0", default-features = false }
+regex = { version = "1.0", default-features = false }
+serde = { version = "1.0", default-features = false }
+clap = { version = "4.0", default-features = false }
+"#;
+
+        assert_eq!(
+            messages(&find_unsorted_dependencies(content).unwrap()),
+            [
+                "'regex' is out of alphabetical order, it should come before 'tokio'",
+                "'serde' is out of alphabetical order, it should come before 'tokio'",
+                "'clap' is out of alphabetical order, it should come before 'regex'",
+            ]
+        );
+
+        assert!(
+            find_unsorted_dependencies("[workspace.dependencies]\na = \"1\"\nb = \"1\"\n")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_validate_package_dependencies() {
+        let content = r#"
+[package]
+name = "sibling"
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1.0", default-features = false }
+regex = "1.0"
+anyhow = { workspace = true }
+
+[build-dependencies]
+cc = { version = "1.0" }
+
+[dev-dependencies]
+tempfile = "3.0"
+"#;
+
+        let errors = validate_package_dependencies(content, &[]).unwrap();
+        assert_eq!(errors.len(), 2, "Should have 2 errors");
+        assert!(errors.iter().any(|e| e.message.contains("'regex'")));
+        assert!(errors.iter().any(|e| e.message.contains("'cc'")));
+
+        let exceptions = compile_exceptions(&["regex".to_string()]);
+        let errors = validate_package_dependencies(content, &exceptions).unwrap();
+        assert_eq!(errors.len(), 1, "Should have 1 error");
+    }
+
+    #[test]
+    fn test_path_dependencies() {
+        let content = r#"
+[workspace]
+members = ["crate1"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+local = { path = "../local", default-features = false }
+
+[dependencies]
+other = { path = "../other" }
+"#;
+
+        let paths = path_dependencies(content).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&("local".to_string(), "../local".to_string())));
+        assert!(paths.contains(&("other".to_string(), "../other".to_string())));
+    }
+
+    #[test]
+    fn test_find_default_feature_requests() {
+        let content = r#"
+[workspace.dependencies]
+tokio = { version = "1.0", default-features = false, features = ["default", "rt"] }
+serde = { version = "1.0", default-features = false, features = ["derive"] }
+anyhow = { version = "1.0", default-features = false, features = ["default"] }
+
+[dependencies]
+serde = { workspace = true, features = ["default"] }
+
+[target.'cfg(unix)'.dev-dependencies]
+libc = { version = "0.2", features = ["default"] }
+"#;
+
+        let requests = find_default_feature_requests(content, &compile_exceptions(&["anyhow".to_string()])).unwrap();
+        assert_eq!(
+            messages(&requests),
+            [
+                "'tokio' in [workspace.dependencies]: lists \"default\" in features, which re-enables the default features",
+                "'serde' in [dependencies]: lists \"default\" in features, which re-enables the default features",
+                "'libc' in [target.'cfg(unix)'.dev-dependencies]: lists \"default\" in features, which re-enables the default features",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_default_feature_references() {
+        let content = r#"
+[features]
+default = ["std"]
+std = ["serde?/default", "tokio/rt"]
+full = ["tokio/default", "anyhow/default", "dep:log"]
+"#;
+
+        let references = find_default_feature_references(content, &compile_exceptions(&["anyhow".to_string()])).unwrap();
+        assert_eq!(
+            messages(&references),
+            [
+                "feature 'full' enables \"tokio/default\", which re-enables the default features of 'tokio'",
+                "feature 'std' enables \"serde?/default\", which re-enables the default features of 'serde'",
+            ]
+        );
+
+        assert!(
+            find_default_feature_references("[package]\nname = \"x\"\n", &[])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_find_default_feature_overrides() {
+        let content = r#"
+[dependencies]
+serde = { workspace = true, default-features = true }
+tokio = { workspace = true, features = ["rt"] }
+anyhow = { workspace = true, default-features = true }
+log = { version = "0.4", default-features = true }
+
+[build-dependencies]
+cc = { workspace = true, default-features = true }
+"#;
+
+        let overrides = find_default_feature_overrides(content, &compile_exceptions(&["anyhow".to_string()])).unwrap();
+        assert_eq!(
+            messages(&overrides),
+            [
+                "'serde' in [dependencies]: sets default-features = true, overriding the default-features setting inherited from [workspace.dependencies]",
+                "'cc' in [build-dependencies]: sets default-features = true, overriding the default-features setting inherited from [workspace.dependencies]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_decentralized_dependencies() {
+        let content = r#"
+[dependencies]
+serde = { workspace = true }
+log = "0.4"
+anyhow = "1.0"
+util = { path = "../util", version = "0.1.0" }
+
+[dev-dependencies]
+mock = { git = "https://example.com/mock.git" }
+"#;
+
+        let decentralized = find_decentralized_dependencies(content, &compile_exceptions(&["anyhow".to_string()])).unwrap();
+        assert_eq!(
+            messages(&decentralized),
+            [
+                "'log' in [dependencies]: declares its own version instead of inheriting from [workspace.dependencies]",
+                "'util' in [dependencies]: declares its own version and path instead of inheriting from [workspace.dependencies]",
+                "'mock' in [dev-dependencies]: declares its own git instead of inheriting from [workspace.dependencies]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_uninherited_dependencies() {
+        let workspace = r#"
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+tokio = { version = "1.0", default-features = false }
+"#;
+        let member = r#"
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+tokio = { workspace = true }
+log = "0.4"
+
+[target.'cfg(unix)'.dev-dependencies]
+tokio = "1.0"
+"#;
+
+        let uninherited = find_uninherited_dependencies(member, workspace).unwrap();
+        assert_eq!(uninherited.len(), 2);
+        assert_eq!(uninherited[0].table_path, ["dependencies"]);
+        assert_eq!(uninherited[0].name, "serde");
+        assert_eq!(
+            uninherited[0].message,
+            "'serde' in [dependencies]: declared directly although [workspace.dependencies] has an entry for it, use `serde = { workspace = true, features = [\"derive\"] }` instead"
+        );
+        assert_eq!(uninherited[1].table_path, ["target", "cfg(unix)", "dev-dependencies"]);
+        assert!(
+            uninherited[1]
+                .message
+                .starts_with("'tokio' in [target.'cfg(unix)'.dev-dependencies]: ")
+        );
+
+        assert!(find_uninherited_dependencies(member, "[workspace]\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_build_dependency_mismatches() {
+        let workspace = r#"
+[workspace]
+members = ["member"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+        let member = r#"
+[package]
+name = "member"
+version = "0.1.0"
+
+[dependencies]
+serde = { workspace = true }
+regex = { version = "1.0", default-features = false }
+log = { version = "0.4", default-features = false }
+
+[build-dependencies]
+serde = "1.0"
+regex = { version = "1.0", default-features = false }
+"#;
+
+        let mismatches = find_build_dependency_mismatches(member, workspace).unwrap();
+        assert_eq!(mismatches.len(), 1, "Should find 1 mismatch");
+        assert!(mismatches[0].message.contains("'serde'"));
+        assert!(
+            mismatches[0]
+                .message
+                .contains("disabled in [dependencies] but enabled in [build-dependencies]")
+        );
+    }
+
+    #[test]
+    fn test_find_build_dependency_mismatches_inherited_override() {
+        let workspace = r#"
+[workspace.dependencies]
+cc = { version = "1.0", default-features = false }
+"#;
+
+        let member = "
+[dependencies]
+cc = { workspace = true, default-features = true }
+
+[build-dependencies]
+cc = { workspace = true }
+";
+
+        let mismatches = find_build_dependency_mismatches(member, workspace).unwrap();
+        assert_eq!(mismatches.len(), 1, "Should find 1 mismatch");
+        assert!(
+            mismatches[0]
+                .message
+                .contains("disabled in [build-dependencies] but enabled in [dependencies]")
+        );
+    }
+
+    #[test]
+    fn test_find_workspace_bypasses() {
+        let workspace = r#"
+[workspace.dependencies]
+tokio = { version = "1.0", default-features = false }
+prost = { version = "0.13", default-features = false }
+"#;
+
+        let member = r#"
+[dependencies]
+tokio = { package = "tokio", version = "1.0", features = ["rt"] }
+proto = { package = "prost", version = "0.13", optional = true }
+other = { package = "other-crate", version = "1.0" }
+serde = { version = "1.0" }
+
+[target.'cfg(unix)'.dev-dependencies]
+tok = { package = "tokio", version = "1.0" }
+"#;
+
+        let bypasses = find_workspace_bypasses(member, workspace).unwrap();
+        assert_eq!(bypasses.len(), 3, "Should find 3 bypasses");
+        assert!(bypasses.iter().any(|b| b.message.contains("'tokio' in [dependencies]")
+            && b.message.contains(r#"`tokio = { workspace = true, features = ["rt"] }`"#)));
+        assert!(
+            bypasses
+                .iter()
+                .any(|b| b.message.contains("'proto' in [dependencies]: renames package 'prost'")
+                    && b.message.contains("`prost = { workspace = true, optional = true }`"))
+        );
+        assert!(
+            bypasses
+                .iter()
+                .any(|b| b.message.contains("'tok' in [target.'cfg(unix)'.dev-dependencies]"))
+        );
+    }
+
+    #[test]
+    fn test_find_inconsistent_aliases() {
+        let workspace = r#"
+[workspace.dependencies]
+prost = { version = "0.13", default-features = false }
+"#;
+
+        let member = |name: &str, content: &str| Member {
+            name: name.to_string(),
+            manifest_path: std::path::PathBuf::from(name).join("Cargo.toml"),
+            content: content.to_string(),
+        };
+
+        let members = vec![
+            member("a", "[dependencies]\nprost = { workspace = true }\nserde = \"1.0\"\n"),
+            member("b", "[dependencies]\nprost = { workspace = true }\nserde = \"1.0\"\n"),
+            member("c", "[dev-dependencies]\nproto = { package = \"prost\", version = \"0.13\" }\n"),
+        ];
+
+        let aliases = find_inconsistent_aliases(&members, workspace).unwrap();
+        assert_eq!(aliases.len(), 1, "Should find 1 inconsistently aliased package");
+        assert_eq!(
+            aliases[0].message,
+            "package 'prost' is imported under inconsistent names: 'prost' (in a, b), 'proto' (in c)"
+        );
+    }
+
+    #[test]
+    fn test_enables_default_features() {
+        let value: toml::Value = toml::from_str("version = \"1.0\"\ndefault-features = false").unwrap();
+        assert!(!enables_default_features(&value));
+
+        let value: toml::Value = toml::from_str("version = \"1.0\"").unwrap();
+        assert!(enables_default_features(&value));
+
+        assert!(enables_default_features(&toml::Value::String("1.0".to_string())));
+    }
+
+    #[test]
+    fn test_dependency_package_name() {
+        let value: toml::Value = toml::from_str("package = \"real-name\"\nversion = \"1.0\"").unwrap();
+        assert_eq!(dependency_package_name("alias", &value), "real-name");
+
+        let value = toml::Value::String("1.0".to_string());
+        assert_eq!(dependency_package_name("plain", &value), "plain");
+    }
 }
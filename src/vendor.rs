@@ -0,0 +1,90 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::validation::{dependency_package_name, enables_default_features};
+
+/// Label used for declarations coming from the workspace's own `[workspace.dependencies]` table
+const WORKSPACE_CONSUMER: &str = "workspace";
+
+/// Audits a directory produced by `cargo vendor` to find which vendored crates are consumed with their
+/// default features enabled
+///
+/// A vendored crate counts as consumed with default features when the workspace's
+/// `[workspace.dependencies]` table, or the manifest of any other vendored crate, declares a dependency
+/// on it without `default-features = false`. Dev-dependencies are ignored since they are never shipped.
+///
+/// # Returns
+///
+/// A vector of vendored crate names, sorted by name, each paired with the sorted names of the consumers
+/// that enable its default features.
+pub fn audit_vendor_dir(vendor_dir: &Path, workspace_content: &str) -> Result<Vec<(String, Vec<String>)>> {
+    let mut vendored = BTreeMap::new();
+    let entries = std::fs::read_dir(vendor_dir).with_context(|| format!("Failed to read {}", vendor_dir.display()))?;
+    for entry in entries {
+        let manifest_path = entry?.path().join("Cargo.toml");
+        if !manifest_path.is_file() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&manifest_path).with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+        let parsed: toml::Value = toml::from_str(&content).with_context(|| format!("Failed to parse {}", manifest_path.display()))?;
+        let name = parsed
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(toml::Value::as_str)
+            .with_context(|| format!("No package name found in {}", manifest_path.display()))?
+            .to_string();
+
+        let _ = vendored.insert(name, parsed);
+    }
+
+    let mut consumers: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+
+    let workspace: toml::Value = toml::from_str(workspace_content).context("Failed to parse Cargo.toml")?;
+    let workspace_deps = workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+    for (name, value) in workspace_deps.into_iter().flatten() {
+        if enables_default_features(value) {
+            let _ = consumers
+                .entry(dependency_package_name(name, value))
+                .or_default()
+                .insert(WORKSPACE_CONSUMER);
+        }
+    }
+
+    for (consumer, parsed) in &vendored {
+        for deps_table in shipped_dependency_tables(parsed) {
+            for (name, value) in deps_table {
+                if enables_default_features(value) {
+                    let _ = consumers.entry(dependency_package_name(name, value)).or_default().insert(consumer);
+                }
+            }
+        }
+    }
+
+    Ok(consumers
+        .into_iter()
+        .filter(|(name, _)| vendored.contains_key(*name))
+        .map(|(name, by)| (name.to_string(), by.into_iter().map(str::to_string).collect()))
+        .collect())
+}
+
+/// Returns the `[dependencies]` and `[build-dependencies]` tables of a package, including target-specific ones
+fn shipped_dependency_tables(parsed: &toml::Value) -> Vec<&toml::Table> {
+    let targets = parsed
+        .get("target")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|targets| targets.values());
+
+    core::iter::once(parsed)
+        .chain(targets)
+        .flat_map(|scope| ["dependencies", "build-dependencies"].map(|section| scope.get(section)))
+        .flatten()
+        .filter_map(toml::Value::as_table)
+        .collect()
+}
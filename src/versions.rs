@@ -0,0 +1,248 @@
+use core::fmt;
+
+use anyhow::{Context, Result};
+use semver::{Op, Version, VersionReq};
+use serde::Deserialize;
+
+use crate::rules::Violation;
+use crate::validation::{dependency_package_name, member_dependency_table_paths, section_label};
+
+/// Minimum number of components required in the version requirements of workspace dependencies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+pub enum VersionPrecision {
+    /// Any requirement is accepted, such as `1`
+    #[default]
+    #[serde(rename = "major")]
+    Major,
+
+    /// Requirements must specify at least the minor version, such as `1.2`
+    #[serde(rename = "major.minor")]
+    MajorMinor,
+
+    /// Requirements must specify the full version, such as `1.2.3`
+    #[serde(rename = "major.minor.patch")]
+    MajorMinorPatch,
+}
+
+impl VersionPrecision {
+    /// Returns the number of version components the precision requires
+    const fn components(self) -> usize {
+        match self {
+            Self::Major => 1,
+            Self::MajorMinor => 2,
+            Self::MajorMinorPatch => 3,
+        }
+    }
+}
+
+impl fmt::Display for VersionPrecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Major => "major",
+            Self::MajorMinor => "major.minor",
+            Self::MajorMinorPatch => "major.minor.patch",
+        })
+    }
+}
+
+/// Finds workspace dependencies whose version requirement is less precise than required
+///
+/// Each comparator of a requirement setting a lower bound (such as `1.2`, `^1.2`, or `>=1.2`) must
+/// specify at least the required number of components. Upper bounds like `<2` are naturally short and
+/// aren't checked. Dependencies without a version, such as path or git dependencies, are skipped.
+pub fn find_imprecise_versions(content: &str, precision: VersionPrecision) -> Result<Vec<Violation>> {
+    let parsed: toml::Value = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let deps_table = parsed
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table);
+
+    let mut imprecise = Vec::new();
+    for (name, value) in deps_table.into_iter().flatten() {
+        let Some(requirement) = version_requirement(value) else {
+            continue;
+        };
+
+        let too_short = requirement
+            .split(',')
+            .map(str::trim)
+            .filter(|comparator| !comparator.starts_with('<'))
+            .any(|comparator| version_components(comparator) < precision.components());
+
+        if too_short {
+            imprecise.push(Violation::workspace_dependency(
+                name,
+                format!("'{name}': version requirement \"{requirement}\" is less precise than the required {precision}"),
+            ));
+        }
+    }
+
+    Ok(imprecise)
+}
+
+/// Finds member dependencies declared with their own version requirement that differs from the one of
+/// the `[workspace.dependencies]` entry for the same crate
+///
+/// Entries are matched by crate name, honoring `package` renames on both sides. Requirements are
+/// compared after dropping the implicit `^` operator and whitespace, so `1.0` and `^1.0` agree.
+pub fn find_version_mismatches(member_content: &str, workspace_content: &str) -> Result<Vec<Violation>> {
+    let member: toml::Value = toml::from_str(member_content).context("Failed to parse member Cargo.toml")?;
+    let workspace: toml::Value = toml::from_str(workspace_content).context("Failed to parse Cargo.toml")?;
+    let workspace_requirements: Vec<_> = workspace
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| Some((dependency_package_name(name, value), version_requirement(value)?)))
+        .collect();
+
+    let mut mismatches = Vec::new();
+    for (table_path, deps_table) in member_dependency_table_paths(&member) {
+        let section = section_label(&table_path);
+        for (name, value) in deps_table {
+            let Some(requirement) = version_requirement(value) else {
+                continue;
+            };
+
+            let package_name = dependency_package_name(name, value);
+            let workspace_requirement = workspace_requirements
+                .iter()
+                .find(|(workspace_name, _)| *workspace_name == package_name)
+                .map(|(_, requirement)| *requirement);
+
+            if let Some(workspace_requirement) = workspace_requirement
+                && normalize_requirement(requirement) != normalize_requirement(workspace_requirement)
+            {
+                mismatches.push(Violation::dependency(
+                    &table_path,
+                    name,
+                    format!(
+                        "'{name}' in {section}: requires version \"{requirement}\", but [workspace.dependencies] requires \"{workspace_requirement}\""
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Returns the version requirement of a dependency entry, if it has one
+pub fn version_requirement(value: &toml::Value) -> Option<&str> {
+    value.as_str().or_else(|| value.get("version").and_then(toml::Value::as_str))
+}
+
+/// Returns the lowest version a requirement allows, such as `1.2.0` for `^1.2` or `>=1.2, <2`
+///
+/// Requirements without a lower bound, such as `<2` or `*`, yield `None`.
+pub fn lowest_version(requirement: &str) -> Option<Version> {
+    VersionReq::parse(requirement)
+        .ok()?
+        .comparators
+        .iter()
+        .filter(|comparator| matches!(comparator.op, Op::Exact | Op::GreaterEq | Op::Tilde | Op::Caret | Op::Wildcard))
+        .map(|comparator| Version {
+            major: comparator.major,
+            minor: comparator.minor.unwrap_or_default(),
+            patch: comparator.patch.unwrap_or_default(),
+            pre: comparator.pre.clone(),
+            build: semver::BuildMetadata::EMPTY,
+        })
+        .max()
+}
+
+/// Drops the implicit `^` operator and whitespace from each comparator of a version requirement
+fn normalize_requirement(requirement: &str) -> Vec<String> {
+    requirement
+        .split(',')
+        .map(|comparator| comparator.trim().trim_start_matches('^').replace(' ', ""))
+        .collect()
+}
+
+/// Counts the numeric components of a single version comparator, ignoring its operator and any
+/// pre-release or build metadata
+fn version_components(comparator: &str) -> usize {
+    let version = comparator.trim_start_matches(['^', '~', '=', '>', ' ']);
+    let version = version.split(['-', '+']).next().unwrap_or_default();
+    version
+        .split('.')
+        .take_while(|component| !component.is_empty() && component.chars().all(|c| c.is_ascii_digit()))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::messages;
+
+    #[test]
+    fn test_version_components() {
+        assert_eq!(version_components("1"), 1);
+        assert_eq!(version_components("^1.2"), 2);
+        assert_eq!(version_components(">= 1.2.3"), 3);
+        assert_eq!(version_components("=1.2.3-beta.1"), 3);
+        assert_eq!(version_components("1.*"), 1);
+        assert_eq!(version_components("*"), 0);
+    }
+
+    #[test]
+    fn test_find_imprecise_versions() {
+        let content = r#"
+[workspace.dependencies]
+serde = { version = "1", default-features = false }
+tokio = { version = "1.38", default-features = false }
+regex = "1.10.2"
+range = { version = ">=1.2, <2", default-features = false }
+local = { path = "local", default-features = false }
+"#;
+
+        assert!(find_imprecise_versions(content, VersionPrecision::Major).unwrap().is_empty());
+        assert_eq!(
+            messages(&find_imprecise_versions(content, VersionPrecision::MajorMinor).unwrap()),
+            ["'serde': version requirement \"1\" is less precise than the required major.minor"]
+        );
+        assert_eq!(
+            find_imprecise_versions(content, VersionPrecision::MajorMinorPatch).unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_find_version_mismatches() {
+        let workspace = r#"
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+json = { package = "serde_json", version = "1.0.100", default-features = false }
+tokio = { version = "1", default-features = false }
+"#;
+        let member = r#"
+[dependencies]
+serde = { version = "^1.0", default-features = false }
+serde_json = "1.0.80"
+tokio = { workspace = true }
+
+[dev-dependencies]
+rt = { package = "tokio", version = "1.38", default-features = false }
+"#;
+
+        assert_eq!(
+            messages(&find_version_mismatches(member, workspace).unwrap()),
+            [
+                "'serde_json' in [dependencies]: requires version \"1.0.80\", but [workspace.dependencies] requires \"1.0.100\"",
+                "'rt' in [dev-dependencies]: requires version \"1.38\", but [workspace.dependencies] requires \"1\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lowest_version() {
+        assert_eq!(lowest_version("1.2"), Some(Version::new(1, 2, 0)));
+        assert_eq!(lowest_version("^1.0.200"), Some(Version::new(1, 0, 200)));
+        assert_eq!(lowest_version(">=1.2, <2"), Some(Version::new(1, 2, 0)));
+        assert_eq!(lowest_version("~0.4"), Some(Version::new(0, 4, 0)));
+        assert_eq!(lowest_version("<2"), None);
+        assert_eq!(lowest_version("*"), None);
+        assert_eq!(lowest_version("not a version"), None);
+    }
+}
@@ -25,6 +25,17 @@ fn create_test_manifest(content: &str) -> tempfile::TempDir {
     temp_dir
 }
 
+/// Helper to run git in a test repository
+fn run_git(dir: &std::path::Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("Failed to run git");
+    assert!(status.success(), "git {} should succeed", args.join(" "));
+}
+
 #[test]
 fn test_valid_workspace_all_deps_have_default_features_false() {
     let content = r#"
@@ -492,3 +503,2872 @@ anyhow = { version = "1.0" }
     assert!(!stderr.contains("⚠️ Warning: exception 'tokio' was not found in [workspace.dependencies]"));
     assert!(!stderr.contains("⚠️ Warning: exception 'anyhow' was not found in [workspace.dependencies]"));
 }
+
+#[test]
+fn test_repeated_exceptions() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = "1.0"
+clap = "4"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["-e", "serde", "-e", " tokio , clap,", "-e", "serde"])
+        .output()
+        .expect("Failed to execute command");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(!stderr.contains("Warning"), "{stderr}");
+}
+
+#[test]
+fn test_no_cli_exceptions() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+serde = "1.0"
+
+[workspace.metadata.ensure-no-default-features]
+exceptions = ["serde"]
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .arg("--no-cli-exceptions")
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    assert!(run(&[]).status.success());
+
+    for args in [&["-e", "tokio"][..], &["--extend-exceptions", "tokio"]] {
+        let output = run(args);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--no-cli-exceptions"), "{stderr}");
+    }
+}
+
+#[test]
+fn test_follow_path_deps_outside_workspace() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let workspace_dir = temp_dir.path().join("workspace");
+    let sibling_dir = temp_dir.path().join("sibling");
+    fs::create_dir_all(&workspace_dir).expect("Failed to create workspace dir");
+    fs::create_dir_all(&sibling_dir).expect("Failed to create sibling dir");
+
+    fs::write(
+        workspace_dir.join("Cargo.toml"),
+        r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+sibling = { path = "../sibling", default-features = false }
+"#,
+    )
+    .expect("Failed to write workspace Cargo.toml");
+
+    fs::write(
+        sibling_dir.join("Cargo.toml"),
+        r#"
+[package]
+name = "sibling"
+version = "0.1.0"
+
+[dependencies]
+regex = "1.0"
+"#,
+    )
+    .expect("Failed to write sibling Cargo.toml");
+
+    let manifest_path = workspace_dir.join("Cargo.toml");
+
+    // Without the flag, only the workspace manifest is checked
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command should succeed without --follow-path-deps");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--follow-path-deps")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail with --follow-path-deps");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("sibling"));
+    assert!(stderr.contains("'regex'"));
+    assert!(stderr.contains("uses simple version string"));
+}
+
+#[test]
+fn test_vendor_dir_audit() {
+    let content = r#"
+[workspace]
+members = ["crate1"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+tokio = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    let vendor_dir = temp_dir.path().join("vendor");
+
+    for (name, manifest) in [
+        ("serde", "[package]\nname = \"serde\"\nversion = \"1.0.0\"\n"),
+        (
+            "tokio",
+            "[package]\nname = \"tokio\"\nversion = \"1.0.0\"\n\n[dependencies.mio]\nversion = \"1.0\"\n\n[dependencies.libc]\nversion = \"0.2\"\ndefault-features = false\n",
+        ),
+        ("mio", "[package]\nname = \"mio\"\nversion = \"1.0.0\"\n"),
+        ("libc", "[package]\nname = \"libc\"\nversion = \"0.2.0\"\n"),
+    ] {
+        let crate_dir = vendor_dir.join(name);
+        fs::create_dir_all(&crate_dir).expect("Failed to create vendored crate dir");
+        fs::write(crate_dir.join("Cargo.toml"), manifest).expect("Failed to write vendored Cargo.toml");
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--vendor-dir")
+        .arg(&vendor_dir)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Found 1 vendored crates consumed with default features enabled"));
+    assert!(stdout.contains("'mio': by tokio"));
+    assert!(!stdout.contains("'libc'"));
+    assert!(!stdout.contains("'serde'"));
+}
+
+#[test]
+fn test_member_build_dependency_mismatch_warning() {
+    let content = r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    let member_dir = temp_dir.path().join("crates").join("member");
+    fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+    fs::write(
+        member_dir.join("Cargo.toml"),
+        r#"
+[package]
+name = "member"
+version = "0.1.0"
+
+[dependencies]
+serde = { workspace = true }
+
+[build-dependencies]
+serde = { version = "1.0" }
+"#,
+    )
+    .expect("Failed to write member Cargo.toml");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .args(["--disable", "workspace-inheritance"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Mismatches should only produce warnings");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("⚠️ Warning: in member 'member', 'serde' has default features disabled in [dependencies]"));
+}
+
+#[test]
+fn test_recursive_scan_combined_report() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let manifests = [
+        (
+            "good",
+            "[workspace]\nmembers = []\n\n[workspace.dependencies]\nserde = { version = \"1.0\", default-features = false }\n",
+        ),
+        (
+            "nested/bad",
+            "[workspace]\nmembers = []\n\n[workspace.dependencies]\nregex = \"1.0\"\n",
+        ),
+        (
+            "target/ignored",
+            "[workspace]\nmembers = []\n\n[workspace.dependencies]\nignored = \"1.0\"\n",
+        ),
+        ("not-a-workspace", "[package]\nname = \"pkg\"\nversion = \"0.1.0\"\n"),
+    ];
+
+    for (dir, content) in manifests {
+        let dir = temp_dir.path().join(dir);
+        fs::create_dir_all(&dir).expect("Failed to create dir");
+        fs::write(dir.join("Cargo.toml"), content).expect("Failed to write Cargo.toml");
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--recursive")
+        .arg(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 1 dependencies without default-features = false"));
+    assert!(stderr.contains(&format!(
+        "In {}",
+        temp_dir.path().join("nested").join("bad").join("Cargo.toml").display()
+    )));
+    assert!(stderr.contains("'regex'"));
+    assert!(!stderr.contains("'ignored'"));
+}
+
+#[test]
+fn test_recursive_scan_defaults_to_current_directory() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--recursive")
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✅ All required workspace dependencies have default-features = false"));
+}
+
+#[test]
+fn test_recursive_scan_respects_gitignore_and_excludes() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let bad_workspace = "[workspace]\nmembers = []\n\n[workspace.dependencies]\nregex = \"1.0\"\n";
+    for dir in ["ignored-by-git", "fixtures/bad", "checked"] {
+        let dir = temp_dir.path().join(dir);
+        fs::create_dir_all(&dir).expect("Failed to create dir");
+        fs::write(dir.join("Cargo.toml"), bad_workspace).expect("Failed to write Cargo.toml");
+    }
+
+    fs::write(temp_dir.path().join(".gitignore"), "ignored-by-git/\n").expect("Failed to write .gitignore");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--recursive")
+        .arg(temp_dir.path())
+        .arg("--exclude")
+        .arg("fixtures")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 1 dependencies without default-features = false"));
+    assert!(stderr.contains("checked"));
+    assert!(!stderr.contains("ignored-by-git"));
+    assert!(!stderr.contains("fixtures"));
+}
+
+#[test]
+fn test_member_renamed_dependency_bypass() {
+    let content = r#"
+[workspace]
+members = ["member"]
+
+[workspace.dependencies]
+tokio = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    let member_dir = temp_dir.path().join("member");
+    fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+    fs::write(
+        member_dir.join("Cargo.toml"),
+        r#"
+[package]
+name = "member"
+version = "0.1.0"
+
+[dependencies]
+tokio = { package = "tokio", version = "1" }
+"#,
+    )
+    .expect("Failed to write member Cargo.toml");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 1 member dependencies bypassing [workspace.dependencies]"));
+    assert!(stderr.contains("use `tokio = { workspace = true }` instead"));
+}
+
+#[test]
+fn test_multiple_manifest_paths() {
+    let good = create_test_manifest(
+        r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#,
+    );
+    let bad = create_test_manifest(
+        r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+regex = "1.0"
+"#,
+    );
+    let other_bad = create_test_manifest(
+        r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+tokio = { version = "1.0" }
+"#,
+    );
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(good.path().join("Cargo.toml"))
+        .arg("--manifest-path")
+        .arg(bad.path().join("Cargo.toml"))
+        .arg(other_bad.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 2 dependencies without default-features = false"));
+    assert!(stderr.contains(&format!("In {}", bad.path().join("Cargo.toml").display())));
+    assert!(stderr.contains(&format!("In {}", other_bad.path().join("Cargo.toml").display())));
+    assert!(stderr.contains("'regex'"));
+    assert!(stderr.contains("'tokio'"));
+}
+
+#[test]
+fn test_manifest_path_directory() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Failed to read"));
+    assert!(stderr.contains("'serde'"));
+}
+
+#[test]
+fn test_check_path_defaults() {
+    let content = r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+core-lib = { path = "crates/core-lib", version = "0.1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    for (name, manifest) in [
+        (
+            "core-lib",
+            "[package]\nname = \"core-lib\"\nversion = \"0.1.0\"\n\n[features]\ndefault = [\"std\"]\nstd = []\n",
+        ),
+        (
+            "app",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\ncore-lib = { workspace = true, default-features = true }\n",
+        ),
+        (
+            "lean",
+            "[package]\nname = \"lean\"\nversion = \"0.1.0\"\n\n[dependencies]\ncore-lib = { workspace = true }\n",
+        ),
+    ] {
+        let member_dir = temp_dir.path().join("crates").join(name);
+        fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+        fs::write(member_dir.join("Cargo.toml"), manifest).expect("Failed to write member Cargo.toml");
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .args(["--disable", "member-default-overrides"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command should succeed");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("'core-lib'"), "No warning without --check-path-defaults");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--check-path-defaults")
+        .args(["--disable", "member-default-overrides"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command should succeed");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("⚠️ Warning: 'core-lib' at crates/core-lib has default features [std] which are enabled by member 'app'"));
+}
+
+#[test]
+fn test_discovers_workspace_root_from_member_directory() {
+    let content = r#"
+[workspace]
+members = ["member"]
+
+[workspace.dependencies]
+serde = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let member_dir = temp_dir.path().join("member");
+    fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+    fs::write(
+        member_dir.join("Cargo.toml"),
+        "[package]\nname = \"member\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { workspace = true }\n",
+    )
+    .expect("Failed to write member Cargo.toml");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .current_dir(&member_dir)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("No [workspace] section found"));
+    assert!(stderr.contains("'serde'"));
+    assert!(stderr.contains("uses simple version string"));
+}
+
+#[test]
+fn test_deadline_exceeded_truncates_report() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--deadline")
+        .arg("0s")
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(3), "Command should exit with the deadline status");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Deadline exceeded: this report is truncated, only 0 of 1 workspaces were fully checked"));
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--deadline")
+        .arg("1h")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command should succeed within the deadline");
+}
+
+#[test]
+fn test_deadline_invalid_value() {
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--deadline")
+        .arg("soon")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid duration"));
+}
+
+#[test]
+fn test_inconsistent_aliases_warning() {
+    let content = r#"
+[workspace]
+members = ["a", "b"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    for (name, deps) in [("a", "bytes = \"1.0\""), ("b", "buf = { package = \"bytes\", version = \"1.0\" }")] {
+        let member_dir = temp_dir.path().join(name);
+        fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+        fs::write(
+            member_dir.join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n\n[dev-dependencies]\n{deps}\n"),
+        )
+        .expect("Failed to write member Cargo.toml");
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command should succeed");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("⚠️ Warning: package 'bytes' is imported under inconsistent names: 'buf' (in b), 'bytes' (in a)"));
+}
+
+#[test]
+fn test_violation_budgets() {
+    let content = r#"
+[workspace]
+members = ["services/*", "tools/*"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    for dir in ["services/payments", "tools/lint"] {
+        let member_dir = temp_dir.path().join(dir);
+        fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+        fs::write(
+            member_dir.join("Cargo.toml"),
+            "[package]\nname = \"member\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { package = \"serde\", version = \"1.0\" }\n",
+        )
+        .expect("Failed to write member Cargo.toml");
+    }
+
+    let run = |budgets: &str| {
+        fs::write(temp_dir.path().join("ensure-no-default-features.toml"), budgets).expect("Failed to write config");
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run("max-violations = { \"services/payments\" = 1, \"*\" = 10 }\n");
+    assert!(output.status.success(), "Violations within budget should be tolerated");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("All violations are within the configured budgets"));
+
+    let output = run("max-violations = { \"services/payments\" = 0, \"*\" = 10 }\n");
+    assert_eq!(output.status.code(), Some(4), "Exceeded budget should use its own exit status");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'services/payments': 1 of 0 allowed (exceeded)"));
+    assert!(stderr.contains("'*': 1 of 10 allowed (ok)"));
+
+    let output = run("max-violations = { \"services/payments\" = 1 }\n");
+    assert_eq!(output.status.code(), Some(1), "Violations outside any budget should fail normally");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 1 violations not covered by any budget"));
+}
+
+#[test]
+fn test_lsp_publishes_diagnostics() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    fn frame(body: &str) -> String {
+        format!("Content-Length: {}\r\n\r\n{body}", body.len())
+    }
+
+    let text = r#"[workspace.dependencies]\nserde = \"1.0\"\n"#;
+    let input = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#.to_string(),
+        format!(
+            r#"{{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{{"textDocument":{{"uri":"file:///ws/Cargo.toml","languageId":"toml","version":1,"text":"{text}"}}}}}}"#
+        ),
+        r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#.to_string(),
+        r#"{"jsonrpc":"2.0","method":"exit"}"#.to_string(),
+    ]
+    .iter()
+    .map(|body| frame(body))
+    .collect::<String>();
+
+    let mut child = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child
+        .stdin
+        .take()
+        .expect("Failed to open stdin")
+        .write_all(input.as_bytes())
+        .expect("Failed to write to stdin");
+    let output = child.wait_with_output().expect("Failed to wait for command");
+
+    assert!(output.status.success(), "Server should exit cleanly");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(r#""codeActionProvider":true"#));
+    assert!(stdout.contains("textDocument/publishDiagnostics"));
+    assert!(stdout.contains("'serde': uses simple version string"));
+    assert!(stdout.contains(r#""range":{"end":{"character":5,"line":1},"start":{"character":0,"line":1}}"#));
+}
+
+#[test]
+fn test_daemon_answers_client_checks() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let temp_dir = create_test_manifest("[workspace]\n\n[workspace.dependencies]\nserde = \"1.0\"\n");
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("Failed to find a free port")
+        .to_string();
+
+    let mut daemon = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--daemon")
+        .arg(&addr)
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start daemon");
+    let mut banner = String::new();
+    let _ = BufReader::new(daemon.stderr.as_mut().expect("Failed to open stderr"))
+        .read_line(&mut banner)
+        .expect("Failed to read daemon output");
+    assert!(banner.contains("Listening for check requests"));
+
+    let check = || {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--connect")
+            .arg(&addr)
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = check();
+    assert_eq!(output.status.code(), Some(1), "Violations should be relayed by the client");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'serde': uses simple version string"));
+
+    fs::write(
+        &manifest_path,
+        "[workspace]\n\n[workspace.dependencies]\nserde = { version = \"1.0\", default-features = false }\n",
+    )
+    .expect("Failed to update Cargo.toml");
+
+    let output = check();
+    let refused = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--connect")
+            .arg(&addr)
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+    let fix = refused(&["--fix"]);
+    let packaged = refused(&["--check-packaged"]);
+    let enabled_packaging = refused(&["--enable", "ENDF004"]);
+    let _ = daemon.kill();
+    let _ = daemon.wait();
+
+    assert!(output.status.success(), "Edits should be picked up by the daemon");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✅ All required workspace dependencies have default-features = false"));
+
+    assert!(!fix.status.success(), "The daemon should refuse to rewrite manifests");
+    assert!(String::from_utf8_lossy(&fix.stderr).contains("The daemon doesn't accept --fix"));
+    assert!(String::from_utf8_lossy(&packaged.stderr).contains("The daemon doesn't accept --check-packaged"));
+    assert!(!enabled_packaging.status.success(), "The daemon should refuse to package members");
+    assert!(String::from_utf8_lossy(&enabled_packaging.stderr).contains("Rule ENDF004 packages the members"));
+}
+
+#[test]
+fn test_daemon_refuses_non_loopback_addresses() {
+    let output = Command::new(get_binary_path())
+        .args(["ensure-no-default-features", "--daemon", "0.0.0.0:0"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("only listens on loopback addresses"), "{stderr}");
+}
+
+#[test]
+fn test_files_checks_owning_workspaces_only() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    for (dir, dependency) in [("alpha", "serde = \"1.0\""), ("beta", "regex = \"1.0\"")] {
+        let member_dir = temp_dir.path().join(dir).join("member");
+        fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+        fs::write(
+            temp_dir.path().join(dir).join("Cargo.toml"),
+            format!("[workspace]\nmembers = [\"member\"]\n\n[workspace.dependencies]\n{dependency}\n"),
+        )
+        .expect("Failed to write workspace Cargo.toml");
+        fs::write(member_dir.join("Cargo.toml"), "[package]\nname = \"member\"\nversion = \"0.1.0\"\n")
+            .expect("Failed to write member Cargo.toml");
+        fs::write(member_dir.join("lib.rs"), "").expect("Failed to write source file");
+    }
+
+    let run = |files: &[&str]| {
+        Command::new(get_binary_path())
+            .current_dir(temp_dir.path())
+            .arg("ensure-no-default-features")
+            .arg("--files")
+            .args(files)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&[
+        "alpha/member/Cargo.toml",
+        "alpha/Cargo.toml",
+        "beta/member/lib.rs",
+        "gone/Cargo.toml",
+    ]);
+    assert!(!output.status.success(), "Command should fail for the changed workspace");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 1 dependencies without default-features = false"));
+    assert!(stderr.contains("'serde'"));
+    assert!(!stderr.contains("'regex'"));
+
+    let output = run(&["alpha/member/lib.rs", "beta/member/lib.rs"]);
+    assert!(output.status.success(), "Command should succeed when no manifest changed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✅ No workspaces found to check"));
+}
+
+#[test]
+fn test_install_hook() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    run_git(temp_dir.path(), &["init", "--quiet"]);
+
+    let install = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .current_dir(temp_dir.path())
+            .arg("ensure-no-default-features")
+            .arg("install-hook")
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = install(&["--", "--exceptions", "serde"]);
+    assert!(output.status.success(), "Installing the hook should succeed");
+    let hook_path = temp_dir.path().join(".git/hooks/pre-commit");
+    let hook = fs::read_to_string(&hook_path).expect("Failed to read hook");
+    assert!(hook.contains("exec cargo ensure-no-default-features --exceptions serde --files $files"));
+
+    let output = install(&["--hook", "pre-push"]);
+    assert!(output.status.success(), "Installing the pre-push hook should succeed");
+    let hook = fs::read_to_string(temp_dir.path().join(".git/hooks/pre-push")).expect("Failed to read hook");
+    assert!(hook.ends_with("exec cargo ensure-no-default-features\n"));
+
+    let output = install(&[]);
+    assert!(output.status.success(), "Reinstalling the tool's own hook should succeed");
+
+    fs::write(&hook_path, "#!/bin/sh\nexit 0\n").expect("Failed to write hook");
+    let output = install(&[]);
+    assert!(!output.status.success(), "An existing hook should not be overwritten");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("use --force to overwrite it"));
+
+    let output = install(&["--force"]);
+    assert!(output.status.success(), "--force should overwrite an existing hook");
+    let hook = fs::read_to_string(&hook_path).expect("Failed to read hook");
+    assert!(hook.contains("--files $files"));
+}
+
+#[test]
+fn test_changed_since_reports_new_violations_only() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    for dir in ["alpha", "beta"] {
+        fs::create_dir_all(temp_dir.path().join(dir)).expect("Failed to create workspace dir");
+        fs::write(
+            temp_dir.path().join(dir).join("Cargo.toml"),
+            "[workspace]\n\n[workspace.dependencies]\nserde = \"1.0\"\n",
+        )
+        .expect("Failed to write Cargo.toml");
+    }
+
+    run_git(temp_dir.path(), &["init", "--quiet"]);
+    run_git(temp_dir.path(), &["add", "."]);
+    run_git(temp_dir.path(), &["commit", "--quiet", "-m", "initial"]);
+
+    let run = || {
+        Command::new(get_binary_path())
+            .current_dir(temp_dir.path())
+            .arg("ensure-no-default-features")
+            .arg("--changed-since")
+            .arg("HEAD")
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run();
+    assert!(output.status.success(), "Nothing changed, so nothing should be checked");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✅ No workspaces found to check"));
+
+    fs::write(
+        temp_dir.path().join("alpha/Cargo.toml"),
+        "[workspace]\n\n[workspace.dependencies]\nserde = \"1.0\"\nregex = \"1.0\"\n",
+    )
+    .expect("Failed to update Cargo.toml");
+
+    let output = run();
+    assert!(!output.status.success(), "The new violation should be reported");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 1 dependencies without default-features = false"));
+    assert!(stderr.contains("'regex'"));
+    assert!(!stderr.contains("'serde'"));
+
+    fs::write(
+        temp_dir.path().join("alpha/Cargo.toml"),
+        "[workspace]\n\n[workspace.dependencies]\nserde = \"1.0\"\nregex = { version = \"1.0\", default-features = false }\n",
+    )
+    .expect("Failed to update Cargo.toml");
+
+    let output = run();
+    assert!(output.status.success(), "Pre-existing violations should be tolerated");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✅ All required workspace dependencies have default-features = false"));
+
+    // A workspace created since, which git doesn't track yet, is checked too
+    fs::create_dir_all(temp_dir.path().join("gamma")).expect("Failed to create workspace dir");
+    fs::write(
+        temp_dir.path().join("gamma/Cargo.toml"),
+        "[workspace]\n\n[workspace.dependencies]\ntokio = \"1.0\"\n",
+    )
+    .expect("Failed to write Cargo.toml");
+
+    let output = run();
+    assert!(!output.status.success(), "The untracked workspace should be checked");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'tokio'"), "{stderr}");
+    assert!(!stderr.contains("'serde'"), "{stderr}");
+}
+
+#[test]
+fn test_changed_since_ignores_preexisting_findings_of_every_rule() {
+    let temp_dir = create_test_manifest(
+        r#"
+[workspace]
+members = ["member"]
+
+[workspace.dependencies]
+log = { version = "0.4", default-features = false }
+regex = { version = "1.0", default-features = false }
+serde = { version = "1.0", default-features = false }
+"#,
+    );
+    let member_dir = temp_dir.path().join("member");
+    fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+    let member = r#"
+[package]
+name = "member"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+log = { workspace = true }
+regex = { workspace = true, features = ["default"] }
+serde = "1.0"
+"#;
+    fs::write(member_dir.join("Cargo.toml"), member).expect("Failed to write member Cargo.toml");
+
+    run_git(temp_dir.path(), &["init", "--quiet"]);
+    run_git(temp_dir.path(), &["add", "."]);
+    run_git(temp_dir.path(), &["commit", "--quiet", "-m", "initial"]);
+
+    // The member's regex entry (ENDF009) and serde entry (ENDF013) were already there, its log entry is new
+    fs::write(
+        member_dir.join("Cargo.toml"),
+        member.replace("log = { workspace = true }", "log = { workspace = true, features = [\"default\"] }"),
+    )
+    .expect("Failed to update member Cargo.toml");
+
+    let output = Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["ensure-no-default-features", "--changed-since", "HEAD"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "The new violation should be reported");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("[ENDF009]"), "{stderr}");
+    assert!(stderr.contains("'log'"), "{stderr}");
+    assert!(!stderr.contains("'regex'"), "{stderr}");
+    assert!(!stderr.contains("[ENDF013]"), "{stderr}");
+}
+
+#[test]
+fn test_diff_between_files_and_revisions() {
+    let temp_dir = create_test_manifest(
+        "[workspace]\n\n[workspace.dependencies]\nserde = \"1.0\"\nregex = { version = \"1.0\", default-features = false }\n",
+    );
+    let old_path = temp_dir.path().join("old.toml");
+    let _ = fs::copy(temp_dir.path().join("Cargo.toml"), &old_path).expect("Failed to copy Cargo.toml");
+
+    run_git(temp_dir.path(), &["init", "--quiet"]);
+    run_git(temp_dir.path(), &["add", "Cargo.toml"]);
+    run_git(temp_dir.path(), &["commit", "--quiet", "-m", "initial"]);
+
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        "[workspace]\n\n[workspace.dependencies]\nserde = { version = \"1.0\", default-features = false }\nregex = \"1.0\"\n",
+    )
+    .expect("Failed to update Cargo.toml");
+
+    let diff = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .current_dir(temp_dir.path())
+            .arg("ensure-no-default-features")
+            .arg("diff")
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = diff(&["HEAD"]);
+    assert!(!output.status.success(), "A regression should fail the comparison");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("Found 1 improvements between HEAD and the working tree"));
+    assert!(stdout.contains("'serde': no longer enables default features"));
+    assert!(stderr.contains("Found 1 regressions between HEAD and the working tree"));
+    assert!(stderr.contains("'regex': uses simple version string"));
+
+    let output = diff(&["Cargo.toml", "old.toml"]);
+    assert!(!output.status.success(), "Comparing files should also find the regression");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'serde': uses simple version string"));
+
+    let output = diff(&["HEAD", "old.toml"]);
+    assert!(output.status.success(), "Identical versions have no regressions");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✅ No regressions between HEAD and old.toml"));
+}
+
+#[test]
+fn test_check_packaged_members() {
+    let content = r#"
+[workspace]
+members = ["app", "util", "internal"]
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+edition = "2021"
+license = "MIT"
+description = "test"
+
+[workspace.dependencies]
+util = { path = "util", version = "0.1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    for (name, extra) in [
+        ("app", "[dependencies]\nutil = { workspace = true, default-features = true }\n"),
+        ("util", ""),
+        (
+            "internal",
+            "publish = false\n\n[dependencies]\nutil = { workspace = true, default-features = true }\n",
+        ),
+    ] {
+        let member_dir = temp_dir.path().join(name);
+        fs::create_dir_all(member_dir.join("src")).expect("Failed to create member dir");
+        fs::write(member_dir.join("src/lib.rs"), "").expect("Failed to write source file");
+        fs::write(
+            member_dir.join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\nversion.workspace = true\nedition.workspace = true\nlicense.workspace = true\ndescription.workspace = true\n{extra}"
+            ),
+        )
+        .expect("Failed to write member Cargo.toml");
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--check-packaged")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "The published manifest should enable default features");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("app-0.1.0.crate"));
+    assert!(stderr.contains("'util': has default-features = true (must be false)"));
+    assert!(!stderr.contains("internal-0.1.0.crate"));
+}
+
+#[test]
+fn test_check_lockfile_reports_leaked_defaults() {
+    let content = r#"
+[workspace]
+members = ["app", "other", "util"]
+resolver = "2"
+
+[workspace.dependencies]
+util = { path = "util", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    for (name, extra) in [
+        ("app", "[dependencies]\nutil = { workspace = true }\n"),
+        ("other", "[dependencies]\nutil = { path = \"../util\" }\n"),
+        ("util", "[features]\ndefault = [\"std\"]\nstd = []\n"),
+    ] {
+        let member_dir = temp_dir.path().join(name);
+        fs::create_dir_all(member_dir.join("src")).expect("Failed to create member dir");
+        fs::write(member_dir.join("src/lib.rs"), "").expect("Failed to write source file");
+        fs::write(
+            member_dir.join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n{extra}"),
+        )
+        .expect("Failed to write member Cargo.toml");
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--check-lockfile")
+        .args(["--disable", "workspace-inheritance"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Leaked defaults are only a warning");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(
+        "'util' is declared with default-features = false, but the resolved dependency graph enables the default features of util 0.1.0 through other 0.1.0"
+    ));
+}
+
+#[test]
+fn test_rule_severities_and_toggles() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("❌ Found 1 dependencies without default-features = false [ENDF001]:"));
+
+    let output = run(&["--warn", "ENDF001"]);
+    assert!(output.status.success(), "Downgraded findings should not fail the check");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("⚠️ Warning: 'serde': uses simple version string, should be a table with default-features = false [ENDF001]"));
+
+    let output = run(&["--disable", "default-features"]);
+    assert!(output.status.success(), "Disabled rules should not report anything");
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("serde"));
+
+    let output = run(&["--enable", "ENDF999"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown rule 'ENDF999'"));
+
+    fs::write(
+        temp_dir.path().join("ensure-no-default-features.toml"),
+        "disable-rules = [\"ENDF001\"]\n",
+    )
+    .expect("Failed to write config");
+    let output = run(&[]);
+    assert!(output.status.success(), "Rules disabled in the configuration should not run");
+
+    let output = run(&["--deny", "ENDF001"]);
+    assert!(
+        !output.status.success(),
+        "Command-line overrides take precedence over the configuration"
+    );
+}
+
+#[test]
+fn test_list_rules() {
+    let content = "
+[workspace]
+members = []
+";
+
+    let temp_dir = create_test_manifest(content);
+    fs::write(
+        temp_dir.path().join("ensure-no-default-features.toml"),
+        "enable-rules = [\"path-defaults\"]\n",
+    )
+    .expect("Failed to write config");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .arg("--list-rules")
+        .args(["--disable", "ENDF006"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = |id: &str| {
+        stdout
+            .lines()
+            .find(|line| line.starts_with(id))
+            .expect("Every rule should be listed")
+            .to_string()
+    };
+    assert!(line("ENDF001").contains("error    enabled (error)"));
+    assert!(line("ENDF003").contains("disabled"));
+    assert!(line("ENDF006").contains("disabled"));
+    assert!(line("ENDF007").contains("warning  enabled (warning)"));
+}
+
+#[test]
+fn test_explain_rule() {
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .args(["--explain", "endf001"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("ENDF001 (default-features): "));
+    assert!(stdout.contains("Default severity: error, enabled by default"));
+    assert!(stdout.contains("serde = { version = \"1.0\", default-features = false }"));
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .args(["--explain", "ENDF999"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_default_in_features_is_reported() {
+    let content = r#"
+[workspace]
+members = ["member"]
+
+[workspace.dependencies]
+tokio = { version = "1.0", default-features = false, features = ["default", "rt"] }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let member_dir = temp_dir.path().join("member");
+    fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+    fs::write(
+        member_dir.join("Cargo.toml"),
+        "[package]\nname = \"member\"\nversion = \"0.1.0\"\n\n[dependencies]\ntokio = { workspace = true, features = [\"default\"] }\n",
+    )
+    .expect("Failed to write member Cargo.toml");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("❌ Found 2 dependencies re-enabling default features through their features [ENDF009]:"));
+    assert!(stderr.contains("'tokio' in [workspace.dependencies]: lists \"default\" in features"));
+    assert!(stderr.contains("'tokio' in [dependencies]: lists \"default\" in features"));
+}
+
+#[test]
+fn test_fix_rewrites_manifests() {
+    let content = r#"
+[workspace]
+members = ["member"]
+
+[workspace.dependencies]
+serde = "1.0" # serialization
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    let member_manifest = temp_dir.path().join("member").join("Cargo.toml");
+    fs::create_dir_all(temp_dir.path().join("member")).expect("Failed to create member dir");
+    fs::write(
+        &member_manifest,
+        "[package]\nname = \"member\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] }\n",
+    )
+    .expect("Failed to write member Cargo.toml");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("[ENDF013]:"));
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--fix")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Fixed manifests should pass the check");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("🔧 Applied 2 fixes:"));
+    assert!(stdout.contains("set default-features = false for 'serde'"));
+    assert!(stdout.contains("'serde' in [dependencies] now inherits from [workspace.dependencies]"));
+
+    let fixed = fs::read_to_string(&manifest_path).expect("Failed to read Cargo.toml");
+    assert!(fixed.contains("serde = { version = \"1.0\", default-features = false } # serialization"));
+    let fixed = fs::read_to_string(&member_manifest).expect("Failed to read member Cargo.toml");
+    assert!(fixed.contains("serde = { workspace = true, features = [\"derive\"] }"));
+}
+
+#[test]
+fn test_no_std_members() {
+    let content = r#"
+[workspace]
+members = ["member"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false, features = ["std"] }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let member_dir = temp_dir.path().join("member");
+    fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+    fs::write(
+        member_dir.join("Cargo.toml"),
+        "[package]\nname = \"member\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { workspace = true }\n",
+    )
+    .expect("Failed to write member Cargo.toml");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Members aren't no_std unless designated");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .arg("--no-std")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("❌ Found 1 dependencies enabling std features in no_std members [ENDF027]:"));
+    assert!(stderr.contains("'serde' in [dependencies]: enables \"std\" through [workspace.dependencies] in a no_std member"));
+}
+
+#[test]
+fn test_per_rule_exceptions() {
+    let content = r#"
+[workspace]
+members = ["member"]
+
+[workspace.dependencies]
+serde = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let member_dir = temp_dir.path().join("member");
+    fs::create_dir_all(&member_dir).expect("Failed to create member dir");
+    fs::write(
+        member_dir.join("Cargo.toml"),
+        "[package]\nname = \"member\"\nversion = \"0.1.0\"\n\n[features]\nfull = [\"serde/default\"]\n\n[dependencies]\nserde = { workspace = true }\n",
+    )
+    .expect("Failed to write member Cargo.toml");
+
+    let config_path = temp_dir.path().join("ensure-no-default-features.toml");
+    let run = || {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    fs::write(&config_path, "[exceptions]\ndefault-features = [\"serde\"]\n").expect("Failed to write config");
+    let output = run();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("[ENDF001]"), "serde is excluded from ENDF001");
+    assert!(stderr.contains("[ENDF010]"), "serde is still checked by ENDF010");
+
+    fs::write(
+        &config_path,
+        "[exceptions]\ndefault-features = [\"serde\"]\nENDF010 = [\"serde\"]\n",
+    )
+    .expect("Failed to write config");
+    let output = run();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    fs::write(&config_path, "[exceptions]\nENDF999 = [\"serde\"]\n").expect("Failed to write config");
+    let output = run();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid exceptions setting"));
+}
+
+#[test]
+fn test_metadata_table_configuration() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = "1.0"
+
+[workspace.metadata.ensure-no-default-features]
+exceptions = { "*" = ["serde"] }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = || {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("serde"), "serde is excluded through the metadata table");
+    assert!(stderr.contains("tokio"));
+
+    // A configuration file takes precedence over the metadata table
+    fs::write(
+        temp_dir.path().join("ensure-no-default-features.toml"),
+        "[exceptions]\n\"*\" = [\"tokio\"]\n",
+    )
+    .expect("Failed to write config");
+    let output = run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("serde"),
+        "the metadata table is ignored when a configuration file exists"
+    );
+    assert!(!stderr.contains("tokio"));
+
+    fs::remove_file(temp_dir.path().join("ensure-no-default-features.toml")).expect("Failed to remove config");
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        content.replace("exceptions = { \"*\" = [\"serde\"] }", "exceptions = \"serde\""),
+    )
+    .expect("Failed to write Cargo.toml");
+    let output = run();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Invalid [workspace.metadata.ensure-no-default-features] table"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_exceptions_replace_or_extend_the_configuration() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = "1.0"
+
+[workspace.metadata.ensure-no-default-features]
+exceptions = { "*" = ["serde"] }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&["--extend-exceptions", "tokio"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = run(&["--exceptions", "tokio"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("serde"), "--exceptions replaces the configured exceptions");
+    assert!(!stderr.contains("tokio"));
+}
+
+#[test]
+fn test_exception_reasons() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = "1.0"
+openssl = "0.10"
+
+[workspace.metadata.ensure-no-default-features.exceptions]
+default-features = [
+    { crate = "serde", reason = "Needs std for now" },
+    "tokio",
+    { crate = "openssl", reason = "Vendored builds", owner = "@platform-team" },
+]
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("📝 Exception 'serde' for 'default-features': Needs std for now\n"));
+    assert!(stdout.contains("📝 Exception 'openssl' for 'default-features': Vendored builds (owner: @platform-team)"));
+    assert!(!stdout.contains("'tokio'"));
+
+    let output = run(&["--require-exception-reasons"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Exception 'tokio' for 'default-features' has no reason"));
+}
+
+#[test]
+fn test_exception_expiry() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = "1.0"
+
+[workspace.metadata.ensure-no-default-features.exceptions]
+default-features = [{ crate = "serde", expires = "2000-01-01", owner = "@core" }, { crate = "tokio", expires = "9999-12-31" }]
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("⚠️ Warning: exception 'serde' for 'default-features' expired on 2000-01-01 (owner: @core)"));
+    assert!(stderr.contains("'serde'"));
+    assert!(!stderr.contains("'tokio'"));
+}
+
+#[test]
+fn test_strict_exceptions() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&["--strict-exceptions", "--exceptions", "serde"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = run(&["--strict-exceptions", "--exceptions", "serde,nonexistent-dep"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("❌ Exception 'nonexistent-dep' was not found in [workspace.dependencies]"));
+
+    let obsolete = "'tokio' is obsolete, the dependencies it covers already have default-features = false";
+    let output = run(&["--exceptions", "serde,tokio"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!("⚠️ Warning: exception {obsolete}")));
+
+    let output = run(&["--strict-exceptions", "--exceptions", "serde,tokio"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!("❌ Exception {obsolete}")));
+}
+
+#[test]
+fn test_member_exceptions() {
+    let content = r#"
+[workspace]
+members = ["cli", "core"]
+
+[workspace.dependencies]
+clap = { version = "4", default-features = false }
+
+[workspace.metadata.ensure-no-default-features]
+exceptions = [{ crate = "clap", members = ["cli"] }]
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    for member in ["cli", "core"] {
+        fs::create_dir_all(temp_dir.path().join(member).join("src")).expect("Failed to create member");
+        fs::write(temp_dir.path().join(member).join("src/lib.rs"), "").expect("Failed to write lib.rs");
+        fs::write(
+            temp_dir.path().join(member).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{member}\"\nversion = \"0.1.0\"\n\n[dependencies]\nclap = {{ workspace = true, default-features = true }}\n"
+            ),
+        )
+        .expect("Failed to write member Cargo.toml");
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("In {}", temp_dir.path().join("core").join("Cargo.toml").display())),
+        "{stderr}"
+    );
+    assert!(
+        !stderr.contains(&format!("In {}", temp_dir.path().join("cli").join("Cargo.toml").display())),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_kind_exceptions() {
+    let content = r#"
+[workspace]
+members = ["bench", "core"]
+
+[workspace.dependencies]
+criterion = { version = "0.5", default-features = false }
+
+[workspace.metadata.ensure-no-default-features]
+exceptions = [{ crate = "criterion", kinds = ["dev"] }]
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    for (member, section) in [("bench", "dev-dependencies"), ("core", "dependencies")] {
+        fs::create_dir_all(temp_dir.path().join(member).join("src")).expect("Failed to create member");
+        fs::write(temp_dir.path().join(member).join("src/lib.rs"), "").expect("Failed to write lib.rs");
+        fs::write(
+            temp_dir.path().join(member).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{member}\"\nversion = \"0.1.0\"\n\n[{section}]\ncriterion = {{ workspace = true, default-features = true }}\n"
+            ),
+        )
+        .expect("Failed to write member Cargo.toml");
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'criterion' in [dependencies]"), "{stderr}");
+    assert!(!stderr.contains("'criterion' in [dev-dependencies]"), "{stderr}");
+}
+
+#[test]
+fn test_suppression_comments() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+# endf: allow(default-features) reason="needs std"
+serde = "1.0"
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("'serde'"), "{stderr}");
+    assert!(stderr.contains("'tokio'"));
+}
+
+#[test]
+fn test_emit_exceptions() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+tokio = "1.0"
+serde = "1.0"
+regex = { version = "1", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let expected = "[exceptions]\ndefault-features = [\n    \"serde\",\n    \"tokio\",\n]\n";
+    let output = run(&["--emit-exceptions"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+
+    let config_path = temp_dir.path().join("ensure-no-default-features.toml");
+    let output = run(&["--emit-exceptions", config_path.to_str().expect("Non-UTF-8 path")]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(&config_path).expect("Failed to read exceptions"), expected);
+
+    let output = run(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn test_baseline() {
+    let temp_dir = create_test_manifest("[workspace]\nmembers = []\n\n[workspace.dependencies]\nserde = \"1.0\"\ntokio = \"1.0\"\n");
+    let baseline_path = temp_dir.path().join("baseline.json");
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&["--write-baseline", baseline_path.to_str().expect("Non-UTF-8 path")]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("with 2 violation(s)"));
+
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        "[workspace]\nmembers = []\n\n[workspace.dependencies]\nserde = { version = \"1.0\", default-features = false }\ntokio = \"1.0\"\nclap = \"4\"\n",
+    )
+    .expect("Failed to write Cargo.toml");
+
+    let output = run(&["--baseline", baseline_path.to_str().expect("Non-UTF-8 path")]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("📊 Compared to the baseline: 1 fixed, 1 new, 1 remaining"));
+}
+
+#[test]
+fn test_init() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .args(args)
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&[]);
+    assert!(!output.status.success());
+
+    let output = run(&["init"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("with 1 exception(s)"));
+    assert!(temp_dir.path().join("ensure-no-default-features.toml").is_file());
+
+    let output = run(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = run(&["init"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+}
+
+#[test]
+fn test_presets() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+tokio = { version = "1.0", default-features = false }
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    // The unsorted dependencies only fail the strict preset
+    let output = run(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = run(&["--preset", "strict"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[ENDF017]"));
+
+    fs::write(temp_dir.path().join("ensure-no-default-features.toml"), "preset = \"strict\"\n").expect("Failed to write config");
+    let output = run(&[]);
+    assert!(!output.status.success());
+
+    let output = run(&["--preset", "minimal"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn test_environment_variables() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .args(args)
+            .env("ENDF_MANIFEST_PATH", temp_dir.path().join("Cargo.toml"))
+            .env("ENDF_EXCEPTIONS", "serde")
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // The command line takes precedence over the environment
+    let output = run(&["--exceptions", "tokio"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("serde"));
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .env("ENDF_MANIFEST_PATH", temp_dir.path().join("Cargo.toml"))
+        .env("ENDF_FORMAT", "json")
+        .output()
+        .expect("Failed to execute command");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("ENDF_FORMAT should select the JSON report");
+    assert_eq!(report["findings"][0]["dependency"], "serde");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--emit-exceptions")
+        .env("ENDF_MANIFEST_PATH", temp_dir.path().join("Cargo.toml"))
+        .env("ENDF_FORMAT", "json")
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // The manifest path of the environment doesn't get in the way of searching for workspaces
+    let other_dir = create_test_manifest("[workspace]\nmembers = []\n\n[workspace.dependencies]\ntokio = \"1.0\"\n");
+    let output = run(&["--recursive", other_dir.path().to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'tokio'") && !stderr.contains("'serde'"), "{stderr}");
+}
+
+#[test]
+fn test_exceptions_file() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let exceptions_path = temp_dir.path().join("exceptions.txt");
+    fs::write(&exceptions_path, "# Needs std\nserde\n").expect("Failed to write exceptions");
+
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .arg("--exceptions-file")
+            .arg(&exceptions_path)
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("serde"));
+    assert!(stderr.contains("tokio"));
+
+    let output = run(&["--exceptions", "tokio"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn test_regex_exceptions() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+winapi = "0.3"
+winapi-util = "0.1"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let run = |exceptions: &str| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .arg("--exceptions")
+            .arg(exceptions)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run("regex:^winapi(-.*)?$");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = run("regex:^winapi(");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid regular expression in exception 'regex:^winapi('"));
+}
+
+#[test]
+fn test_version_constrained_exceptions() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    let run = |serde_version: &str| {
+        fs::write(
+            &manifest_path,
+            format!("[workspace]\nmembers = []\n\n[workspace.dependencies]\nserde = \"{serde_version}\"\n"),
+        )
+        .expect("Failed to write Cargo.toml");
+
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .arg("--exceptions")
+            .arg("serde@^1.0")
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run("1.0.200");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = run("2.0");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'serde'"));
+}
+
+#[test]
+fn test_cargo_deny_exceptions() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = "1.0"
+
+[workspace.metadata.ensure-no-default-features]
+cargo-deny = "deny.toml"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    fs::write(
+        temp_dir.path().join("deny.toml"),
+        "[bans]\nskip = [\"syn\"]\n\n[[bans.features]]\ncrate = \"serde\"\nallow = [\"default\"]\n",
+    )
+    .expect("Failed to write deny.toml");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'tokio'"), "{stderr}");
+    assert!(!stderr.contains("'serde'"), "{stderr}");
+
+    fs::remove_file(temp_dir.path().join("deny.toml")).expect("Failed to remove deny.toml");
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("deny.toml"));
+}
+
+#[test]
+fn test_json_format() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should hold a JSON document");
+    assert_eq!(document["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(document["findings"][0]["rule"], "ENDF001");
+    assert_eq!(document["findings"][0]["dependency"], "tokio");
+    assert_eq!(document["findings"][0]["severity"], "error");
+    assert_eq!(document["findings"][0]["location"], serde_json::json!({ "line": 6, "column": 1 }));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'tokio'"), "{stderr}");
+}
+
+#[test]
+fn test_apply_fixes_from_json_report() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    let report_path = temp_dir.path().join("report.json");
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .args(["--manifest-path", "Cargo.toml", "--format", "json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+    fs::write(&report_path, &output.stdout).expect("Failed to write the report");
+
+    let apply = || {
+        Command::new(get_binary_path())
+            .args(["ensure-no-default-features", "apply", "--from", "report.json"])
+            .current_dir(temp_dir.path())
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    // A manifest edited since the report was written is left alone
+    fs::write(&manifest_path, format!("{content}\n")).expect("Failed to edit Cargo.toml");
+    let output = apply();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("changed since report.json was written"));
+    assert_eq!(fs::read_to_string(&manifest_path).unwrap(), format!("{content}\n"));
+
+    fs::write(&manifest_path, content).expect("Failed to restore Cargo.toml");
+    let output = apply();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("'tokio': Set default-features = false"));
+    assert!(
+        fs::read_to_string(&manifest_path)
+            .unwrap()
+            .contains("tokio = { version = \"1.0\", default-features = false }")
+    );
+}
+
+#[test]
+fn test_sarif_format() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "sarif"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should hold a SARIF log");
+    assert_eq!(log["version"], "2.1.0");
+
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "ENDF001");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "Cargo.toml");
+    assert_eq!(location["region"]["startLine"], 5);
+    assert!(
+        result["fixes"][0]["artifactChanges"][0]["replacements"][0]["insertedContent"]["text"]
+            .as_str()
+            .is_some_and(|text| text.contains("default-features = false"))
+    );
+}
+
+#[test]
+fn test_junit_format() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+tokio = "1.0"
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "junit"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("<?xml"), "{stdout}");
+    assert!(
+        stdout.contains("<testsuite name=\"Cargo.toml\" tests=\"2\" failures=\"1\""),
+        "{stdout}"
+    );
+    assert!(stdout.contains("name=\"tokio\" file=\"Cargo.toml\" line=\"5\">"), "{stdout}");
+    assert!(stdout.contains("name=\"serde\" file=\"Cargo.toml\"/>"), "{stdout}");
+}
+
+#[test]
+fn test_teamcity_format() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "teamcity"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("##teamcity[inspectionType id='ENDF001'"), "{stdout}");
+    assert!(
+        stdout.contains("##teamcity[inspection typeId='ENDF001' message='|'tokio|'"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("file='Cargo.toml' line='5' SEVERITY='ERROR']"), "{stdout}");
+}
+
+#[test]
+fn test_markdown_format() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "markdown"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("**1 error(s)** and **0 warning(s)**"), "{stdout}");
+    assert!(stdout.contains("### `Cargo.toml`"), "{stdout}");
+    assert!(stdout.contains("| ENDF001 default-features | ❌ error | `tokio` | 5 |"), "{stdout}");
+}
+
+#[test]
+fn test_html_format() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "html"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("<!DOCTYPE html>"), "{stdout}");
+    assert!(stdout.contains("<tr><td>Cargo.toml</td><td>1</td><td>0</td></tr>"), "{stdout}");
+    assert!(stdout.contains("<td>tokio</td><td>5</td>"), "{stdout}");
+    assert!(stdout.contains("<section id=\"ENDF001\">"), "{stdout}");
+}
+
+#[test]
+fn test_csv_format() {
+    let content = r#"
+[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    fs::create_dir(temp_dir.path().join("app")).unwrap();
+    fs::write(
+        temp_dir.path().join("app/Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[dependencies]\nserde = { workspace = true, features = [\"derive\"] }\n",
+    )
+    .unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "csv"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "workspace,dependency,member,section,inherited,default_features,features\nCargo.toml,serde,app,[dependencies],true,false,derive\n"
+    );
+}
+
+#[test]
+fn test_dot_format() {
+    let content = r#"
+[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false, features = ["std"] }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    fs::create_dir(temp_dir.path().join("app")).unwrap();
+    fs::write(
+        temp_dir.path().join("app/Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[dependencies]\nserde = { workspace = true }\n",
+    )
+    .unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "dot"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("digraph "), "{stdout}");
+    assert!(stdout.contains("label=\"Cargo.toml\";"), "{stdout}");
+    assert!(
+        stdout.contains("\"0:member:app\" -> \"0:dependency:serde\" [label=\"[dependencies]\"];"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("\"0:dependency:serde\" -> \"0:feature:serde/std\";"), "{stdout}");
+}
+
+#[test]
+fn test_badge_format() {
+    let run = |content: &str| {
+        let temp_dir = create_test_manifest(content);
+        let output = Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .args(["--format", "badge"])
+            .output()
+            .expect("Failed to execute command");
+        let badge: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should hold the badge");
+        (output.status.code(), badge)
+    };
+
+    let (status, badge) = run("[workspace]\n\n[workspace.dependencies]\ntokio = \"1.0\"\n");
+    assert_eq!(status, Some(1));
+    assert_eq!(badge["schemaVersion"], 1);
+    assert_eq!(badge["message"], "1 violation");
+    assert_eq!(badge["color"], "red");
+
+    let (status, badge) = run("[workspace]\n\n[workspace.dependencies]\ntokio = { version = \"1.0\", default-features = false }\n");
+    assert_eq!(status, Some(0));
+    assert_eq!(badge["message"], "passing");
+}
+
+#[test]
+fn test_compact_format() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "compact"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Cargo.toml:5:1: error[ENDF001]: 'tokio': "), "{stdout}");
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+}
+
+#[test]
+fn test_quickfix_format() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "quickfix"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Cargo.toml:5:1: error: 'tokio': "), "{stdout}");
+    assert!(stdout.trim_end().ends_with(" [ENDF001]"), "{stdout}");
+}
+
+#[test]
+fn test_code_frames() {
+    let content = r#"
+[workspace]
+
+[workspace.dependencies]
+tokio = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "     --> Cargo.toml:5:1\n      |\n    5 | tokio = \"1.0\"\n      | ^^^^^^^^^^^^^\n      \
+             = help: set default-features = false: `tokio = { version = \"1.0\", default-features = false }`\n"
+        ),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_finding_locations() {
+    let content = r#"
+[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    fs::create_dir(temp_dir.path().join("app")).unwrap();
+    fs::write(
+        temp_dir.path().join("app/Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[dev-dependencies]\nserde = \"1.0\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .args(["--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should hold a JSON report");
+    let finding = report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|finding| finding["rule"] == "ENDF013")
+        .expect("the member's dependency should be reported");
+    assert!(finding["manifest_path"].as_str().unwrap().ends_with("Cargo.toml"));
+    assert_eq!(finding["location"], serde_json::json!({ "line": 5, "column": 1 }));
+}
+
+#[test]
+fn test_findings_grouped_by_manifest() {
+    let content = r#"
+[package]
+name = "root"
+
+[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+log = { version = "0.4", default-features = false }
+
+[dev-dependencies]
+serde = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    fs::create_dir(temp_dir.path().join("app")).unwrap();
+    fs::write(
+        temp_dir.path().join("app/Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[dependencies]\nlog = \"0.4\"\nserde = \"1.0\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let section = stderr
+        .split("❌ Found 3 member dependencies declared directly")
+        .nth(1)
+        .expect("the decentralized dependencies should be reported");
+    let section = section.split("\n\n❌").next().unwrap_or(section);
+    assert_eq!(section.matches("In Cargo.toml:").count(), 1, "{stderr}");
+    assert_eq!(section.matches("In app/Cargo.toml:").count(), 1, "{stderr}");
+}
+
+#[test]
+fn test_group_by() {
+    let content = r#"
+[package]
+name = "root"
+
+[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+log = { version = "0.4", default-features = false }
+
+[dependencies]
+serde = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    fs::create_dir(temp_dir.path().join("app")).unwrap();
+    fs::write(
+        temp_dir.path().join("app/Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[dependencies]\nlog = \"0.4\"\nserde = \"1.0\"\n",
+    )
+    .unwrap();
+
+    let run = |group_by: &str| {
+        let output = Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(temp_dir.path().join("Cargo.toml"))
+            .arg("--group-by")
+            .arg(group_by)
+            .arg("--sort")
+            .arg("count")
+            .current_dir(temp_dir.path())
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+
+    let by_file = run("file");
+    let root = by_file.find("❌ Cargo.toml (3 finding(s)):").expect(&by_file);
+    let app = by_file.find("❌ app/Cargo.toml (2 finding(s)):").expect(&by_file);
+    assert!(root < app, "the larger group should come first: {by_file}");
+    assert!(
+        by_file.contains("  - ⚠️ [ENDF029] 'log': isn't inherited by any member"),
+        "{by_file}"
+    );
+    assert!(!by_file.contains("Warning:"), "{by_file}");
+
+    let by_crate = run("crate");
+    let serde = by_crate.find("❌ 'serde' (3 finding(s)):").expect(&by_crate);
+    let log = by_crate.find("❌ 'log' (2 finding(s)):").expect(&by_crate);
+    assert!(serde < log, "the larger group should come first: {by_crate}");
+    assert!(by_crate.contains("  - ❌ [ENDF013] app/Cargo.toml: 'log'"), "{by_crate}");
+}
+
+#[test]
+fn test_violation_summary() {
+    let content = r#"
+[workspace]
+members = []
+
+[workspace.dependencies]
+tokio = "1.0"
+serde = { version = "1.0" }
+log = { version = "0.4" }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let summary = stderr
+        .find("📊 2 missing default-features, 1 simple version string across 1 manifest\n")
+        .expect(&stderr);
+    let listing = stderr.find("❌ Found 3 dependencies").expect(&stderr);
+    assert!(summary < listing, "the summary should come before the detailed listing: {stderr}");
+}
+
+#[test]
+fn test_top_offenders() {
+    let content = r#"
+[package]
+name = "root"
+
+[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+log = { version = "0.4", default-features = false }
+
+[dependencies]
+serde = "1.0"
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    fs::create_dir(temp_dir.path().join("app")).unwrap();
+    fs::write(
+        temp_dir.path().join("app/Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[dependencies]\nlog = \"0.4\"\nserde = \"1.0\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .arg("--top")
+        .arg("1")
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("🔝 Dependencies with the most errors:\n  - serde: 2\n\n"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("🔝 Manifests with the most errors:\n  - app (app/Cargo.toml): 2\n\n"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_identical_findings_collapsed() {
+    let content = r#"
+[workspace]
+members = ["a", "b", "c", "d", "e"]
+
+[workspace.dependencies]
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    for member in ["a", "b", "c", "d", "e"] {
+        fs::create_dir(temp_dir.path().join(member)).unwrap();
+        fs::write(
+            temp_dir.path().join(member).join("Cargo.toml"),
+            format!("[package]\nname = \"{member}\"\n\n[dependencies]\nserde = \"1.0\"\n"),
+        )
+        .unwrap();
+    }
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("❌ Found 5 member dependencies declared directly"), "{stderr}");
+    assert!(stderr.contains("In several members:\n"), "{stderr}");
+    assert_eq!(stderr.matches("  - 'serde' in [dependencies]").count(), 1, "{stderr}");
+    assert!(stderr.contains(" (seen in 5 members)\n"), "{stderr}");
+    assert!(
+        stderr.contains("    = seen in: a/Cargo.toml, b/Cargo.toml, c/Cargo.toml and 2 more\n"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_summary_file() {
+    let content = r#"
+[workspace.dependencies]
+tokio = "1.0"
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let summary_path = temp_dir.path().join("summary.json");
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .arg("--format")
+        .arg("json")
+        .arg("--summary-file")
+        .arg(&summary_path)
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let summary: serde_json::Value = serde_json::from_str(&fs::read_to_string(&summary_path).unwrap()).unwrap();
+    assert_eq!(summary["status"], 1);
+    assert_eq!(summary["findings"]["errors"], 1);
+    assert_eq!(summary["rules"]["ENDF001"], 1);
+    assert!(summary["duration_ms"].is_u64());
+    assert_eq!(summary["workspaces"].as_array().unwrap().len(), 1);
+    assert!(summary["version"].is_string());
+
+    // The full report still goes to stdout
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["findings"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_message_template() {
+    let content = r#"
+[workspace.dependencies]
+tokio = "1.0"
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .arg("--message-template")
+        .arg("{severity}|{rule}|{dep}|{file}|{line}")
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "error|ENDF001|tokio|Cargo.toml|3\n");
+
+    let output = Command::new(get_binary_path())
+        .arg("ensure-no-default-features")
+        .arg("--manifest-path")
+        .arg(temp_dir.path().join("Cargo.toml"))
+        .arg("--message-template")
+        .arg("{crate}")
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("unknown placeholder '{crate}'"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_unused_workspace_dependencies_follow_implicit_members() {
+    let content = r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+helper = { path = "helper" }
+
+[workspace]
+
+[workspace.dependencies]
+log = { version = "0.4", default-features = false }
+serde = { version = "1.0", default-features = false }
+"#;
+
+    let temp_dir = create_test_manifest(content);
+    let manifest_path = temp_dir.path().join("Cargo.toml");
+    fs::create_dir(temp_dir.path().join("helper")).unwrap();
+    fs::write(
+        temp_dir.path().join("helper/Cargo.toml"),
+        "[package]\nname = \"helper\"\nversion = \"0.1.0\"\n\n[dependencies]\nlog = { workspace = true }\n",
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(get_binary_path())
+            .arg("ensure-no-default-features")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run(&[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'serde': isn't inherited by any member"), "{stderr}");
+    assert!(
+        !stderr.contains("'log': isn't inherited"),
+        "helper is a member through its path: {stderr}"
+    );
+
+    let output = run(&["--fix", "-e", "serde,log"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("isn't inherited"), "{stderr}");
+    let fixed = fs::read_to_string(&manifest_path).unwrap();
+    assert!(fixed.contains("log = {") && fixed.contains("serde = {"), "{fixed}");
+
+    let _ = run(&["--fix"]);
+    let fixed = fs::read_to_string(&manifest_path).unwrap();
+    assert!(
+        fixed.contains("serde = {"),
+        "Unused entries are only removed with --remove-unused: {fixed}"
+    );
+
+    let output = run(&["--fix", "--remove-unused"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("removed 'serde' from [workspace.dependencies]"), "{stdout}");
+    let fixed = fs::read_to_string(&manifest_path).unwrap();
+    assert!(fixed.contains("log = {") && !fixed.contains("serde = {"), "{fixed}");
+}